@@ -31,7 +31,7 @@ define_str_consts! {
 ///
 /// Supports the standard checkbox states plus an indeterminate state
 /// commonly used for partial selections in hierarchical lists.
-#[derive(Clone, Copy, Display, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Default)]
 pub enum CheckStatus {
     /// Checkbox is selected/checked
     Checked,
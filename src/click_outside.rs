@@ -78,6 +78,18 @@ pub fn toggle_visibility(id: &str) -> Result<(), JsValue> {
     Ok(())
 }
 
+/// Returns `true` if the element with the given ID is displayed.
+///
+/// Returns `false` if the element cannot be found.
+pub(crate) fn is_visible(id: &str) -> bool {
+    get_window_and_document()
+        .and_then(|(window, document)| {
+            let element = get_html_element_by_id(&document, id)?;
+            is_element_hidden(&window, &element)
+        })
+        .is_ok_and(|hidden| !hidden)
+}
+
 /// Toggle the visibility of a complex select element by ID.
 ///
 /// # Errors
@@ -0,0 +1,379 @@
+//! Promise-style confirm, prompt and alert dialogs built on [`Modal`].
+//!
+//! Wrap the application in `DialogProvider` and get a [`Dialog`] handle with
+//! [`use_dialog`] in function components, or with
+//! `ctx.link().context::<Dialog>(Callback::noop())` in struct components.
+//! Each request mounts a `Modal` into a portal on `document.body` and
+//! resolves a callback or a future with the user's choice. Requests made
+//! while a dialog is open are shown one after another.
+//!
+//! [`Modal`]: crate::Modal
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context as TaskContext, Poll, Waker};
+
+use json_gettext::get_text;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, KeyboardEvent};
+use yew::{
+    Callback, Component, Context, ContextProvider, Html, InputEvent, Properties, create_portal,
+    hook, html, use_context,
+};
+
+use crate::{Modal, ModalAlign, ModalTextStyle, ModalType, Texts, Theme, language::Language};
+
+/// Validates the text entered in a prompt, returning an error message
+/// (a translation key or raw text) if it is invalid.
+pub type Validator = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+/// A text input shown in a prompt dialog.
+#[derive(Clone, Default)]
+pub struct PromptInput {
+    /// Text initially in the input
+    pub initial: String,
+    /// Placeholder shown while the input is empty
+    pub placeholder: String,
+    /// Validation run before the prompt can be confirmed
+    pub validator: Option<Validator>,
+}
+
+impl PartialEq for PromptInput {
+    fn eq(&self, other: &Self) -> bool {
+        self.initial == other.initial
+            && self.placeholder == other.placeholder
+            && match (&self.validator, &other.validator) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+/// The content and buttons of a dialog.
+#[derive(Clone, PartialEq)]
+pub struct Request {
+    pub kind: ModalType,
+    /// Translation key of the header, shown with `ModalType::TextOnly`
+    pub title: Option<&'static str>,
+    pub messages: Vec<(String, ModalTextStyle)>,
+    /// Translation keys of the buttons; the first one is highlighted
+    pub options: Vec<String>,
+    /// The text input of a prompt
+    pub input: Option<PromptInput>,
+}
+
+impl Request {
+    fn new(kind: ModalType, message: &str, options: &[&str]) -> Self {
+        Self {
+            kind,
+            title: None,
+            messages: vec![(message.to_string(), ModalTextStyle::RawNormal)],
+            options: options.iter().map(ToString::to_string).collect(),
+            input: None,
+        }
+    }
+
+    /// An alert with a single "OK" button.
+    #[must_use]
+    pub fn alert(message: &str) -> Self {
+        Self::new(ModalType::Alert, message, &["OK"])
+    }
+
+    /// A confirmation with "OK" and "Cancel" buttons.
+    #[must_use]
+    pub fn confirm(message: &str) -> Self {
+        Self::new(ModalType::Info, message, &["OK", "Cancel"])
+    }
+
+    /// A prompt with a text input and "OK" and "Cancel" buttons.
+    #[must_use]
+    pub fn prompt(message: &str, input: PromptInput) -> Self {
+        let mut request = Self::new(ModalType::TextOnly, message, &["OK", "Cancel"]);
+        request.input = Some(input);
+        request
+    }
+}
+
+/// The user's answer to a dialog.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The index of the clicked button, and the entered text for a prompt
+    Option(usize, Option<String>),
+    /// The dialog was closed or Escape was pressed
+    Cancel,
+}
+
+/// A future resolved with the [`Response`] to a dialog.
+pub struct ResponseFuture {
+    state: Rc<RefCell<(Option<Response>, Option<Waker>)>>,
+}
+
+impl Future for ResponseFuture {
+    type Output = Response;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+        if let Some(response) = state.0.take() {
+            Poll::Ready(response)
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// A handle for opening dialogs, provided by `DialogProvider`.
+#[derive(Clone, PartialEq)]
+pub struct Dialog {
+    open: Callback<(Request, Callback<Response>)>,
+}
+
+impl Dialog {
+    /// Opens a dialog and calls `on_response` with the answer.
+    pub fn open(&self, request: Request, on_response: Callback<Response>) {
+        self.open.emit((request, on_response));
+    }
+
+    /// Opens a dialog and returns a future resolved with the answer.
+    #[must_use]
+    pub fn ask(&self, request: Request) -> ResponseFuture {
+        let state: Rc<RefCell<(Option<Response>, Option<Waker>)>> =
+            Rc::new(RefCell::new((None, None)));
+        let on_response = {
+            let state = state.clone();
+            Callback::from(move |response| {
+                let mut state = state.borrow_mut();
+                state.0 = Some(response);
+                if let Some(waker) = state.1.take() {
+                    waker.wake();
+                }
+            })
+        };
+        self.open(request, on_response);
+        ResponseFuture { state }
+    }
+
+    /// Shows `message` and calls `on_close` when the dialog is dismissed.
+    pub fn alert(&self, message: &str, on_close: Callback<()>) {
+        self.open(
+            Request::alert(message),
+            Callback::from(move |_| on_close.emit(())),
+        );
+    }
+
+    /// Asks `message` and calls `on_result` with `true` if the user confirms.
+    pub fn confirm(&self, message: &str, on_result: Callback<bool>) {
+        self.open(
+            Request::confirm(message),
+            Callback::from(move |response| {
+                on_result.emit(matches!(response, Response::Option(0, _)));
+            }),
+        );
+    }
+
+    /// Asks `message` with a text input and calls `on_result` with the valid
+    /// text the user confirmed, or `None` if the prompt was cancelled.
+    pub fn prompt(&self, message: &str, input: PromptInput, on_result: Callback<Option<String>>) {
+        self.open(
+            Request::prompt(message, input),
+            Callback::from(move |response| {
+                on_result.emit(match response {
+                    Response::Option(0, text) => text,
+                    _ => None,
+                });
+            }),
+        );
+    }
+}
+
+/// Returns the [`Dialog`] handle of the enclosing `DialogProvider`.
+///
+/// # Panics
+///
+/// Panics if the component is not inside a `DialogProvider`.
+#[hook]
+#[must_use]
+pub fn use_dialog() -> Dialog {
+    use_context::<Dialog>().expect("use_dialog must be called inside a DialogProvider")
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Message {
+    Open(Request, Callback<Response>),
+    Respond(usize),
+    Cancel,
+    Input(String),
+    InputError,
+}
+
+/// Provides a [`Dialog`] handle to its children and renders open dialogs.
+#[derive(Clone, PartialEq)]
+pub struct Model {
+    dialog: Dialog,
+    queue: VecDeque<(Request, Callback<Response>)>,
+    input: String,
+    error: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub txt: Texts,
+    pub language: Language,
+    #[prop_or(None)]
+    pub theme: Option<Theme>,
+    #[prop_or_default]
+    pub children: Html,
+}
+
+impl Component for Model {
+    type Message = Message;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let open = ctx
+            .link()
+            .callback(|(request, on_response)| Message::Open(request, on_response));
+        Self {
+            dialog: Dialog { open },
+            queue: VecDeque::new(),
+            input: String::new(),
+            error: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Open(request, on_response) => {
+                if self.queue.is_empty() {
+                    self.reset_input(&request);
+                }
+                self.queue.push_back((request, on_response));
+            }
+            Message::Respond(index) => {
+                let Some((request, _)) = self.queue.front() else {
+                    return false;
+                };
+                let text = if let Some(input) = request.input.as_ref() {
+                    if index == 0
+                        && let Some(Err(error)) = input.validator.as_ref().map(|v| v(&self.input))
+                    {
+                        self.error = Some(error);
+                        return true;
+                    }
+                    (index == 0).then(|| self.input.clone())
+                } else {
+                    None
+                };
+                self.resolve(Response::Option(index, text));
+            }
+            Message::Cancel => self.resolve(Response::Cancel),
+            Message::Input(input) => {
+                self.input = input;
+                self.error = None;
+            }
+            Message::InputError => return false,
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let dialog = self.queue.front().map_or_else(
+            || html! {},
+            |(request, _)| {
+                let host = gloo_utils::document()
+                    .body()
+                    .expect("document should have a body");
+                create_portal(self.view_dialog(ctx, request), host.into())
+            },
+        );
+
+        html! {
+            <ContextProvider<Dialog> context={self.dialog.clone()}>
+                { ctx.props().children.clone() }
+                { dialog }
+            </ContextProvider<Dialog>>
+        }
+    }
+}
+
+impl Model {
+    fn reset_input(&mut self, request: &Request) {
+        self.input = request
+            .input
+            .as_ref()
+            .map(|input| input.initial.clone())
+            .unwrap_or_default();
+        self.error = None;
+    }
+
+    fn resolve(&mut self, response: Response) {
+        if let Some((_, on_response)) = self.queue.pop_front() {
+            on_response.emit(response);
+        }
+        if let Some((request, _)) = self.queue.front().cloned() {
+            self.reset_input(&request);
+        }
+    }
+
+    fn view_dialog(&self, ctx: &Context<Self>, request: &Request) -> Html {
+        let parent_messages = (0..request.options.len())
+            .map(Message::Respond)
+            .collect::<Vec<_>>();
+
+        html! {
+            <Modal<Self>
+                txt={ctx.props().txt.clone()}
+                language={ctx.props().language}
+                kind={request.kind}
+                align_button={ModalAlign::Row}
+                title_header={request.title}
+                title_messages={Rc::new(vec![request.messages.clone()])}
+                option_messages={Rc::new(request.options.clone())}
+                {parent_messages}
+                parent_cancel_message={Message::Cancel}
+                theme={ctx.props().theme}
+            >
+                { request.input.as_ref().map_or_else(|| html! {}, |input| self.view_input(ctx, input)) }
+            </Modal<Self>>
+        }
+    }
+
+    fn view_input(&self, ctx: &Context<Self>, input: &PromptInput) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            e.target()
+                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+                .map_or(Message::InputError, |input| Message::Input(input.value()))
+        });
+        let onkeydown = ctx.link().batch_callback(|e: KeyboardEvent| {
+            (e.key() == "Enter").then(|| {
+                e.prevent_default();
+                Message::Respond(0)
+            })
+        });
+        let error = self.error.as_ref().map(|error| {
+            get_text!(txt, ctx.props().language.tag(), error)
+                .map_or_else(|| error.clone(), |text| text.to_string())
+        });
+
+        html! {
+            <div class="dialog-prompt">
+                <input type="text"
+                    class={if error.is_some() { "frontary-input-text dialog-prompt-invalid" } else { "frontary-input-text" }}
+                    value={self.input.clone()}
+                    placeholder={input.placeholder.clone()}
+                    aria-invalid={error.is_some().to_string()}
+                    {oninput}
+                    {onkeydown}
+                />
+                if let Some(error) = error {
+                    <div class="dialog-prompt-error" role="alert">{ error }</div>
+                }
+            </div>
+        }
+    }
+}
@@ -1,6 +1,9 @@
 #![allow(clippy::module_name_repetitions)]
 mod component;
 mod config;
+mod encoding;
+mod evaluate;
+mod expression;
 mod host_network;
 mod item;
 mod recursive;
@@ -12,7 +15,14 @@ mod user_input_nic;
 mod user_input_select;
 
 use core::panic;
-use std::{cell::RefCell, collections::HashSet, fmt, net::IpAddr, rc::Rc, sync::LazyLock};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    net::IpAddr,
+    rc::Rc,
+    sync::LazyLock,
+};
 
 use bincode::Options;
 pub use component::{InputSecondId, Model};
@@ -23,6 +33,9 @@ pub use config::{
     SelectSingleConfig, TagConfig, TextConfig, Unsigned8Config, Unsigned16Config, Unsigned32Config,
     VecSelectConfig,
 };
+pub use encoding::{ENCODING_VERSION, EncodingError};
+pub use evaluate::MatchError;
+pub use expression::{ComparisonExpression, ParseComparisonError};
 pub use host_network::Kind as HostNetworkKind;
 pub use host_network::Model as HostNetworkHtml;
 pub use item::{
@@ -34,8 +47,11 @@ pub use item::{
 use num_bigint::BigUint;
 use num_traits::ToPrimitive;
 pub use recursive::invalid_password;
+pub(crate) use recursive::verify_cell;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumString};
 pub use tag::Model as Tag;
+pub(crate) use tag::view_color as view_tag_color;
 
 pub use self::user_input::view_asterisk;
 use crate::{CheckStatus, HostNetwork, HostNetworkGroupTrait, IpRange, parse_host_network};
@@ -105,6 +121,55 @@ mod tests {
             num_bigint::BigUint::from(34_345_028_u32)
         );
     }
+
+    #[test]
+    fn tag_reconcile() {
+        use std::collections::HashMap;
+
+        use super::{InputTagGroup, TagInfo, TagOperation};
+
+        let mut group = InputTagGroup {
+            old: ["a".to_string(), "b".to_string()].into_iter().collect(),
+            operations: vec![
+                TagOperation::Add(TagInfo::new("new".to_string())),
+                TagOperation::Delete("a".to_string()),
+                TagOperation::Edit("b".to_string(), TagInfo::new("B".to_string())),
+                TagOperation::Add(TagInfo::new("rejected".to_string())),
+            ],
+        };
+        let tags = HashMap::from([
+            ("b".to_string(), TagInfo::new("B".to_string())),
+            ("c".to_string(), TagInfo::new("new".to_string())),
+        ]);
+        group.reconcile(&tags);
+        assert_eq!(
+            group.operations,
+            vec![TagOperation::Add(TagInfo::new("rejected".to_string()))]
+        );
+        let mut old = group.old.iter().cloned().collect::<Vec<_>>();
+        old.sort_unstable();
+        assert_eq!(old, vec!["b".to_string(), "c".to_string()]);
+
+        group.discard(&TagOperation::Add(TagInfo::new("rejected".to_string())));
+        assert!(group.operations.is_empty());
+    }
+
+    #[test]
+    fn tag_css_color() {
+        use super::TagInfo;
+
+        let color = |color: &str| TagInfo {
+            color: Some(color.to_string()),
+            ..TagInfo::default()
+        };
+        assert_eq!(color("#2F80ED").css_color(), Some("#2F80ED"));
+        assert_eq!(color("#fff").css_color(), Some("#fff"));
+        assert_eq!(color("teal").css_color(), Some("teal"));
+        assert_eq!(color("#12345").css_color(), None);
+        assert_eq!(color("red; position: fixed").css_color(), None);
+        assert_eq!(color("url(x)").css_color(), None);
+        assert_eq!(TagInfo::default().css_color(), None);
+    }
 }
 
 fn cal_index(base_index: Option<&BigUint>, layer_index: usize) -> BigUint {
@@ -157,25 +222,106 @@ impl HostNetworkGroupTrait for InputHostNetworkGroup {
     }
 }
 
+/// The metadata of a tag.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TagInfo {
+    pub name: String,
+    /// A CSS color such as `#2F80ED`
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+impl TagInfo {
+    #[must_use]
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            color: None,
+            description: None,
+        }
+    }
+
+    /// Returns `color` if it is a hex color such as `#2F80ED` or a named
+    /// color such as `teal`, and `None` otherwise, so that other CSS cannot
+    /// be injected through it.
+    #[must_use]
+    pub fn css_color(&self) -> Option<&str> {
+        let color = self.color.as_deref()?.trim();
+        let valid = if let Some(hex) = color.strip_prefix('#') {
+            matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+        } else {
+            !color.is_empty() && color.chars().all(|c| c.is_ascii_alphabetic())
+        };
+        valid.then_some(color)
+    }
+}
+
+/// An operation on the tags that users request while editing an
+/// `InputItem::Tag` item. The parent applies it to the backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagOperation {
+    /// Creates a tag. The new tag is selected once it appears in
+    /// `TagConfig::tags`.
+    Add(TagInfo),
+    /// Changes the metadata of the tag with the key.
+    Edit(String, TagInfo),
+    /// Deletes the tag with the key.
+    Delete(String),
+}
+
 #[derive(Clone, PartialEq, Eq, Default)]
 pub struct InputTagGroup {
-    pub old: HashSet<String>,           // keys from review
-    pub new: Option<String>,            // the name of a tag input by users
-    pub edit: Option<(String, String)>, // (the key, a new name)
-    pub delete: Option<String>,         // the key that users want to be deleted
+    pub old: HashSet<String>, // keys from review
+    /// The operations not yet reflected in `TagConfig::tags`, in the order
+    /// users requested them
+    pub operations: Vec<TagOperation>,
 }
 
 impl InputTagGroup {
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.old.is_empty() && self.new.is_none() && self.edit.is_none() && self.delete.is_none()
+        self.old.is_empty() && self.operations.is_empty()
     }
 
     pub fn clear(&mut self) {
         self.old.clear();
-        self.new = None;
-        self.edit = None;
-        self.delete = None;
+        self.operations.clear();
+    }
+
+    /// Removes the operations reflected in `tags`, the updated tags from the
+    /// backend, and keeps the others pending. A new tag becomes selected, and
+    /// a deleted tag unselected.
+    pub fn reconcile(&mut self, tags: &HashMap<String, TagInfo>) {
+        let old = &mut self.old;
+        self.operations.retain(|operation| match operation {
+            TagOperation::Add(info) => {
+                if let Some(key) = tags
+                    .iter()
+                    .find_map(|(key, tag)| (tag.name == info.name).then_some(key))
+                {
+                    old.insert(key.clone());
+                    false
+                } else {
+                    true
+                }
+            }
+            TagOperation::Edit(key, info) => tags.get(key).is_some_and(|tag| tag != info),
+            TagOperation::Delete(key) => {
+                if tags.contains_key(key) {
+                    true
+                } else {
+                    old.remove(key);
+                    false
+                }
+            }
+        });
+    }
+
+    /// Discards a pending operation, e.g., after the backend rejects it.
+    pub fn discard(&mut self, operation: &TagOperation) {
+        if let Some(index) = self.operations.iter().position(|o| o == operation) {
+            self.operations.remove(index);
+        }
     }
 }
 
@@ -184,16 +330,26 @@ pub struct InputNic {
     pub name: String,
     pub interface: String,
     pub gateway: String,
+    /// The IPv6 address of the interface with its prefix length, used if
+    /// `NicConfig::ipv6` is set
+    pub ipv6: String,
+    /// Used if `NicConfig::mtu` is set
+    pub mtu: String,
+    /// Used if `NicConfig::vlan_id` is set
+    pub vlan_id: String,
 }
 
+/// The tag operations for the parent of a list. The list sends
+/// `MessageType::AddTag`, `EditTag` or `DeleteTag` to the parent after pushing
+/// an operation, and the parent is expected to take the operations out.
 #[derive(Clone, PartialEq, Eq, Default)]
 pub struct InputTag {
-    pub new: Option<String>,
-    pub edit: Option<(String, String)>,
-    pub delete: Option<String>,
+    pub operations: Vec<TagOperation>,
 }
 
-#[derive(Clone, Copy, Display, EnumIter, EnumString, Eq, PartialEq)]
+#[derive(
+    Clone, Copy, Debug, Deserialize, Display, EnumIter, EnumString, Eq, PartialEq, Serialize,
+)]
 #[strum(serialize_all = "PascalCase")]
 pub enum ValueKind {
     String,
@@ -205,18 +361,39 @@ pub enum ValueKind {
     Bool,
 }
 
-#[derive(Clone, PartialEq)]
+/// A value of a comparison.
+///
+/// With serde, a value is externally tagged by its kind, e.g.
+/// `{"Integer":5}`. In human-readable formats, an infinite or `NaN` float is
+/// written as the string `"inf"`, `"-inf"` or `"NaN"`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum Value {
     String(Option<String>),
     Integer(Option<i64>),
     UInteger(Option<u64>),
     Vector(Option<Vec<u8>>),
-    Float(Option<f64>),
+    Float(#[serde(with = "encoding::float")] Option<f64>),
     IpAddr(Option<IpAddr>),
     Bool(Option<bool>),
 }
 
 impl Value {
+    #[must_use]
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Self::String(_) => ValueKind::String,
+            Self::Integer(_) => ValueKind::Integer,
+            Self::UInteger(_) => ValueKind::UInteger,
+            Self::Vector(_) => ValueKind::Vector,
+            Self::Float(_) => ValueKind::Float,
+            Self::IpAddr(_) => ValueKind::IpAddr,
+            Self::Bool(_) => ValueKind::Bool,
+        }
+    }
+
+    /// Returns the bytes of the inner value without its kind, or `None` if
+    /// there is no inner value. [`Value::to_bytes`] returns a self-describing
+    /// encoding instead, and [`Value::from_legacy_bytes`] reads these bytes.
     #[must_use]
     pub fn serialize(&self) -> Option<Vec<u8>> {
         match self {
@@ -247,7 +424,7 @@ impl fmt::Display for Value {
     }
 }
 
-#[derive(Clone, Copy, Display, EnumString, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize)]
 pub enum ComparisonKind {
     #[strum(serialize = "x < a")]
     Less,
@@ -299,7 +476,7 @@ impl ComparisonKind {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum Comparison {
     Less(Value),
     Equal(Value),
@@ -447,15 +624,7 @@ impl Comparison {
             | Self::NotOpenRange(v, _)
             | Self::NotCloseRange(v, _)
             | Self::NotLeftOpenRange(v, _)
-            | Self::NotRightOpenRange(v, _) => match v {
-                Value::String(_) => ValueKind::String,
-                Value::Integer(_) => ValueKind::Integer,
-                Value::UInteger(_) => ValueKind::UInteger,
-                Value::Vector(_) => ValueKind::Vector,
-                Value::Float(_) => ValueKind::Float,
-                Value::IpAddr(_) => ValueKind::IpAddr,
-                Value::Bool(_) => ValueKind::Bool,
-            },
+            | Self::NotRightOpenRange(v, _) => v.kind(),
         }
     }
 
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::time::Duration;
 use std::{cell::RefCell, marker::PhantomData};
 
 use data_encoding::BASE64;
@@ -7,24 +8,29 @@ use gloo_file::{
     File,
     callbacks::{FileReader, read_as_bytes},
 };
+use gloo_timers::callback::Timeout;
 use json_gettext::get_text;
 use num_bigint::BigUint;
-use yew::{Component, Context, Html, Properties, html, virtual_dom::AttrValue};
+use yew::{Callback, Component, Context, Html, Properties, html, virtual_dom::AttrValue};
 
 use super::{
-    DomainNameItem, FileItem, Float64Item, HostNetworkGroupItem, InputConfig,
-    InputHostNetworkGroup, InputItem, InputTag, InputTagGroup, PasswordItem, PercentageItem,
-    SelectMultipleItem, SelectSingleItem, TagItem, TextItem, Unsigned8Item, Unsigned16Item,
-    Unsigned32Item, Value as ComparisonValue, cal_index, group_item_list_preset,
+    ComparisonExpression, DomainNameItem, FileItem, Float64Item, HostNetworkGroupItem, InputConfig,
+    InputHostNetworkGroup, InputItem, InputTag, InputTagGroup, POWER_OF_MAX_NUM_OF_LAYER,
+    ParseComparisonError, PasswordItem, PercentageItem, SelectMultipleItem, SelectSingleItem,
+    TagItem, TagOperation, TextItem, Unsigned8Item, Unsigned16Item, Unsigned32Item,
+    Value as ComparisonValue, cal_index, group_item_list_preset,
+    user_input_comparison::edit_comparison_expression,
 };
 use crate::{
-    InputNic, InvalidPasswordKind, MessageType, Rerender, Texts, Theme, ViewString,
-    language::Language,
+    InputNic, InvalidPasswordKind, MessageType, Notification, NotificationItem, Rerender, Texts,
+    Theme, ViewString,
+    language::{Language, translate},
     list::{Column, ListItem},
+    notification::{UNDO_ACTION, gen_undo_notification, timer_delay},
     sort_hosts, sort_networks, text,
 };
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub(super) enum InvalidMessage {
     InvalidInput,
     InvalidDomain,
@@ -35,6 +41,13 @@ pub(super) enum InvalidMessage {
     GatewayRequired,
     WrongInterface,
     WrongGateway,
+    DuplicateInterfaceName,
+    OverlappingInterface,
+    GatewayOutsideInterface,
+    GatewayReserved,
+    WrongIpv6Interface,
+    WrongMtu,
+    WrongVlanId,
 }
 
 #[derive(Clone, PartialEq)]
@@ -57,6 +70,9 @@ type CompValueBuf = HashMap<
     ),
 >;
 
+// the typed text of a comparison in text mode, and the error in it if any
+type CompTextBuf = HashMap<BigUint, (String, Option<ParseComparisonError>)>;
+
 type VecSelectBuf = HashMap<BigUint, Vec<Rc<RefCell<Option<HashSet<String>>>>>>;
 
 pub struct Model<T> {
@@ -68,13 +84,14 @@ pub struct Model<T> {
     pub(super) comparison_value_kind_buffer: HashMap<BigUint, Rc<RefCell<Option<HashSet<String>>>>>,
     pub(super) comparison_value_cmp_buffer: HashMap<BigUint, Rc<RefCell<Option<HashSet<String>>>>>,
     pub(super) comparison_value_buffer: CompValueBuf,
+    pub(super) comparison_text_buffer: CompTextBuf,
 
     pub(super) confirm_password: HashMap<BigUint, String>,
     pub(super) unique_msg: HashSet<BigUint>,
     pub(super) required_msg: HashSet<BigUint>,
 
     pub(super) verification: HashMap<BigUint, Verification>,
-    pub(super) verification_nic: HashMap<(BigUint, usize), Verification>, // 2nd usize: 0 -> name, 1 -> interface ip, 2 -> gateway ip
+    pub(super) verification_nic: HashMap<(BigUint, usize), Verification>, // 2nd usize: a field such as `user_input_nic::NAME`
     pub(super) verification_host_network: HashMap<BigUint, Option<bool>>, // None means no checking done yet, Some(true) valid Some(false) invalid
     pub(super) verify_host_network_group: bool,
 
@@ -85,10 +102,26 @@ pub struct Model<T> {
     file_reader: Option<FileReader>,
 
     pub(super) rerender_serial_host_network: u64,
+    pub(super) rerender_serial_tag: u64,
+
+    pending_tag_delete: Option<PendingTagDelete>,
+    pending_tag_delete_serial: usize,
+    undo_notices: Rc<RefCell<Vec<(usize, NotificationItem)>>>, // by serial of `PendingTagDelete`
+    undo_notices_serial: usize,                                // to rerender `undo_notices`
 
     phantom: PhantomData<T>,
 }
 
+/// The deletion of a tag, sent to the parent after `Props::undo_delete`
+/// unless users undo it
+#[derive(Clone, PartialEq)]
+struct PendingTagDelete {
+    serial: usize,
+    id: BigUint,
+    operation: TagOperation,
+    input_data: Rc<RefCell<InputItem>>,
+}
+
 impl<T> PartialEq for Model<T>
 where
     T: Clone + Component + PartialEq,
@@ -103,6 +136,7 @@ where
             && self.comparison_value_kind_buffer == other.comparison_value_kind_buffer
             && self.comparison_value_cmp_buffer == other.comparison_value_cmp_buffer
             && self.comparison_value_buffer == other.comparison_value_buffer
+            && self.comparison_text_buffer == other.comparison_text_buffer
             && self.confirm_password == other.confirm_password
             && self.unique_msg == other.unique_msg
             && self.required_msg == other.required_msg
@@ -115,6 +149,11 @@ where
             && self.file_name == other.file_name
             && self.file_content == other.file_content
             && self.rerender_serial_host_network == other.rerender_serial_host_network
+            && self.rerender_serial_tag == other.rerender_serial_tag
+            && self.pending_tag_delete == other.pending_tag_delete
+            && self.pending_tag_delete_serial == other.pending_tag_delete_serial
+            && self.undo_notices == other.undo_notices
+            && self.undo_notices_serial == other.undo_notices_serial
     }
 }
 
@@ -129,6 +168,7 @@ impl<T> Clone for Model<T> {
             comparison_value_kind_buffer: self.comparison_value_kind_buffer.clone(),
             comparison_value_cmp_buffer: self.comparison_value_cmp_buffer.clone(),
             comparison_value_buffer: self.comparison_value_buffer.clone(),
+            comparison_text_buffer: self.comparison_text_buffer.clone(),
             confirm_password: self.confirm_password.clone(),
             unique_msg: self.unique_msg.clone(),
             required_msg: self.required_msg.clone(),
@@ -142,6 +182,11 @@ impl<T> Clone for Model<T> {
             file_content: self.file_content.clone(),
             file_reader: None,
             rerender_serial_host_network: self.rerender_serial_host_network,
+            rerender_serial_tag: self.rerender_serial_tag,
+            pending_tag_delete: self.pending_tag_delete.clone(),
+            pending_tag_delete_serial: self.pending_tag_delete_serial,
+            undo_notices: self.undo_notices.clone(),
+            undo_notices_serial: self.undo_notices_serial,
             phantom: PhantomData,
         }
     }
@@ -193,6 +238,9 @@ pub enum Message {
         Rc<RefCell<InputItem>>,
     ),
     InputTagGroup(BigUint, Rc<RefCell<InputItem>>),
+    InputTagOperation(BigUint, TagOperation, Rc<RefCell<InputItem>>),
+    CommitTagDelete(usize), // serial of `PendingTagDelete`
+    UndoTagDelete(usize),   // serial of `PendingTagDelete`
     UserInputHostNetworkGroup(BigUint),
     WrongHostNetworkGroup(BigUint),
     RightHostNetworkGroup(BigUint, Rc<RefCell<InputItem>>),
@@ -200,6 +248,9 @@ pub enum Message {
     InputNicName(BigUint, usize, String, Rc<RefCell<InputItem>>),
     InputNicInterface(BigUint, usize, String, Rc<RefCell<InputItem>>),
     InputNicGateway(BigUint, usize, String, Rc<RefCell<InputItem>>),
+    InputNicIpv6(BigUint, usize, String, Rc<RefCell<InputItem>>),
+    InputNicMtu(BigUint, usize, String, Rc<RefCell<InputItem>>),
+    InputNicVlanId(BigUint, usize, String, Rc<RefCell<InputItem>>),
     InputNicAdd(BigUint, usize, Rc<RefCell<InputItem>>),
     InputNicDelete(BigUint, usize, Rc<RefCell<InputItem>>),
     InputGroupAdd(BigUint, Rc<RefCell<InputItem>>, Vec<Rc<InputConfig>>),
@@ -214,6 +265,13 @@ pub enum Message {
     InputComparisonComparisionKind(BigUint, Rc<RefCell<InputItem>>),
     InputComparisonValue(BigUint, usize, ComparisonValue, Rc<RefCell<InputItem>>),
     InvalidInputComparisonValue,
+    InputComparisonAddPredicate(BigUint, Vec<usize>, Rc<RefCell<InputItem>>),
+    InputComparisonAddGroup(BigUint, Vec<usize>, Rc<RefCell<InputItem>>),
+    InputComparisonRemove(BigUint, Vec<usize>, Rc<RefCell<InputItem>>),
+    InputComparisonToggleOperator(BigUint, Vec<usize>, Rc<RefCell<InputItem>>),
+    InputComparisonToggleNot(BigUint, Vec<usize>, Rc<RefCell<InputItem>>),
+    InputComparisonTextMode(BigUint, Rc<RefCell<InputItem>>),
+    InputComparisonText(BigUint, String, Rc<RefCell<InputItem>>),
     ChooseFile(BigUint, Vec<File>, Rc<RefCell<InputItem>>),
     FileLoaded(String, Vec<u8>),
     FailLoadFile,
@@ -252,6 +310,11 @@ impl Clone for Message {
             }
             Self::InputVecSelect(a, b, c) => Self::InputVecSelect(a.clone(), *b, c.clone()),
             Self::InputTagGroup(a, b) => Self::InputTagGroup(a.clone(), b.clone()),
+            Self::InputTagOperation(a, b, c) => {
+                Self::InputTagOperation(a.clone(), b.clone(), c.clone())
+            }
+            Self::CommitTagDelete(a) => Self::CommitTagDelete(*a),
+            Self::UndoTagDelete(a) => Self::UndoTagDelete(*a),
             Self::UserInputHostNetworkGroup(a) => Self::UserInputHostNetworkGroup(a.clone()),
             Self::RightHostNetworkGroup(a, b) => Self::RightHostNetworkGroup(a.clone(), b.clone()),
             Self::WrongHostNetworkGroup(a) => Self::WrongHostNetworkGroup(a.clone()),
@@ -265,6 +328,13 @@ impl Clone for Message {
             Self::InputNicGateway(a, b, c, d) => {
                 Self::InputNicGateway(a.clone(), *b, c.clone(), d.clone())
             }
+            Self::InputNicIpv6(a, b, c, d) => {
+                Self::InputNicIpv6(a.clone(), *b, c.clone(), d.clone())
+            }
+            Self::InputNicMtu(a, b, c, d) => Self::InputNicMtu(a.clone(), *b, c.clone(), d.clone()),
+            Self::InputNicVlanId(a, b, c, d) => {
+                Self::InputNicVlanId(a.clone(), *b, c.clone(), d.clone())
+            }
             Self::InputNicAdd(a, b, c) => Self::InputNicAdd(a.clone(), *b, c.clone()),
             Self::InputNicDelete(a, b, c) => Self::InputNicDelete(a.clone(), *b, c.clone()),
             Self::InputGroupAdd(a, b, c) => Self::InputGroupAdd(a.clone(), b.clone(), c.clone()),
@@ -281,6 +351,27 @@ impl Clone for Message {
                 Self::InputComparisonValue(a.clone(), *b, c.clone(), d.clone())
             }
             Self::InvalidInputComparisonValue => Self::InvalidInputComparisonValue,
+            Self::InputComparisonAddPredicate(a, b, c) => {
+                Self::InputComparisonAddPredicate(a.clone(), b.clone(), c.clone())
+            }
+            Self::InputComparisonAddGroup(a, b, c) => {
+                Self::InputComparisonAddGroup(a.clone(), b.clone(), c.clone())
+            }
+            Self::InputComparisonRemove(a, b, c) => {
+                Self::InputComparisonRemove(a.clone(), b.clone(), c.clone())
+            }
+            Self::InputComparisonToggleOperator(a, b, c) => {
+                Self::InputComparisonToggleOperator(a.clone(), b.clone(), c.clone())
+            }
+            Self::InputComparisonToggleNot(a, b, c) => {
+                Self::InputComparisonToggleNot(a.clone(), b.clone(), c.clone())
+            }
+            Self::InputComparisonTextMode(a, b) => {
+                Self::InputComparisonTextMode(a.clone(), b.clone())
+            }
+            Self::InputComparisonText(a, b, c) => {
+                Self::InputComparisonText(a.clone(), b.clone(), c.clone())
+            }
             Self::ChooseFile(a, _, c) => Self::ChooseFile(a.clone(), Vec::new(), c.clone()),
             Self::FileLoaded(a, b) => Self::FileLoaded(a.clone(), b.clone()),
             Self::FailLoadFile => Self::FailLoadFile,
@@ -290,6 +381,7 @@ impl Clone for Message {
 }
 
 impl PartialEq for Message {
+    #[allow(clippy::too_many_lines)]
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Escape, Self::Escape)
@@ -304,9 +396,15 @@ impl PartialEq for Message {
             | (Self::InputError, Self::InputError) => true,
             (Self::UserInputHostNetworkGroup(s1), Self::UserInputHostNetworkGroup(o1))
             | (Self::WrongHostNetworkGroup(s1), Self::WrongHostNetworkGroup(o1)) => s1 == o1,
+            (Self::InputTagOperation(s1, s2, s3), Self::InputTagOperation(o1, o2, o3)) => {
+                s1 == o1 && s2 == o2 && s3 == o3
+            }
+            (Self::CommitTagDelete(s1), Self::CommitTagDelete(o1))
+            | (Self::UndoTagDelete(s1), Self::UndoTagDelete(o1)) => s1 == o1,
             (Self::InputText(s1, s2, s3), Self::InputText(o1, o2, o3))
             | (Self::InputDomainName(s1, s2, s3), Self::InputDomainName(o1, o2, o3))
-            | (Self::InputPassword(s1, s2, s3), Self::InputPassword(o1, o2, o3)) => {
+            | (Self::InputPassword(s1, s2, s3), Self::InputPassword(o1, o2, o3))
+            | (Self::InputComparisonText(s1, s2, s3), Self::InputComparisonText(o1, o2, o3)) => {
                 s1 == o1 && s2 == o2 && s3 == o3
             }
             (Self::InputConfirmPassword(s1, s2), Self::InputConfirmPassword(o1, o2)) => {
@@ -339,8 +437,26 @@ impl PartialEq for Message {
                 Self::InputComparisonValue(s1, s2, s3, s4),
                 Self::InputComparisonValue(o1, o2, o3, o4),
             ) => s1 == o1 && s2 == o2 && s3 == o3 && s4 == o4,
+            (
+                Self::InputComparisonAddPredicate(s1, s2, s3),
+                Self::InputComparisonAddPredicate(o1, o2, o3),
+            )
+            | (
+                Self::InputComparisonAddGroup(s1, s2, s3),
+                Self::InputComparisonAddGroup(o1, o2, o3),
+            )
+            | (Self::InputComparisonRemove(s1, s2, s3), Self::InputComparisonRemove(o1, o2, o3))
+            | (
+                Self::InputComparisonToggleOperator(s1, s2, s3),
+                Self::InputComparisonToggleOperator(o1, o2, o3),
+            )
+            | (
+                Self::InputComparisonToggleNot(s1, s2, s3),
+                Self::InputComparisonToggleNot(o1, o2, o3),
+            ) => s1 == o1 && s2 == o2 && s3 == o3,
             (Self::InputHostNetworkGroup(s1, s2), Self::InputHostNetworkGroup(o1, o2))
-            | (Self::RightHostNetworkGroup(s1, s2), Self::RightHostNetworkGroup(o1, o2)) => {
+            | (Self::RightHostNetworkGroup(s1, s2), Self::RightHostNetworkGroup(o1, o2))
+            | (Self::InputComparisonTextMode(s1, s2), Self::InputComparisonTextMode(o1, o2)) => {
                 s1 == o1 && s2 == o2
             }
             (Self::InputSingleSelect(s1, s2, s3), Self::InputSingleSelect(o1, o2, o3))
@@ -354,7 +470,10 @@ impl PartialEq for Message {
             }
             (Self::InputNicName(s1, s2, s3, s4), Self::InputNicName(o1, o2, o3, o4))
             | (Self::InputNicGateway(s1, s2, s3, s4), Self::InputNicGateway(o1, o2, o3, o4))
-            | (Self::InputNicInterface(s1, s2, s3, s4), Self::InputNicInterface(o1, o2, o3, o4)) => {
+            | (Self::InputNicInterface(s1, s2, s3, s4), Self::InputNicInterface(o1, o2, o3, o4))
+            | (Self::InputNicIpv6(s1, s2, s3, s4), Self::InputNicIpv6(o1, o2, o3, o4))
+            | (Self::InputNicMtu(s1, s2, s3, s4), Self::InputNicMtu(o1, o2, o3, o4))
+            | (Self::InputNicVlanId(s1, s2, s3, s4), Self::InputNicVlanId(o1, o2, o3, o4)) => {
                 s1 == o1 && s2 == o2 && s3 == o3 && s4 == o4
             }
             (Self::FileLoaded(s1, s2), Self::FileLoaded(o1, o2)) => s1 == o1 && s2 == o2,
@@ -392,6 +511,16 @@ where
     pub input_data: Vec<Rc<RefCell<InputItem>>>,
     #[prop_or(None)]
     pub input_data_tag: Option<Rc<RefCell<InputTag>>>,
+    /// Called with each operation on the tags of an `InputItem::Tag` item as
+    /// users request it. The parent applies it to the backend and updates
+    /// `TagConfig::tags`, which settles the operation.
+    #[prop_or(None)]
+    pub on_tag_operation: Option<Callback<TagOperation>>,
+    /// The time during which users can undo deleting a tag. If set, a deleted
+    /// tag is hidden at once with an "Undo" notification, and its
+    /// `TagOperation::Delete` is sent only when the time passes.
+    #[prop_or(None)]
+    pub undo_delete: Option<Duration>,
 
     pub action_message: T::Message,
     pub escape_message: T::Message,
@@ -422,6 +551,7 @@ where
             comparison_value_kind_buffer: HashMap::new(),
             comparison_value_cmp_buffer: HashMap::new(),
             comparison_value_buffer: HashMap::new(),
+            comparison_text_buffer: HashMap::new(),
 
             confirm_password: HashMap::new(),
             unique_msg: HashSet::new(),
@@ -439,6 +569,12 @@ where
             file_reader: None,
 
             rerender_serial_host_network: 0,
+            rerender_serial_tag: 0,
+
+            pending_tag_delete: None,
+            pending_tag_delete_serial: 0,
+            undo_notices: Rc::new(RefCell::new(Vec::new())),
+            undo_notices_serial: 0,
 
             phantom: PhantomData,
         };
@@ -466,29 +602,9 @@ where
                         (&**input_conf, self.tag_buffer.get(&(BigUint::from(index))))
                 // HIGHLIGHT: Since Tag is always on the first layer, don't need to check recursively
                 {
-                    let reverse = config
-                        .name_map // This is the updated one.
-                        .iter()
-                        .map(|(k, v)| (v.clone(), k.clone()))
-                        .collect::<HashMap<String, String>>();
-
+                    // `config.tags` is the updated one.
                     if let Ok(mut buffer) = buffer.try_borrow_mut() {
-                        let deleted = buffer.delete.clone();
-                        if let Some(deleted) = deleted {
-                            buffer.old.remove(&deleted);
-                        }
-                        buffer.delete = None;
-                        *item = InputItem::Tag(TagItem::new((*buffer).clone()));
-                    }
-                    if let Ok(mut buffer) = buffer.try_borrow_mut() {
-                        if let Some(new) = buffer.new.as_ref()
-                            && let Some(key) = reverse.get(new)
-                        {
-                            buffer.old.insert(key.clone());
-                            buffer.new = None;
-                        }
-                        // no need to verify the value has been actually edited right.
-                        buffer.edit = None;
+                        buffer.reconcile(&config.tags);
                         *item = InputItem::Tag(TagItem::new((*buffer).clone()));
                     }
                 }
@@ -711,81 +827,61 @@ where
                 }
             }
             Message::InputTagGroup(id, input_data) => {
-                let (new, edit, delete) = if let Some(buffer) = self.tag_buffer.get(&id) {
-                    let (empty, new, edit, delete) = if let Ok(buffer) = buffer.try_borrow_mut() {
+                if let Some(buffer) = self.tag_buffer.get(&id) {
+                    let empty = if let Ok(buffer) = buffer.try_borrow() {
                         if let Ok(mut item) = input_data.try_borrow_mut() {
                             *item = InputItem::Tag(TagItem::new(buffer.clone()));
                         }
-                        (
-                            buffer.old.is_empty(),
-                            buffer.new.clone(),
-                            buffer.edit.clone(),
-                            buffer.delete.clone(),
-                        )
+                        buffer.old.is_empty()
                     } else {
-                        (false, None, None, None)
+                        false
                     };
                     self.remove_required_msg(&id, empty);
-
-                    (new, edit, delete)
-                } else {
-                    (None, None, None)
-                };
-
-                if let Some(new) = new
-                    && !new.is_empty()
+                }
+                return false; // HIGHLIGHT: DO NOT return true
+            }
+            Message::InputTagOperation(id, operation, input_data) => {
+                if matches!(operation, TagOperation::Delete(_))
+                    && let Some(time) = ctx.props().undo_delete
                 {
-                    if let Some(data_tag) = ctx.props().input_data_tag.as_ref()
-                        && let Ok(mut data_tag) = data_tag.try_borrow_mut()
-                    {
-                        data_tag.new = Some(new);
-                    }
-                    let msg = ctx
-                        .props()
-                        .extra_messages
-                        .as_ref()
-                        .and_then(|m| m.get(&MessageType::AddTag).cloned());
-                    if let (Some(parent), Some(msg)) = (ctx.link().get_parent(), msg) {
-                        parent.clone().downcast::<T>().send_message(msg);
-                    }
+                    self.soft_delete_tag(ctx, id, operation, input_data, time);
+                    return true;
                 }
-                if let Some(edit) = edit
-                    && !edit.0.is_empty()
-                    && !edit.1.is_empty()
+                Self::send_tag_operation(ctx, operation);
+                return false;
+            }
+            Message::CommitTagDelete(serial) => {
+                if self
+                    .pending_tag_delete
+                    .as_ref()
+                    .is_none_or(|pending| pending.serial != serial)
                 {
-                    if let Some(data_tag) = ctx.props().input_data_tag.as_ref()
-                        && let Ok(mut data_tag) = data_tag.try_borrow_mut()
-                    {
-                        data_tag.edit = Some(edit);
-                    }
-                    let msg = ctx
-                        .props()
-                        .extra_messages
-                        .as_ref()
-                        .and_then(|m| m.get(&MessageType::EditTag).cloned());
-                    if let (Some(parent), Some(msg)) = (ctx.link().get_parent(), msg) {
-                        parent.clone().downcast::<T>().send_message(msg);
-                    }
+                    return false;
                 }
-                if let Some(delete) = delete
-                    && !delete.is_empty()
+                self.commit_tag_delete(ctx);
+            }
+            Message::UndoTagDelete(serial) => {
+                if self
+                    .pending_tag_delete
+                    .as_ref()
+                    .is_none_or(|pending| pending.serial != serial)
                 {
-                    if let Some(data_tag) = ctx.props().input_data_tag.as_ref()
-                        && let Ok(mut data_tag) = data_tag.try_borrow_mut()
-                    {
-                        data_tag.delete = Some(delete);
-                    }
-                    let msg = ctx
-                        .props()
-                        .extra_messages
-                        .as_ref()
-                        .and_then(|m| m.get(&MessageType::DeleteTag).cloned());
-                    if let (Some(parent), Some(msg)) = (ctx.link().get_parent(), msg) {
-                        parent.clone().downcast::<T>().send_message(msg);
+                    return false;
+                }
+                let Some(pending) = self.pending_tag_delete.take() else {
+                    return false;
+                };
+                self.remove_undo_notice(serial);
+                if let Some(buffer) = self.tag_buffer.get(&pending.id)
+                    && let Ok(mut buffer) = buffer.try_borrow_mut()
+                {
+                    buffer.operations.retain(|o| *o != pending.operation);
+                    if let Ok(mut item) = pending.input_data.try_borrow_mut() {
+                        *item = InputItem::Tag(TagItem::new(buffer.clone()));
                     }
                 }
-
-                return false; // HIGHLIGHT: DO NOT return true
+                // `Tag` shows the tag again.
+                self.rerender_serial_tag += 1;
             }
             Message::ClickCheckbox(data_id, item) => {
                 self.radio_buffer_after_checkbox(&data_id, &item);
@@ -799,7 +895,7 @@ where
                     nic.name.clone_from(&name);
                 }
                 self.remove_required_msg(&data_id, name.is_empty());
-                self.remove_verification_nic(cal_index(Some(&data_id), nic_id));
+                self.remove_verification_nic(&data_id);
             }
             Message::InputNicInterface(data_id, nic_id, interface, input_data) => {
                 if let Ok(mut input_data) = input_data.try_borrow_mut()
@@ -809,7 +905,7 @@ where
                     nic.interface.clone_from(&interface);
                 }
                 self.remove_required_msg(&data_id, interface.is_empty());
-                self.remove_verification_nic(cal_index(Some(&data_id), nic_id));
+                self.remove_verification_nic(&data_id);
             }
             Message::InputNicGateway(data_id, nic_id, gateway, input_data) => {
                 if let Ok(mut input_data) = input_data.try_borrow_mut()
@@ -819,15 +915,26 @@ where
                     nic.gateway.clone_from(&gateway);
                 }
                 self.remove_required_msg(&data_id, gateway.is_empty());
-                self.remove_verification_nic(cal_index(Some(&data_id), nic_id));
+                self.remove_verification_nic(&data_id);
             }
-            Message::InputNicAdd(data_id, nic_id, input_data) => {
+            Message::InputNicIpv6(data_id, nic_id, ipv6, input_data) => {
+                self.input_nic_option(&data_id, nic_id, ipv6, &input_data, |nic| &mut nic.ipv6);
+            }
+            Message::InputNicMtu(data_id, nic_id, mtu, input_data) => {
+                self.input_nic_option(&data_id, nic_id, mtu, &input_data, |nic| &mut nic.mtu);
+            }
+            Message::InputNicVlanId(data_id, nic_id, vlan_id, input_data) => {
+                self.input_nic_option(&data_id, nic_id, vlan_id, &input_data, |nic| {
+                    &mut nic.vlan_id
+                });
+            }
+            Message::InputNicAdd(data_id, _, input_data) => {
                 if let Ok(mut input_data) = input_data.try_borrow_mut()
                     && let InputItem::Nic(data) = &mut *input_data
                 {
                     data.push(InputNic::default());
                 }
-                self.remove_verification_nic(cal_index(Some(&data_id), nic_id));
+                self.remove_verification_nic(&data_id);
             }
             Message::InputNicDelete(data_id, nic_id, input_data) => {
                 if let Ok(mut input_data) = input_data.try_borrow_mut()
@@ -838,7 +945,7 @@ where
                         data.push(InputNic::default());
                     }
                 }
-                self.remove_verification_nic(cal_index(Some(&data_id), nic_id));
+                self.remove_verification_nic(&data_id);
             }
             Message::InputGroupAdd(base_index, input_data, items_conf) => {
                 if let Ok(mut input_data) = input_data.try_borrow_mut()
@@ -953,6 +1060,35 @@ where
             Message::InputComparisonValue(data_id, value_index, value, input_data) => {
                 self.input_comparison_value(&data_id, value_index, &value, &input_data);
             }
+            Message::InputComparisonAddPredicate(data_id, path, input_data) => {
+                self.add_comparison_predicate(&data_id, &path, &input_data);
+            }
+            Message::InputComparisonAddGroup(_, path, input_data) => {
+                edit_comparison_expression(&input_data, |expression| {
+                    expression.push(&path, ComparisonExpression::And(Vec::new()));
+                });
+            }
+            Message::InputComparisonRemove(_, path, input_data) => {
+                edit_comparison_expression(&input_data, |expression| {
+                    expression.remove(&path);
+                });
+            }
+            Message::InputComparisonToggleOperator(_, path, input_data) => {
+                edit_comparison_expression(&input_data, |expression| {
+                    expression.toggle_operator(&path);
+                });
+            }
+            Message::InputComparisonToggleNot(_, path, input_data) => {
+                edit_comparison_expression(&input_data, |expression| {
+                    expression.toggle_not(&path);
+                });
+            }
+            Message::InputComparisonTextMode(data_id, input_data) => {
+                self.toggle_comparison_text_mode(&data_id, &input_data);
+            }
+            Message::InputComparisonText(data_id, text, input_data) => {
+                self.input_comparison_text(&data_id, text, &input_data);
+            }
             Message::ChooseFile(data_id, files, input_data) => {
                 for file in files {
                     let file_name = file.name();
@@ -994,6 +1130,11 @@ where
         true
     }
 
+    fn destroy(&mut self, ctx: &Context<Self>) {
+        // The deletion is not lost when users close the input before it is sent.
+        self.commit_tag_delete(ctx);
+    }
+
     fn view(&self, ctx: &Context<Self>) -> Html {
         let style = if cfg!(feature = "pumpkin") {
             format!("width: {}px;", ctx.props().width)
@@ -1031,6 +1172,7 @@ where
                         </div>
                     </div>
                 </div>
+                { self.view_undo_notices(ctx) }
             </div>
         }
     }
@@ -1070,7 +1212,7 @@ where
                             input_data, None, index, 0, config.theme)
                     }
                     InputConfig::Tag(config) => {
-                        self.view_tag_group(ctx, &config.ess, &config.name_map, input_data, None, index, config.theme)
+                        self.view_tag_group(ctx, &config.ess, &config.tags, input_data, None, index, config.theme)
                     }
                     InputConfig::VecSelect(config) => {
                         self.view_vec_select(ctx, &config.ess, &config.items_ess_list, config.last,
@@ -1098,13 +1240,13 @@ where
                             config.num_decimals, config.width, input_data, None, index, index == 0)
                     }
                     InputConfig::Nic(config) => {
-                        self.view_nic(ctx, &config.ess, input_data, None, index)
+                        self.view_nic(ctx, config, input_data, None, index)
                     }
                     InputConfig::File(config) => {
                         self.view_file(ctx, &config.ess, &config.allowed_extensions, input_data, None, index)
                     }
                     InputConfig::Comparison(config) => {
-                        self.view_comparison(ctx, &config.ess, input_data, None, index, false)
+                        self.view_comparison(ctx, &config.ess, config.compound, config.text, input_data, None, index, false)
                     }
                     InputConfig::Group(config) => {
                         self.view_group(ctx, &config.ess, config.all_in_one_row, &config.widths,
@@ -1165,10 +1307,31 @@ where
         }
     }
 
-    fn remove_verification_nic(&mut self, id: BigUint) {
-        self.verification_nic.remove(&(id.clone(), 0));
-        self.verification_nic.remove(&(id.clone(), 1));
-        self.verification_nic.remove(&(id, 2));
+    /// Removes the verification of every NIC of the item at `data_id`, since
+    /// a change in one NIC can resolve a conflict with another.
+    fn remove_verification_nic(&mut self, data_id: &BigUint) {
+        let nics = (0..2_usize.pow(POWER_OF_MAX_NUM_OF_LAYER))
+            .map(|nic_id| cal_index(Some(data_id), nic_id))
+            .collect::<HashSet<_>>();
+        self.verification_nic
+            .retain(|(id, _), _| !nics.contains(id));
+    }
+
+    fn input_nic_option(
+        &mut self,
+        data_id: &BigUint,
+        nic_id: usize,
+        value: String,
+        input_data: &Rc<RefCell<InputItem>>,
+        field: fn(&mut InputNic) -> &mut String,
+    ) {
+        if let Ok(mut input_data) = input_data.try_borrow_mut()
+            && let InputItem::Nic(data) = &mut *input_data
+            && let Some(nic) = data.get_mut(nic_id)
+        {
+            *field(nic) = value;
+        }
+        self.remove_verification_nic(data_id);
     }
 
     fn text_has_duplicate(ctx: &Context<Self>, index: usize, id: Option<&str>) -> bool {
@@ -1325,6 +1488,99 @@ where
         has_duplicate
     }
 
+    /// Hides the deleted tag and sends its deletion to the parent after
+    /// `time` unless users undo it. A deletion still waiting is sent at once.
+    fn soft_delete_tag(
+        &mut self,
+        ctx: &Context<Self>,
+        id: BigUint,
+        operation: TagOperation,
+        input_data: Rc<RefCell<InputItem>>,
+        time: Duration,
+    ) {
+        self.commit_tag_delete(ctx);
+        self.pending_tag_delete_serial += 1;
+        let serial = self.pending_tag_delete_serial;
+        self.pending_tag_delete = Some(PendingTagDelete {
+            serial,
+            id,
+            operation,
+            input_data,
+        });
+        if let Ok(mut notices) = self.undo_notices.try_borrow_mut() {
+            let message = translate(&ctx.props().txt.txt, ctx.props().language, "Tag deleted");
+            notices.push((serial, gen_undo_notification(message, time)));
+            self.undo_notices_serial += 1;
+        }
+        let link = ctx.link().clone();
+        Timeout::new(timer_delay(time), move || {
+            link.send_message(Message::CommitTagDelete(serial));
+        })
+        .forget();
+    }
+
+    /// Sends the deletion of a tag waiting for `Props::undo_delete` to the
+    /// parent.
+    fn commit_tag_delete(&mut self, ctx: &Context<Self>) {
+        let Some(pending) = self.pending_tag_delete.take() else {
+            return;
+        };
+        self.remove_undo_notice(pending.serial);
+        Self::send_tag_operation(ctx, pending.operation);
+    }
+
+    fn remove_undo_notice(&mut self, serial: usize) {
+        if let Ok(mut notices) = self.undo_notices.try_borrow_mut() {
+            notices.retain(|(s, _)| *s != serial);
+            self.undo_notices_serial += 1;
+        }
+    }
+
+    fn send_tag_operation(ctx: &Context<Self>, operation: TagOperation) {
+        let kind = match &operation {
+            TagOperation::Add(_) => MessageType::AddTag,
+            TagOperation::Edit(..) => MessageType::EditTag,
+            TagOperation::Delete(_) => MessageType::DeleteTag,
+        };
+        if let Some(data_tag) = ctx.props().input_data_tag.as_ref()
+            && let Ok(mut data_tag) = data_tag.try_borrow_mut()
+        {
+            data_tag.operations.push(operation.clone());
+        }
+        if let Some(on_tag_operation) = ctx.props().on_tag_operation.as_ref() {
+            on_tag_operation.emit(operation);
+        }
+        let msg = ctx
+            .props()
+            .extra_messages
+            .as_ref()
+            .and_then(|m| m.get(&kind).cloned());
+        if let (Some(parent), Some(msg)) = (ctx.link().get_parent(), msg) {
+            parent.clone().downcast::<T>().send_message(msg);
+        }
+    }
+
+    /// The notification of the deleted tag with the action to undo the
+    /// deletion
+    fn view_undo_notices(&self, ctx: &Context<Self>) -> Html {
+        if ctx.props().undo_delete.is_none() {
+            return html! {};
+        }
+        let on_action = ctx.link().batch_callback(|(serial, id): (usize, String)| {
+            (id == UNDO_ACTION).then_some(Message::UndoTagDelete(serial))
+        });
+        html! {
+            <Notification
+                txt={ctx.props().txt.clone()}
+                language={ctx.props().language}
+                list={Rc::clone(&self.undo_notices)}
+                serial={self.undo_notices_serial}
+                {on_action}
+                theme={ctx.props().theme}
+            />
+        }
+    }
+
     fn radio_buffer_after_checkbox(&mut self, data_id: &BigUint, item: &Rc<RefCell<InputItem>>) {
         if let Ok(item) = item.try_borrow()
             && let InputItem::Checkbox(cb) = &*item
@@ -3,7 +3,7 @@ use std::{
     rc::Rc,
 };
 
-use super::{HostNetworkKind, user_input_select::VecSelectListMap};
+use super::{HostNetworkKind, TagInfo, user_input_select::VecSelectListMap};
 use crate::{CheckStatus, Theme, ViewString};
 
 #[derive(Clone, PartialEq)]
@@ -120,8 +120,8 @@ pub struct SelectMultipleConfig {
 #[derive(Clone, PartialEq)]
 pub struct TagConfig {
     pub ess: Essential,
-    /// The map of tag's key and name.
-    pub name_map: HashMap<String, String>,
+    /// The map of tag's key and metadata.
+    pub tags: HashMap<String, TagInfo>,
     pub theme: Option<Theme>,
 }
 
@@ -174,6 +174,12 @@ pub struct PercentageConfig {
 #[derive(Clone, PartialEq)]
 pub struct NicConfig {
     pub ess: Essential,
+    /// Whether each NIC can have an IPv6 address, e.g. `2001:db8::1/64`
+    pub ipv6: bool,
+    /// Whether each NIC can have an MTU from 68 to 65535
+    pub mtu: bool,
+    /// Whether each NIC can have a VLAN ID from 1 to 4094
+    pub vlan_id: bool,
 }
 
 #[derive(Clone, PartialEq)]
@@ -190,6 +196,11 @@ pub struct FileConfig {
 pub struct ComparisonConfig {
     // TODO: #183
     pub ess: Essential,
+    /// Whether comparisons can be combined with AND, OR and NOT into a
+    /// `ComparisonExpression`
+    pub compound: bool,
+    /// Whether a comparison can also be typed as text, e.g. `10 < x <= 20`
+    pub text: bool,
 }
 
 #[derive(Clone, PartialEq)]
@@ -0,0 +1,372 @@
+//! Versioned encodings of [`Value`]s and [`Comparison`]s.
+//!
+//! The JSON encoding wraps the serde representation of a value in an object
+//! with its version, e.g. `{"version":1,"data":{"Less":{"Integer":5}}}`. The
+//! binary encoding is the version as one byte followed by the `bincode`
+//! encoding with `bincode::DefaultOptions`.
+//!
+//! Variants of `Value`, `ValueKind`, `Comparison` and `ComparisonKind` are
+//! encoded by name in JSON and by position in binary, so new variants must be
+//! added at the end, and a change to the existing ones requires a new version.
+
+use std::fmt;
+
+use bincode::Options;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{Comparison, Value, ValueKind};
+
+/// The version of the encodings written by `to_json` and `to_bytes`.
+pub const ENCODING_VERSION: u8 = 1;
+
+/// An error from encoding or decoding a value or a comparison.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodingError {
+    /// The data was encoded with a version this crate cannot read.
+    UnsupportedVersion(u64),
+    /// The data is malformed.
+    Invalid(String),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported encoding version: {version}")
+            }
+            Self::Invalid(reason) => write!(f, "Invalid encoding: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+#[derive(Deserialize, Serialize)]
+struct Envelope<T> {
+    version: u64,
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u64,
+}
+
+fn to_json<T: Serialize>(data: &T) -> Result<String, EncodingError> {
+    serde_json::to_string(&Envelope {
+        version: u64::from(ENCODING_VERSION),
+        data,
+    })
+    .map_err(|e| EncodingError::Invalid(e.to_string()))
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, EncodingError> {
+    let header: Header =
+        serde_json::from_str(json).map_err(|e| EncodingError::Invalid(e.to_string()))?;
+    if header.version != u64::from(ENCODING_VERSION) {
+        return Err(EncodingError::UnsupportedVersion(header.version));
+    }
+    serde_json::from_str::<Envelope<T>>(json)
+        .map(|envelope| envelope.data)
+        .map_err(|e| EncodingError::Invalid(e.to_string()))
+}
+
+fn to_bytes<T: Serialize>(data: &T) -> Result<Vec<u8>, EncodingError> {
+    let mut bytes = vec![ENCODING_VERSION];
+    bincode::DefaultOptions::new()
+        .serialize_into(&mut bytes, data)
+        .map_err(|e| EncodingError::Invalid(e.to_string()))?;
+    Ok(bytes)
+}
+
+fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, EncodingError> {
+    let Some((&version, data)) = bytes.split_first() else {
+        return Err(EncodingError::Invalid("empty data".to_string()));
+    };
+    if version != ENCODING_VERSION {
+        return Err(EncodingError::UnsupportedVersion(u64::from(version)));
+    }
+    bincode::DefaultOptions::new()
+        .deserialize(data)
+        .map_err(|e| EncodingError::Invalid(e.to_string()))
+}
+
+impl Value {
+    /// Encodes the value with its kind as versioned JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, EncodingError> {
+        to_json(self)
+    }
+
+    /// Decodes a value encoded by [`Value::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not supported or `json` is
+    /// malformed.
+    pub fn from_json(json: &str) -> Result<Self, EncodingError> {
+        from_json(json)
+    }
+
+    /// Encodes the value with its kind in the versioned binary form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EncodingError> {
+        to_bytes(self)
+    }
+
+    /// Decodes a value encoded by [`Value::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not supported or `bytes` are
+    /// malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EncodingError> {
+        from_bytes(bytes)
+    }
+
+    /// Decodes the bytes returned by [`Value::serialize`] as a value of
+    /// `kind`, to migrate data stored before the versioned encodings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` are not a value of `kind`.
+    pub fn from_legacy_bytes(kind: ValueKind, bytes: &[u8]) -> Result<Self, EncodingError> {
+        fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<Option<T>, EncodingError> {
+            bincode::DefaultOptions::new()
+                .deserialize(bytes)
+                .map(Some)
+                .map_err(|e| EncodingError::Invalid(e.to_string()))
+        }
+        Ok(match kind {
+            ValueKind::String => Self::String(decode(bytes)?),
+            ValueKind::Integer => Self::Integer(decode(bytes)?),
+            ValueKind::UInteger => Self::UInteger(decode(bytes)?),
+            ValueKind::Vector => Self::Vector(decode(bytes)?),
+            ValueKind::Float => Self::Float(decode(bytes)?),
+            ValueKind::IpAddr => Self::IpAddr(decode(bytes)?),
+            ValueKind::Bool => Self::Bool(decode(bytes)?),
+        })
+    }
+}
+
+impl Comparison {
+    /// Encodes the comparison as versioned JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, EncodingError> {
+        to_json(self)
+    }
+
+    /// Decodes a comparison encoded by [`Comparison::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not supported or `json` is
+    /// malformed.
+    pub fn from_json(json: &str) -> Result<Self, EncodingError> {
+        from_json(json)
+    }
+
+    /// Encodes the comparison in the versioned binary form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EncodingError> {
+        to_bytes(self)
+    }
+
+    /// Decodes a comparison encoded by [`Comparison::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not supported or `bytes` are
+    /// malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EncodingError> {
+        from_bytes(bytes)
+    }
+}
+
+/// Serializes an `f64` as a number, or in human-readable formats such as
+/// JSON, which have no representation for them, an infinity or `NaN` as a
+/// string.
+pub(super) mod float {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+    const NAN: &str = "NaN";
+    const INFINITY: &str = "inf";
+    const NEG_INFINITY: &str = "-inf";
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(f64),
+        Text(String),
+    }
+
+    #[allow(clippy::ref_option)] // required by `serde(with)`
+    pub(in crate::input) fn serialize<S: Serializer>(
+        value: &Option<f64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(v) if serializer.is_human_readable() && !v.is_finite() => {
+                let text = if v.is_nan() {
+                    NAN
+                } else if v.is_sign_positive() {
+                    INFINITY
+                } else {
+                    NEG_INFINITY
+                };
+                serializer.serialize_some(text)
+            }
+            _ => value.serialize(serializer),
+        }
+    }
+
+    pub(in crate::input) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<f64>, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Option::<f64>::deserialize(deserializer);
+        }
+        match Option::<Repr>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(Repr::Number(v)) => Ok(Some(v)),
+            Some(Repr::Text(text)) => match text.as_str() {
+                NAN => Ok(Some(f64::NAN)),
+                INFINITY => Ok(Some(f64::INFINITY)),
+                NEG_INFINITY => Ok(Some(f64::NEG_INFINITY)),
+                _ => Err(de::Error::invalid_value(
+                    de::Unexpected::Str(&text),
+                    &"a number, \"NaN\", \"inf\" or \"-inf\"",
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use bincode::Options;
+
+    use super::{ENCODING_VERSION, EncodingError};
+    use crate::{Comparison, ComparisonKind, ComparisonValue as V, ValueKind};
+
+    fn values() -> Vec<V> {
+        vec![
+            V::String(Some("a \"quoted\" string".to_string())),
+            V::String(None),
+            V::Integer(Some(i64::MIN)),
+            V::Integer(None),
+            V::UInteger(Some(u64::MAX)),
+            V::Vector(Some(vec![0, 1, 255])),
+            V::Vector(Some(Vec::new())),
+            V::Float(Some(-0.5)),
+            V::Float(Some(f64::INFINITY)),
+            V::Float(Some(f64::NEG_INFINITY)),
+            V::Float(None),
+            V::IpAddr(Some(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)))),
+            V::IpAddr(Some(IpAddr::V6(Ipv6Addr::LOCALHOST))),
+            V::Bool(Some(false)),
+            V::Bool(None),
+        ]
+    }
+
+    #[test]
+    fn round_trip() {
+        for value in values() {
+            let json = value.to_json().expect("serializable");
+            assert_eq!(V::from_json(&json), Ok(value.clone()), "{json}");
+            let bytes = value.to_bytes().expect("serializable");
+            assert_eq!(V::from_bytes(&bytes), Ok(value.clone()));
+
+            let second = Some(value.clone());
+            for kind in [
+                ComparisonKind::Less,
+                ComparisonKind::NotContain,
+                ComparisonKind::CloseRange,
+                ComparisonKind::NotRightOpenRange,
+            ] {
+                let comparison =
+                    Comparison::try_new(kind, value.clone(), second.clone()).expect("complete");
+                let json = comparison.to_json().expect("serializable");
+                assert_eq!(Comparison::from_json(&json), Ok(comparison.clone()));
+                let bytes = comparison.to_bytes().expect("serializable");
+                assert_eq!(Comparison::from_bytes(&bytes), Ok(comparison));
+            }
+        }
+
+        let nan = V::Float(Some(f64::NAN));
+        for decoded in [
+            V::from_json(&nan.to_json().expect("serializable")),
+            V::from_bytes(&nan.to_bytes().expect("serializable")),
+        ] {
+            assert!(matches!(decoded, Ok(V::Float(Some(v))) if v.is_nan()));
+        }
+    }
+
+    #[test]
+    fn stable_format() {
+        let comparison = Comparison::CloseRange(V::Integer(Some(1)), V::Float(Some(f64::NAN)));
+        assert_eq!(
+            comparison.to_json(),
+            Ok(
+                r#"{"version":1,"data":{"CloseRange":[{"Integer":1},{"Float":"NaN"}]}}"#
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            V::IpAddr(Some(IpAddr::V4(Ipv4Addr::LOCALHOST))).to_json(),
+            Ok(r#"{"version":1,"data":{"IpAddr":"127.0.0.1"}}"#.to_string())
+        );
+        assert_eq!(
+            serde_json::to_string(&ValueKind::UInteger).ok(),
+            Some(r#""UInteger""#.to_string())
+        );
+        assert_eq!(
+            Comparison::Less(V::Integer(Some(5))).to_bytes(),
+            Ok(vec![ENCODING_VERSION, 0, 1, 1, 10])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            V::from_json(r#"{"version":2,"data":{"Integer":1}}"#),
+            Err(EncodingError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            V::from_bytes(&[2, 1, 1, 2]),
+            Err(EncodingError::UnsupportedVersion(2))
+        );
+        assert!(V::from_json(r#"{"version":1,"data":{"Float":"infinity"}}"#).is_err());
+        assert!(V::from_json(r#"{"data":{"Integer":1}}"#).is_err());
+        assert!(V::from_bytes(&[]).is_err());
+        assert!(Comparison::from_bytes(&[ENCODING_VERSION, 0, 1, 1, 10, 0]).is_err());
+    }
+
+    #[test]
+    fn legacy_bytes() {
+        for value in values() {
+            let Some(bytes) = value.serialize() else {
+                continue;
+            };
+            assert_eq!(V::from_legacy_bytes(value.kind(), &bytes), Ok(value));
+        }
+        let bytes = bincode::DefaultOptions::new()
+            .serialize(&-1_i64)
+            .expect("serializable");
+        assert!(V::from_legacy_bytes(ValueKind::String, &bytes).is_err());
+    }
+}
@@ -0,0 +1,466 @@
+//! Evaluation of [`Comparison`]s and [`ComparisonExpression`]s against
+//! values.
+
+use std::{cmp::Ordering, fmt, net::IpAddr};
+
+use ipnet::IpNet;
+
+use super::{Comparison, ComparisonExpression, ComparisonKind, Value, ValueKind};
+
+/// An error from evaluating a comparison.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MatchError {
+    /// The tested value or an operand is `None`.
+    MissingValue,
+    /// The tested value is of a different kind than the operand.
+    KindMismatch {
+        expected: ValueKind,
+        found: ValueKind,
+    },
+    /// The comparison is not defined for the kind of value.
+    Unsupported {
+        comparison: ComparisonKind,
+        kind: ValueKind,
+    },
+    /// A network operand is not in CIDR notation.
+    InvalidNetwork(String),
+}
+
+impl fmt::Display for MatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingValue => write!(f, "Missing value"),
+            Self::KindMismatch { expected, found } => {
+                write!(f, "Expected a value of {expected}, found {found}")
+            }
+            Self::Unsupported { comparison, kind } => {
+                write!(f, "\"{comparison}\" is not supported for {kind}")
+            }
+            Self::InvalidNetwork(network) => write!(f, "Invalid network: {network}"),
+        }
+    }
+}
+
+impl std::error::Error for MatchError {}
+
+impl Comparison {
+    /// Returns whether `value`, the `x` of the comparison, satisfies it.
+    ///
+    /// * Operands must be of the same kind as `x`, except that an `IpAddr`
+    ///   can be tested with `Contain` against a `String` network in CIDR
+    ///   notation.
+    /// * Ordering follows the natural order of each kind: numerically for
+    ///   numbers, lexicographically for strings (by code point) and byte
+    ///   vectors, and `false < true` for booleans. An IPv4 and an IPv6
+    ///   address are not ordered, and neither is `NaN` with any float, so no
+    ///   ordering or equality comparison between them matches.
+    /// * `Contain` tests a substring for `String`, a contiguous byte sequence
+    ///   for `Vector`, and network membership for `IpAddr`; an `IpAddr`
+    ///   operand contains only itself. Other kinds do not support `Contain`.
+    /// * Every negated comparison (`NotEqual`, `NotContain`, `NotOpenRange`,
+    ///   ...) matches exactly when its positive counterpart does not, so
+    ///   `x != NaN` is always `true`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` or an operand is `None`, if their kinds
+    /// differ, if the comparison is not defined for the kind, or if a network
+    /// operand is invalid.
+    pub fn matches(&self, value: &Value) -> Result<bool, MatchError> {
+        let first = self.first();
+        let is = |expected: &[Ordering]| -> Result<bool, MatchError> {
+            Ok(order(value, &first)?.is_some_and(|o| expected.contains(&o)))
+        };
+        let within = |left_closed: bool, right_closed: bool| -> Result<bool, MatchError> {
+            let second = self.second().ok_or(MatchError::MissingValue)?;
+            let above = order(value, &first)?
+                .is_some_and(|o| o == Ordering::Greater || (left_closed && o == Ordering::Equal));
+            let below = order(value, &second)?
+                .is_some_and(|o| o == Ordering::Less || (right_closed && o == Ordering::Equal));
+            Ok(above && below)
+        };
+        match self.comparison_kind() {
+            ComparisonKind::Less => is(&[Ordering::Less]),
+            ComparisonKind::Equal => is(&[Ordering::Equal]),
+            ComparisonKind::Greater => is(&[Ordering::Greater]),
+            ComparisonKind::LessOrEqual => is(&[Ordering::Less, Ordering::Equal]),
+            ComparisonKind::GreaterOrEqual => is(&[Ordering::Greater, Ordering::Equal]),
+            ComparisonKind::NotEqual => is(&[Ordering::Equal]).map(|m| !m),
+            ComparisonKind::Contain => contains(value, &first),
+            ComparisonKind::NotContain => contains(value, &first).map(|m| !m),
+            ComparisonKind::OpenRange => within(false, false),
+            ComparisonKind::CloseRange => within(true, true),
+            ComparisonKind::LeftOpenRange => within(false, true),
+            ComparisonKind::RightOpenRange => within(true, false),
+            ComparisonKind::NotOpenRange => within(false, false).map(|m| !m),
+            ComparisonKind::NotCloseRange => within(true, true).map(|m| !m),
+            ComparisonKind::NotLeftOpenRange => within(false, true).map(|m| !m),
+            ComparisonKind::NotRightOpenRange => within(true, false).map(|m| !m),
+        }
+    }
+}
+
+impl ComparisonExpression {
+    /// Returns whether `value` satisfies the expression. An empty AND group
+    /// matches any value, and an empty OR group matches none.
+    ///
+    /// Evaluation stops as soon as the result is known, so errors in the
+    /// remaining conditions are not reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`Comparison::matches`].
+    pub fn matches(&self, value: &Value) -> Result<bool, MatchError> {
+        match self {
+            Self::Predicate(comparison) => comparison.matches(value),
+            Self::And(children) => {
+                for child in children {
+                    if !child.matches(value)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Self::Or(children) => {
+                for child in children {
+                    if child.matches(value)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Self::Not(child) => child.matches(value).map(|m| !m),
+        }
+    }
+}
+
+/// Orders `value` against `operand`, or returns `None` if they are not
+/// ordered.
+fn order(value: &Value, operand: &Value) -> Result<Option<Ordering>, MatchError> {
+    let ordering = match (value, operand) {
+        (Value::String(Some(x)), Value::String(Some(a))) => Some(x.cmp(a)),
+        (Value::Integer(Some(x)), Value::Integer(Some(a))) => Some(x.cmp(a)),
+        (Value::UInteger(Some(x)), Value::UInteger(Some(a))) => Some(x.cmp(a)),
+        (Value::Vector(Some(x)), Value::Vector(Some(a))) => Some(x.cmp(a)),
+        (Value::Float(Some(x)), Value::Float(Some(a))) => x.partial_cmp(a),
+        (Value::IpAddr(Some(x)), Value::IpAddr(Some(a))) => match (x, a) {
+            (IpAddr::V4(x), IpAddr::V4(a)) => Some(x.cmp(a)),
+            (IpAddr::V6(x), IpAddr::V6(a)) => Some(x.cmp(a)),
+            _ => None,
+        },
+        (Value::Bool(Some(x)), Value::Bool(Some(a))) => Some(x.cmp(a)),
+        _ => return Err(mismatch(value, operand)),
+    };
+    Ok(ordering)
+}
+
+fn contains(value: &Value, operand: &Value) -> Result<bool, MatchError> {
+    match (value, operand) {
+        (Value::String(Some(x)), Value::String(Some(a))) => Ok(x.contains(a.as_str())),
+        (Value::Vector(Some(x)), Value::Vector(Some(a))) => {
+            Ok(a.is_empty() || x.windows(a.len()).any(|window| window == a))
+        }
+        (Value::IpAddr(Some(x)), Value::String(Some(network))) => network
+            .trim()
+            .parse::<IpNet>()
+            .map(|network| network.contains(x))
+            .map_err(|_| MatchError::InvalidNetwork(network.clone())),
+        (Value::IpAddr(Some(x)), Value::IpAddr(Some(a))) => Ok(x == a),
+        (Value::IpAddr(_), Value::String(_)) => Err(MatchError::MissingValue),
+        _ if value.kind() == operand.kind()
+            && matches!(
+                value.kind(),
+                ValueKind::Integer | ValueKind::UInteger | ValueKind::Float | ValueKind::Bool
+            ) =>
+        {
+            Err(MatchError::Unsupported {
+                comparison: ComparisonKind::Contain,
+                kind: value.kind(),
+            })
+        }
+        _ => Err(mismatch(value, operand)),
+    }
+}
+
+fn mismatch(value: &Value, operand: &Value) -> MatchError {
+    if value.kind() == operand.kind() {
+        MatchError::MissingValue
+    } else {
+        MatchError::KindMismatch {
+            expected: operand.kind(),
+            found: value.kind(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use super::MatchError;
+    use crate::{
+        Comparison as C, ComparisonExpression, ComparisonKind, ComparisonValue as V, ValueKind,
+    };
+
+    fn int(v: i64) -> V {
+        V::Integer(Some(v))
+    }
+
+    fn float(v: f64) -> V {
+        V::Float(Some(v))
+    }
+
+    fn string(v: &str) -> V {
+        V::String(Some(v.to_string()))
+    }
+
+    fn ip(v: &str) -> V {
+        V::IpAddr(Some(v.parse::<IpAddr>().expect("valid address")))
+    }
+
+    /// Checks `comparison` against each value and its expected result.
+    fn check(comparison: &C, cases: &[(V, bool)]) {
+        for (value, expected) in cases {
+            assert_eq!(
+                comparison.matches(value),
+                Ok(*expected),
+                "{comparison} with x = {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn single_value_comparisons() {
+        let values = [int(4), int(5), int(6)];
+        let expected = [
+            (C::Less(int(5)), [true, false, false]),
+            (C::Equal(int(5)), [false, true, false]),
+            (C::Greater(int(5)), [false, false, true]),
+            (C::LessOrEqual(int(5)), [true, true, false]),
+            (C::GreaterOrEqual(int(5)), [false, true, true]),
+            (C::NotEqual(int(5)), [true, false, true]),
+        ];
+        for (comparison, results) in expected {
+            let cases = values.iter().cloned().zip(results).collect::<Vec<_>>();
+            check(&comparison, &cases);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let values = [int(0), int(1), int(5), int(10), int(11)];
+        let expected = [
+            (
+                C::OpenRange(int(1), int(10)),
+                [false, false, true, false, false],
+            ),
+            (
+                C::CloseRange(int(1), int(10)),
+                [false, true, true, true, false],
+            ),
+            (
+                C::LeftOpenRange(int(1), int(10)),
+                [false, false, true, true, false],
+            ),
+            (
+                C::RightOpenRange(int(1), int(10)),
+                [false, true, true, false, false],
+            ),
+            (
+                C::NotOpenRange(int(1), int(10)),
+                [true, true, false, true, true],
+            ),
+            (
+                C::NotCloseRange(int(1), int(10)),
+                [true, false, false, false, true],
+            ),
+            (
+                C::NotLeftOpenRange(int(1), int(10)),
+                [true, true, false, false, true],
+            ),
+            (
+                C::NotRightOpenRange(int(1), int(10)),
+                [true, false, false, true, true],
+            ),
+        ];
+        for (comparison, results) in expected {
+            let cases = values.iter().cloned().zip(results).collect::<Vec<_>>();
+            check(&comparison, &cases);
+        }
+        // an empty range
+        check(&C::CloseRange(int(10), int(1)), &[(int(5), false)]);
+    }
+
+    #[test]
+    fn kinds() {
+        check(
+            &C::Less(V::UInteger(Some(10))),
+            &[(V::UInteger(Some(9)), true), (V::UInteger(Some(10)), false)],
+        );
+        check(
+            &C::Less(string("b")),
+            &[(string("a"), true), (string("ba"), false)],
+        );
+        check(
+            &C::Greater(V::Vector(Some(vec![1, 2]))),
+            &[
+                (V::Vector(Some(vec![1, 2, 0])), true),
+                (V::Vector(Some(vec![1, 1, 9])), false),
+            ],
+        );
+        check(
+            &C::CloseRange(ip("10.0.0.1"), ip("10.0.0.9")),
+            &[
+                (ip("10.0.0.5"), true),
+                (ip("10.0.0.10"), false),
+                (ip("::1"), false),
+            ],
+        );
+        check(
+            &C::NotEqual(ip("10.0.0.1")),
+            &[(ip("::ffff:10.0.0.1"), true), (ip("10.0.0.1"), false)],
+        );
+        check(
+            &C::Greater(V::Bool(Some(false))),
+            &[(V::Bool(Some(true)), true), (V::Bool(Some(false)), false)],
+        );
+    }
+
+    #[test]
+    fn nan() {
+        let nan = float(f64::NAN);
+        for kind in [
+            ComparisonKind::Less,
+            ComparisonKind::Equal,
+            ComparisonKind::Greater,
+            ComparisonKind::LessOrEqual,
+            ComparisonKind::GreaterOrEqual,
+        ] {
+            let comparison = C::try_new(kind, float(1.0), None).expect("single value");
+            check(&comparison, &[(nan.clone(), false)]);
+            let comparison = C::try_new(kind, nan.clone(), None).expect("single value");
+            check(&comparison, &[(float(1.0), false), (nan.clone(), false)]);
+        }
+        check(&C::NotEqual(nan.clone()), &[(nan.clone(), true)]);
+        check(
+            &C::CloseRange(float(0.0), float(1.0)),
+            &[(nan.clone(), false)],
+        );
+        check(&C::NotCloseRange(float(0.0), float(1.0)), &[(nan, true)]);
+        check(
+            &C::LessOrEqual(float(f64::INFINITY)),
+            &[(float(f64::MAX), true), (float(f64::INFINITY), true)],
+        );
+    }
+
+    #[test]
+    fn contain() {
+        check(
+            &C::Contain(string("tmp")),
+            &[(string("/tmp/a"), true), (string("/TMP"), false)],
+        );
+        check(&C::Contain(string("")), &[(string(""), true)]);
+        check(
+            &C::NotContain(string("tmp")),
+            &[(string("/tmp/a"), false), (string("/var"), true)],
+        );
+        check(
+            &C::Contain(V::Vector(Some(vec![2, 3]))),
+            &[
+                (V::Vector(Some(vec![1, 2, 3])), true),
+                (V::Vector(Some(vec![2, 1, 3])), false),
+                (V::Vector(Some(vec![2])), false),
+            ],
+        );
+        check(
+            &C::Contain(V::Vector(Some(Vec::new()))),
+            &[(V::Vector(Some(Vec::new())), true)],
+        );
+        check(
+            &C::Contain(string("10.0.0.0/8")),
+            &[
+                (ip("10.1.2.3"), true),
+                (ip("11.0.0.1"), false),
+                (ip("::1"), false),
+            ],
+        );
+        check(
+            &C::NotContain(string("2001:db8::/32")),
+            &[(ip("2001:db8::1"), false), (ip("10.0.0.1"), true)],
+        );
+        check(
+            &C::Contain(ip("10.0.0.1")),
+            &[(ip("10.0.0.1"), true), (ip("10.0.0.2"), false)],
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(C::Equal(int(1)).matches(&int(1)), Ok(true));
+        assert_eq!(
+            C::Equal(V::Integer(None)).matches(&int(1)),
+            Err(MatchError::MissingValue)
+        );
+        assert_eq!(
+            C::Equal(int(1)).matches(&V::Integer(None)),
+            Err(MatchError::MissingValue)
+        );
+        assert_eq!(
+            C::Equal(int(1)).matches(&float(1.0)),
+            Err(MatchError::KindMismatch {
+                expected: ValueKind::Integer,
+                found: ValueKind::Float
+            })
+        );
+        assert_eq!(
+            C::Contain(int(1)).matches(&int(1)),
+            Err(MatchError::Unsupported {
+                comparison: ComparisonKind::Contain,
+                kind: ValueKind::Integer
+            })
+        );
+        assert_eq!(
+            C::NotContain(V::Bool(Some(true))).matches(&V::Bool(Some(true))),
+            Err(MatchError::Unsupported {
+                comparison: ComparisonKind::Contain,
+                kind: ValueKind::Bool
+            })
+        );
+        assert_eq!(
+            C::Contain(string("10.0.0.0/33")).matches(&ip("10.0.0.1")),
+            Err(MatchError::InvalidNetwork("10.0.0.0/33".to_string()))
+        );
+        assert!(matches!(
+            C::Contain(string("tmp")).matches(&ip("10.0.0.1")),
+            Err(MatchError::InvalidNetwork(_))
+        ));
+        assert!(matches!(
+            C::Less(string("a")).matches(&ip("10.0.0.1")),
+            Err(MatchError::KindMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn expressions() {
+        let expression: ComparisonExpression = "(x > 10 AND x < 100) OR x = 0"
+            .parse()
+            .expect("valid expression");
+        assert_eq!(expression.matches(&int(50)), Ok(true));
+        assert_eq!(expression.matches(&int(0)), Ok(true));
+        assert_eq!(expression.matches(&int(100)), Ok(false));
+
+        let expression: ComparisonExpression =
+            "NOT x Contains \"tmp\"".parse().expect("valid expression");
+        assert_eq!(expression.matches(&string("/var")), Ok(true));
+        assert_eq!(expression.matches(&string("/tmp")), Ok(false));
+
+        assert_eq!(
+            ComparisonExpression::And(Vec::new()).matches(&int(1)),
+            Ok(true)
+        );
+        assert_eq!(
+            ComparisonExpression::Or(Vec::new()).matches(&int(1)),
+            Ok(false)
+        );
+    }
+}
@@ -0,0 +1,837 @@
+//! Boolean combinations of [`Comparison`]s and their text syntax.
+//!
+//! In the text syntax, conditions are joined with `AND` and `OR` and negated
+//! with `NOT`. `AND` binds tighter than `OR`, and parentheses group
+//! conditions, e.g. `(10 < x < 100 AND x != 50) OR NOT x = 0`.
+//!
+//! Values are written so that their kinds can be told apart: strings are
+//! quoted (`"tmp"`), unsigned integers have a `u` suffix (`10u`), floats
+//! have a decimal point or an exponent (`1.0`, `NaN`), byte vectors are
+//! bracketed (`[1, 2]`), and IP addresses and booleans are written as is.
+//! Any other unquoted word is a string, so `x Contains tmp` is accepted, but
+//! a string with spaces or operators must be quoted. `<=`, `>=` and `!=` may
+//! be used for `≤`, `≥` and `≠`.
+
+use std::{fmt, net::IpAddr, str::FromStr};
+
+use super::{Comparison, ComparisonKind, Value, ValueKind};
+
+/// A tree of comparisons combined with AND, OR and NOT.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ComparisonExpression {
+    Predicate(Comparison),
+    And(Vec<ComparisonExpression>),
+    Or(Vec<ComparisonExpression>),
+    Not(Box<ComparisonExpression>),
+}
+
+impl ComparisonExpression {
+    /// Returns the sub-expressions of an AND, OR or NOT node.
+    #[must_use]
+    pub fn children(&self) -> &[Self] {
+        match self {
+            Self::Predicate(_) => &[],
+            Self::And(children) | Self::Or(children) => children,
+            Self::Not(child) => std::slice::from_ref(child),
+        }
+    }
+
+    /// Returns the node at `path`, a list of child indices from the root.
+    #[must_use]
+    pub fn get(&self, path: &[usize]) -> Option<&Self> {
+        path.iter()
+            .try_fold(self, |node, &index| node.children().get(index))
+    }
+
+    /// Returns the node at `path` mutably.
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut Self> {
+        let mut node = self;
+        for &index in path {
+            node = match node {
+                Self::And(children) | Self::Or(children) => children.get_mut(index)?,
+                Self::Not(child) if index == 0 => child,
+                Self::Predicate(_) | Self::Not(_) => return None,
+            };
+        }
+        Some(node)
+    }
+
+    /// Appends `child` to the AND or OR group at `path`. Returns `false` if
+    /// there is no such group.
+    pub fn push(&mut self, path: &[usize], child: Self) -> bool {
+        match self.get_mut(path) {
+            Some(Self::And(children) | Self::Or(children)) => {
+                children.push(child);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes and returns the node at `path`. The root cannot be removed,
+    /// and removing the only child of a NOT removes the NOT.
+    pub fn remove(&mut self, path: &[usize]) -> Option<Self> {
+        let (&last, parent) = path.split_last()?;
+        if matches!(self.get(parent)?, Self::Not(_)) {
+            return match self.remove(parent)? {
+                Self::Not(child) => Some(*child),
+                _ => None,
+            };
+        }
+        match self.get_mut(parent)? {
+            Self::And(children) | Self::Or(children) if last < children.len() => {
+                Some(children.remove(last))
+            }
+            _ => None,
+        }
+    }
+
+    /// Switches the group at `path` between AND and OR.
+    pub fn toggle_operator(&mut self, path: &[usize]) {
+        if let Some(node) = self.get_mut(path) {
+            *node = match std::mem::replace(node, Self::And(Vec::new())) {
+                Self::And(children) => Self::Or(children),
+                Self::Or(children) => Self::And(children),
+                other => other,
+            };
+        }
+    }
+
+    /// Negates the node at `path`, or removes its negation if it is a NOT.
+    pub fn toggle_not(&mut self, path: &[usize]) {
+        if let Some(node) = self.get_mut(path) {
+            *node = match std::mem::replace(node, Self::And(Vec::new())) {
+                Self::Not(child) => *child,
+                other => Self::Not(Box::new(other)),
+            };
+        }
+    }
+
+    /// Returns an equivalent expression without redundant groups: nested
+    /// groups with the same operator are merged, groups with one condition
+    /// are replaced with it, and groups without conditions are dropped.
+    ///
+    /// Returns `None` if the expression has no conditions at all.
+    #[must_use]
+    pub fn normalize(self) -> Option<Self> {
+        match self {
+            Self::Predicate(_) => Some(self),
+            Self::Not(child) => child.normalize().map(|child| Self::Not(Box::new(child))),
+            Self::And(children) => Self::normalize_group(children, true),
+            Self::Or(children) => Self::normalize_group(children, false),
+        }
+    }
+
+    fn normalize_group(children: Vec<Self>, is_and: bool) -> Option<Self> {
+        let mut flat = Vec::new();
+        for child in children.into_iter().filter_map(Self::normalize) {
+            match child {
+                Self::And(nested) if is_and => flat.extend(nested),
+                Self::Or(nested) if !is_and => flat.extend(nested),
+                child => flat.push(child),
+            }
+        }
+        match flat.len() {
+            0 => None,
+            1 => flat.pop(),
+            _ if is_and => Some(Self::And(flat)),
+            _ => Some(Self::Or(flat)),
+        }
+    }
+
+    // Skips groups with a single child, which are written without parentheses.
+    fn unwrap_single(&self) -> &Self {
+        match self {
+            Self::And(children) | Self::Or(children) if children.len() == 1 => {
+                children[0].unwrap_single()
+            }
+            _ => self,
+        }
+    }
+
+    fn fmt_child(&self, f: &mut fmt::Formatter<'_>, parenthesize_or: bool) -> fmt::Result {
+        match self.unwrap_single() {
+            child @ Self::Or(_) if parenthesize_or => write!(f, "({child})"),
+            child => write!(f, "{child}"),
+        }
+    }
+}
+
+impl From<Comparison> for ComparisonExpression {
+    fn from(comparison: Comparison) -> Self {
+        Self::Predicate(comparison)
+    }
+}
+
+impl fmt::Display for ComparisonExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.unwrap_single() {
+            Self::Predicate(comparison) => fmt_predicate(comparison, f),
+            Self::And(children) | Self::Or(children) if children.is_empty() => write!(f, "()"),
+            node @ (Self::And(children) | Self::Or(children)) => {
+                let (separator, parenthesize_or) = if matches!(node, Self::And(_)) {
+                    (" AND ", true)
+                } else {
+                    (" OR ", false)
+                };
+                for (index, child) in children.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(separator)?;
+                    }
+                    child.fmt_child(f, parenthesize_or)?;
+                }
+                Ok(())
+            }
+            Self::Not(child) => {
+                f.write_str("NOT ")?;
+                match child.unwrap_single() {
+                    group @ (Self::And(_) | Self::Or(_)) => write!(f, "({group})"),
+                    child => write!(f, "{child}"),
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for ComparisonExpression {
+    type Err = ParseComparisonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s, None)?;
+        let expression = parser.or()?;
+        parser.end()?;
+        Ok(expression)
+    }
+}
+
+/// Parses a single comparison, such as `10 < x <= 20` or
+/// `x != 192.168.0.1`, inferring the kind of its values.
+impl FromStr for Comparison {
+    type Err = ParseComparisonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, None)
+    }
+}
+
+impl Comparison {
+    /// Parses a single comparison whose values are of `kind`, e.g. `x = 5`
+    /// as an unsigned integer or `x Contains 10` as a string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `s` is not a comparison or a value is not of
+    /// `kind`.
+    pub fn parse_with_kind(s: &str, kind: ValueKind) -> Result<Self, ParseComparisonError> {
+        Self::parse(s, Some(kind))
+    }
+
+    fn parse(s: &str, kind: Option<ValueKind>) -> Result<Self, ParseComparisonError> {
+        let mut parser = Parser::new(s, kind)?;
+        let comparison = parser.predicate()?;
+        parser.end()?;
+        Ok(comparison)
+    }
+}
+
+/// An error from parsing the text of a comparison.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseComparisonError {
+    position: usize,
+    reason: &'static str,
+}
+
+impl ParseComparisonError {
+    pub(crate) fn new(position: usize, reason: &'static str) -> Self {
+        Self { position, reason }
+    }
+
+    /// Returns the position of the error in characters from the start of the
+    /// text.
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns what is wrong at [`position`](Self::position).
+    #[must_use]
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl fmt::Display for ParseComparisonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.reason, self.position)
+    }
+}
+
+impl std::error::Error for ParseComparisonError {}
+
+fn fmt_predicate(comparison: &Comparison, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let first = Literal(comparison.first());
+    let second = Literal(comparison.second().unwrap_or(Value::Bool(None)));
+    match comparison.comparison_kind() {
+        ComparisonKind::Less => write!(f, "x < {first}"),
+        ComparisonKind::Equal => write!(f, "x = {first}"),
+        ComparisonKind::Greater => write!(f, "x > {first}"),
+        ComparisonKind::LessOrEqual => write!(f, "x ≤ {first}"),
+        ComparisonKind::GreaterOrEqual => write!(f, "x ≥ {first}"),
+        ComparisonKind::Contain => write!(f, "x Contains {first}"),
+        ComparisonKind::NotEqual => write!(f, "x != {first}"),
+        ComparisonKind::NotContain => write!(f, "x !Contains {first}"),
+        ComparisonKind::OpenRange => write!(f, "{first} < x < {second}"),
+        ComparisonKind::CloseRange => write!(f, "{first} ≤ x ≤ {second}"),
+        ComparisonKind::LeftOpenRange => write!(f, "{first} < x ≤ {second}"),
+        ComparisonKind::RightOpenRange => write!(f, "{first} ≤ x < {second}"),
+        ComparisonKind::NotOpenRange => write!(f, "!({first} < x < {second})"),
+        ComparisonKind::NotCloseRange => write!(f, "!({first} ≤ x ≤ {second})"),
+        ComparisonKind::NotLeftOpenRange => write!(f, "!({first} < x ≤ {second})"),
+        ComparisonKind::NotRightOpenRange => write!(f, "!({first} ≤ x < {second})"),
+    }
+}
+
+/// A value written so that its kind can be told from the text.
+struct Literal(Value);
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Value::String(Some(v)) => {
+                write!(f, "\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            Value::Integer(Some(v)) => write!(f, "{v}"),
+            Value::UInteger(Some(v)) => write!(f, "{v}u"),
+            Value::Vector(Some(v)) => write!(f, "{v:?}"),
+            Value::Float(Some(v)) => write!(f, "{v:?}"),
+            Value::IpAddr(Some(v)) => write!(f, "{v}"),
+            Value::Bool(Some(v)) => write!(f, "{v}"),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    Contain,
+    NotContain,
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    And,
+    Or,
+    Not,
+    Bang,
+    X,
+    Operator(Operator),
+    Value(Value),
+    // an unquoted word whose kind is decided by the parser
+    Word(String),
+}
+
+// characters that end a word
+const DELIMITERS: &[char] = &['(', ')', '[', ']', '"', '<', '>', '=', '!', '≤', '≥', '≠'];
+
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ParseComparisonError> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let error = |position, reason| ParseComparisonError { position, reason };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let token = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '<' | '>' if chars.get(i + 1) == Some(&'=') => {
+                i += 1;
+                if chars[start] == '<' {
+                    Token::Operator(Operator::LessOrEqual)
+                } else {
+                    Token::Operator(Operator::GreaterOrEqual)
+                }
+            }
+            '<' => Token::Operator(Operator::Less),
+            '>' => Token::Operator(Operator::Greater),
+            '≤' => Token::Operator(Operator::LessOrEqual),
+            '≥' => Token::Operator(Operator::GreaterOrEqual),
+            '≠' => Token::Operator(Operator::NotEqual),
+            '=' => Token::Operator(Operator::Equal),
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                i += 1;
+                Token::Operator(Operator::NotEqual)
+            }
+            '!' if chars[i + 1..].starts_with(&['C', 'o', 'n', 't', 'a', 'i', 'n', 's']) => {
+                i += 8;
+                Token::Operator(Operator::NotContain)
+            }
+            '!' => Token::Bang,
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    i += 1;
+                    match chars.get(i) {
+                        None => return Err(error(start, "Unterminated string")),
+                        Some('"') => break,
+                        Some('\\') if i + 1 < chars.len() => {
+                            i += 1;
+                            value.push(chars[i]);
+                        }
+                        Some(&c) => value.push(c),
+                    }
+                }
+                Token::Value(Value::String(Some(value)))
+            }
+            '[' => {
+                let Some(len) = chars[i..].iter().position(|&c| c == ']') else {
+                    return Err(error(start, "Unterminated byte array"));
+                };
+                let inner = chars[i + 1..i + len].iter().collect::<String>();
+                i += len;
+                let bytes = if inner.trim().is_empty() {
+                    Ok(Vec::new())
+                } else {
+                    inner.split(',').map(|b| b.trim().parse::<u8>()).collect()
+                };
+                Token::Value(Value::Vector(Some(
+                    bytes.map_err(|_| error(start, "Invalid byte array"))?,
+                )))
+            }
+            ']' => return Err(error(start, "Unexpected character")),
+            _ => {
+                let len = chars[i..]
+                    .iter()
+                    .position(|&c| c.is_whitespace() || DELIMITERS.contains(&c))
+                    .unwrap_or(chars.len() - i);
+                let word = chars[i..i + len].iter().collect::<String>();
+                i += len - 1;
+                match word.as_str() {
+                    "x" => Token::X,
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    "Contains" => Token::Operator(Operator::Contain),
+                    _ => Token::Word(word),
+                }
+            }
+        };
+        tokens.push((start, token));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// Parses an unquoted value, inferring its kind.
+fn infer_value(word: &str) -> Value {
+    [
+        ValueKind::Bool,
+        ValueKind::UInteger,
+        ValueKind::Integer,
+        ValueKind::IpAddr,
+        ValueKind::Float,
+    ]
+    .into_iter()
+    .find_map(|kind| {
+        parse_value(word, kind).filter(|_| kind != ValueKind::UInteger || word.ends_with('u'))
+    })
+    .unwrap_or_else(|| Value::String(Some(word.to_string())))
+}
+
+/// Parses an unquoted value of `kind`.
+fn parse_value(word: &str, kind: ValueKind) -> Option<Value> {
+    match kind {
+        ValueKind::String => Some(Value::String(Some(word.to_string()))),
+        ValueKind::Integer => word.parse().ok().map(|v| Value::Integer(Some(v))),
+        ValueKind::UInteger => word
+            .strip_suffix('u')
+            .unwrap_or(word)
+            .parse()
+            .ok()
+            .map(|v| Value::UInteger(Some(v))),
+        ValueKind::Vector => None,
+        ValueKind::Float => word.parse().ok().map(|v| Value::Float(Some(v))),
+        ValueKind::IpAddr => word.parse::<IpAddr>().ok().map(|v| Value::IpAddr(Some(v))),
+        ValueKind::Bool => word.parse().ok().map(|v| Value::Bool(Some(v))),
+    }
+}
+
+/// Converts an integer to a float if the other value of a range is a float,
+/// so that `0 < x < 2.5` is a range of floats.
+#[allow(clippy::cast_precision_loss)] // a typed integer is exact enough
+fn unify(first: Value, second: Value) -> (Value, Value) {
+    let to_float = |value: Value| match value {
+        Value::Integer(Some(v)) => Value::Float(Some(v as f64)),
+        Value::UInteger(Some(v)) => Value::Float(Some(v as f64)),
+        v => v,
+    };
+    match (&first, &second) {
+        (Value::Integer(_) | Value::UInteger(_), Value::Float(_)) => (to_float(first), second),
+        (Value::Float(_), Value::Integer(_) | Value::UInteger(_)) => (first, to_float(second)),
+        _ => (first, second),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    // the position of the end of the text
+    end: usize,
+    // the kind of every value, or `None` to infer it
+    kind: Option<ValueKind>,
+}
+
+impl Parser {
+    fn new(s: &str, kind: Option<ValueKind>) -> Result<Self, ParseComparisonError> {
+        Ok(Self {
+            tokens: tokenize(s)?,
+            next: 0,
+            end: s.chars().count(),
+            kind,
+        })
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.end, |(position, _)| *position)
+    }
+
+    fn error(&self, reason: &'static str) -> ParseComparisonError {
+        ParseComparisonError {
+            position: self.position(),
+            reason,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &Token, reason: &'static str) -> Result<(), ParseComparisonError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(reason))
+        }
+    }
+
+    fn end(&self) -> Result<(), ParseComparisonError> {
+        if self.next < self.tokens.len() {
+            Err(self.error("Unexpected token"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn or(&mut self) -> Result<ComparisonExpression, ParseComparisonError> {
+        let mut children = vec![self.and()?];
+        while self.eat(&Token::Or) {
+            children.push(self.and()?);
+        }
+        Ok(if children.len() == 1 {
+            children.remove(0)
+        } else {
+            ComparisonExpression::Or(children)
+        })
+    }
+
+    fn and(&mut self) -> Result<ComparisonExpression, ParseComparisonError> {
+        let mut children = vec![self.unary()?];
+        while self.eat(&Token::And) {
+            children.push(self.unary()?);
+        }
+        Ok(if children.len() == 1 {
+            children.remove(0)
+        } else {
+            ComparisonExpression::And(children)
+        })
+    }
+
+    fn unary(&mut self) -> Result<ComparisonExpression, ParseComparisonError> {
+        if self.eat(&Token::Not) {
+            return Ok(ComparisonExpression::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LeftParen) {
+            if self.peek() == Some(&Token::RightParen) {
+                return Err(self.error("Empty group"));
+            }
+            let expression = self.or()?;
+            self.expect(&Token::RightParen, "Expected \")\"")?;
+            return Ok(expression);
+        }
+        self.predicate().map(ComparisonExpression::Predicate)
+    }
+
+    fn predicate(&mut self) -> Result<Comparison, ParseComparisonError> {
+        match self.peek() {
+            Some(Token::X) => {
+                self.next += 1;
+                let operator = self.operator()?;
+                let value = self.value()?;
+                let kind = match operator {
+                    Operator::Less => ComparisonKind::Less,
+                    Operator::LessOrEqual => ComparisonKind::LessOrEqual,
+                    Operator::Greater => ComparisonKind::Greater,
+                    Operator::GreaterOrEqual => ComparisonKind::GreaterOrEqual,
+                    Operator::Equal => ComparisonKind::Equal,
+                    Operator::NotEqual => ComparisonKind::NotEqual,
+                    Operator::Contain => ComparisonKind::Contain,
+                    Operator::NotContain => ComparisonKind::NotContain,
+                };
+                Ok(Comparison::try_new(kind, value, None).expect("a single value is enough"))
+            }
+            Some(Token::Bang) => {
+                self.next += 1;
+                self.expect(&Token::LeftParen, "Expected \"(\"")?;
+                let range = self.range(true)?;
+                self.expect(&Token::RightParen, "Expected \")\"")?;
+                Ok(range)
+            }
+            Some(Token::Value(_) | Token::Word(_)) => self.range(false),
+            _ => Err(self.error("Expected a comparison")),
+        }
+    }
+
+    /// Parses `a < x < b` and the like.
+    fn range(&mut self, negated: bool) -> Result<Comparison, ParseComparisonError> {
+        let first = self.value()?;
+        let left = self.range_operator()?;
+        self.expect(&Token::X, "Expected \"x\"")?;
+        let right = self.range_operator()?;
+        let position = self.position();
+        let second = self.value()?;
+        let (first, second) = unify(first, second);
+        if first.kind() != second.kind() {
+            return Err(ParseComparisonError {
+                position,
+                reason: "Values of different types",
+            });
+        }
+        let kind = match (negated, left, right) {
+            (false, Operator::Less, Operator::Less) => ComparisonKind::OpenRange,
+            (false, Operator::LessOrEqual, Operator::LessOrEqual) => ComparisonKind::CloseRange,
+            (false, Operator::Less, _) => ComparisonKind::LeftOpenRange,
+            (false, _, _) => ComparisonKind::RightOpenRange,
+            (true, Operator::Less, Operator::Less) => ComparisonKind::NotOpenRange,
+            (true, Operator::LessOrEqual, Operator::LessOrEqual) => ComparisonKind::NotCloseRange,
+            (true, Operator::Less, _) => ComparisonKind::NotLeftOpenRange,
+            (true, _, _) => ComparisonKind::NotRightOpenRange,
+        };
+        Ok(Comparison::try_new(kind, first, Some(second)).expect("both values are given"))
+    }
+
+    fn operator(&mut self) -> Result<Operator, ParseComparisonError> {
+        match self.peek() {
+            Some(&Token::Operator(operator)) => {
+                self.next += 1;
+                Ok(operator)
+            }
+            _ => Err(self.error("Expected a comparison operator")),
+        }
+    }
+
+    fn range_operator(&mut self) -> Result<Operator, ParseComparisonError> {
+        match self.peek() {
+            Some(&Token::Operator(operator @ (Operator::Less | Operator::LessOrEqual))) => {
+                self.next += 1;
+                Ok(operator)
+            }
+            _ => Err(self.error("Expected \"<\" or \"≤\"")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseComparisonError> {
+        let value = match (self.peek(), self.kind) {
+            (Some(Token::Value(value)), kind) if kind.is_none_or(|k| k == value.kind()) => {
+                value.clone()
+            }
+            (Some(Token::Word(word)), None) => infer_value(word),
+            (Some(Token::Word(word)), Some(kind)) => {
+                parse_value(word, kind).ok_or_else(|| self.error("Value of a different type"))?
+            }
+            (Some(Token::Value(_)), Some(_)) => {
+                return Err(self.error("Value of a different type"));
+            }
+            _ => return Err(self.error("Expected a value")),
+        };
+        self.next += 1;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::ComparisonExpression as Expr;
+    use crate::{Comparison, ComparisonValue as Value, ValueKind};
+
+    fn int(v: i64) -> Value {
+        Value::Integer(Some(v))
+    }
+
+    #[test]
+    fn round_trip() {
+        let expression = Expr::Or(vec![
+            Expr::And(vec![
+                Comparison::Greater(int(10)).into(),
+                Comparison::Less(int(100)).into(),
+            ]),
+            Comparison::Equal(int(0)).into(),
+            Expr::Not(Box::new(
+                Comparison::Contain(Value::String(Some("t\"mp".to_string()))).into(),
+            )),
+            Expr::And(vec![
+                Expr::Or(vec![
+                    Comparison::NotLeftOpenRange(
+                        Value::UInteger(Some(1)),
+                        Value::UInteger(Some(5)),
+                    )
+                    .into(),
+                    Comparison::NotEqual(Value::IpAddr(Some(IpAddr::V4(Ipv4Addr::LOCALHOST))))
+                        .into(),
+                ]),
+                Comparison::GreaterOrEqual(Value::Float(Some(1.0))).into(),
+                Comparison::Equal(Value::Vector(Some(vec![1, 2]))).into(),
+            ]),
+        ]);
+        let text = expression.to_string();
+        assert_eq!(
+            text,
+            "x > 10 AND x < 100 OR x = 0 OR NOT x Contains \"t\\\"mp\" OR \
+             (!(1u < x ≤ 5u) OR x != 127.0.0.1) AND x ≥ 1.0 AND x = [1, 2]"
+        );
+        assert_eq!(text.parse::<Expr>(), Ok(expression));
+    }
+
+    #[test]
+    fn normalize() {
+        let predicate = |v| Expr::Predicate(Comparison::Equal(int(v)));
+        let expression = Expr::And(vec![
+            Expr::And(vec![predicate(1), Expr::Or(vec![])]),
+            Expr::Or(vec![predicate(2)]),
+            Expr::Not(Box::new(Expr::And(vec![predicate(3), predicate(4)]))),
+        ]);
+        let normalized = Expr::And(vec![
+            predicate(1),
+            predicate(2),
+            Expr::Not(Box::new(Expr::And(vec![predicate(3), predicate(4)]))),
+        ]);
+        assert_eq!(expression.clone().normalize(), Some(normalized.clone()));
+        let text = Expr::And(vec![expression, Expr::And(vec![])])
+            .normalize()
+            .expect("has conditions")
+            .to_string();
+        assert_eq!(text, "x = 1 AND x = 2 AND NOT (x = 3 AND x = 4)");
+        assert_eq!(text.parse(), Ok(normalized));
+        assert_eq!(Expr::Or(vec![Expr::And(vec![])]).normalize(), None);
+    }
+
+    #[test]
+    fn edit() {
+        let mut expression = Expr::And(vec![Comparison::Equal(int(1)).into()]);
+        assert!(expression.push(&[], Expr::Or(Vec::new())));
+        assert!(expression.push(&[1], Comparison::Equal(int(2)).into()));
+        assert!(!expression.push(&[0], Comparison::Equal(int(3)).into()));
+        expression.toggle_not(&[1]);
+        expression.toggle_operator(&[1, 0]);
+        assert_eq!(expression.to_string(), "x = 1 AND NOT x = 2");
+        assert!(matches!(expression.get(&[1, 0]), Some(Expr::And(_))));
+        assert_eq!(
+            expression.remove(&[1, 0]),
+            Some(Expr::And(vec![Comparison::Equal(int(2)).into()]))
+        );
+        assert_eq!(expression.children().len(), 1);
+    }
+
+    #[test]
+    fn errors() {
+        let position = |text: &str| text.parse::<Expr>().map_err(|e| e.position());
+        assert_eq!(position("x = 1 AND"), Err(9));
+        assert_eq!(position("(x = 1"), Err(6));
+        assert_eq!(position("x = \"a"), Err(4));
+        assert_eq!(position("1 < x < \"a\""), Err(8));
+        assert_eq!(position("x = 1 x = 2"), Err(6));
+        assert_eq!(position("()"), Err(1));
+    }
+
+    #[test]
+    fn comparison() {
+        let string = |v: &str| Value::String(Some(v.to_string()));
+        let parse = |text: &str| text.parse::<Comparison>();
+        assert_eq!(
+            parse("10 < x <= 20"),
+            Ok(Comparison::LeftOpenRange(int(10), int(20)))
+        );
+        assert_eq!(parse("10 < x ≤ 20"), parse("10 < x <= 20"));
+        assert_eq!(parse("x ≠ 1"), parse("x != 1"));
+        assert_eq!(
+            parse("!(0 <= x < 2.5)"),
+            Ok(Comparison::NotRightOpenRange(
+                Value::Float(Some(0.0)),
+                Value::Float(Some(2.5))
+            ))
+        );
+        assert_eq!(
+            parse("x != 192.168.0.1"),
+            Ok(Comparison::NotEqual(Value::IpAddr(Some(IpAddr::V4(
+                Ipv4Addr::new(192, 168, 0, 1)
+            )))))
+        );
+        assert_eq!(
+            parse("x >= 5u"),
+            Ok(Comparison::GreaterOrEqual(Value::UInteger(Some(5))))
+        );
+        assert_eq!(
+            parse("x !Contains foo"),
+            Ok(Comparison::NotContain(string("foo")))
+        );
+        assert_eq!(
+            parse("x Contains \"a b\""),
+            Ok(Comparison::Contain(string("a b")))
+        );
+        assert_eq!(parse("x Contains a b").map_err(|e| e.position()), Err(13));
+        assert!(parse("x = 1 AND x = 2").is_err());
+
+        let parse = |text: &str, kind| Comparison::parse_with_kind(text, kind);
+        assert_eq!(
+            parse("1 < x < 5", ValueKind::UInteger),
+            Ok(Comparison::OpenRange(
+                Value::UInteger(Some(1)),
+                Value::UInteger(Some(5))
+            ))
+        );
+        assert_eq!(
+            parse("x Contains 10", ValueKind::String),
+            Ok(Comparison::Contain(string("10")))
+        );
+        assert_eq!(
+            parse("x = 1", ValueKind::Float),
+            Ok(Comparison::Equal(Value::Float(Some(1.0))))
+        );
+        let position = |text: &str, kind| parse(text, kind).map_err(|e| e.position());
+        assert_eq!(position("x = -1", ValueKind::UInteger), Err(4));
+        assert_eq!(position("x = \"1\"", ValueKind::Integer), Err(4));
+        assert_eq!(position("x = [1]", ValueKind::String), Err(4));
+    }
+}
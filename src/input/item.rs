@@ -7,8 +7,8 @@ use std::{
 };
 
 use super::{
-    CheckStatus, Comparison, HostNetwork, InputConfig, InputHostNetworkGroup, InputNic,
-    InputTagGroup, parse_host_network,
+    CheckStatus, Comparison, ComparisonExpression, HostNetwork, InputConfig, InputHostNetworkGroup,
+    InputNic, InputTagGroup, parse_host_network,
 };
 use crate::{Theme, list::Column};
 
@@ -790,9 +790,16 @@ impl FileItem {
     }
 }
 
+/// The value of a comparison input.
+///
+/// It dereferences to the comparison being edited. With
+/// `ComparisonConfig::compound`, comparisons are combined into an
+/// expression; use [`ComparisonItem::to_expression`] to get the whole
+/// condition.
 #[derive(Clone, PartialEq, Default)]
 pub struct ComparisonItem {
     comparison: Option<Comparison>,
+    expression: Option<ComparisonExpression>,
 }
 
 impl Deref for ComparisonItem {
@@ -812,12 +819,23 @@ impl DerefMut for ComparisonItem {
 impl ComparisonItem {
     #[must_use]
     pub fn new(comparison: Option<Comparison>) -> Self {
-        Self { comparison }
+        Self {
+            comparison,
+            expression: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_expression(expression: Option<ComparisonExpression>) -> Self {
+        Self {
+            comparison: None,
+            expression,
+        }
     }
 
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.comparison.is_none()
+        self.to_expression().is_none()
     }
 
     #[must_use]
@@ -825,6 +843,25 @@ impl ComparisonItem {
         self.comparison.clone()
     }
 
+    #[must_use]
+    pub fn expression(&self) -> Option<&ComparisonExpression> {
+        self.expression.as_ref()
+    }
+
+    pub fn expression_mut(&mut self) -> &mut Option<ComparisonExpression> {
+        &mut self.expression
+    }
+
+    /// Returns the normalized expression if it has any comparison, or else
+    /// the comparison being edited.
+    #[must_use]
+    pub fn to_expression(&self) -> Option<ComparisonExpression> {
+        self.expression
+            .clone()
+            .and_then(ComparisonExpression::normalize)
+            .or_else(|| self.comparison.clone().map(ComparisonExpression::Predicate))
+    }
+
     pub fn clear(&mut self) {
         *self = Self::new(None);
     }
@@ -1204,9 +1241,7 @@ impl From<&Column> for InputItem {
             }
             Column::Tag(tags) => Self::Tag(TagItem::new(InputTagGroup {
                 old: tags.tags.clone(),
-                new: None,
-                edit: None,
-                delete: None,
+                operations: Vec::new(),
             })),
             Column::Unsigned32(value) => Self::Unsigned32(Unsigned32Item::new(value.value)),
             Column::Unsigned16(value) => Self::Unsigned16(Unsigned16Item::new(value.value)),
@@ -1270,9 +1305,10 @@ impl From<&Column> for InputItem {
                 }
                 Self::Group(GroupItem::new(input))
             }
-            Column::Comparison(value) => {
-                Self::Comparison(ComparisonItem::new(value.comparison.clone()))
-            }
+            Column::Comparison(value) => Self::Comparison(ComparisonItem {
+                comparison: value.comparison.clone(),
+                expression: value.expression.clone(),
+            }),
             Column::File(value) => Self::File(FileItem::new(value.filename.clone(), String::new())),
         }
     }
@@ -1,7 +1,6 @@
 use core::panic;
-use std::{cell::RefCell, collections::HashSet, net::Ipv4Addr, rc::Rc, str::FromStr};
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
 
-use ipnet::Ipv4Net;
 use num_bigint::BigUint;
 use num_traits::ToPrimitive;
 use passwords::analyzer;
@@ -14,6 +13,7 @@ use super::{
     VecSelectItem, cal_index,
     component::{InvalidMessage, Model, Verification},
     group_item_list_preset,
+    user_input_nic::verify_nics,
 };
 use crate::{InvalidPasswordKind as Kind, PASSWORD_MIN_LEN, is_adjacent};
 
@@ -606,7 +606,7 @@ where
                                 | InputItem::Float64(_)
                                 | InputItem::Percentage(_) => Some(Some(col.is_empty())),
                                 InputItem::Comparison(v) => {
-                                    if v.is_some() {
+                                    if !v.is_empty() {
                                         Some(Some(false))
                                     } else if self
                                         .comparison_kind(
@@ -698,103 +698,20 @@ where
                     let item_index = cal_index(base_index, index);
                     // HIGHTLIGHT: All kinds are not necessarily to be verified.
                     // HIGHTLIGHT: Since HostNetworkGroup items were verified, they don't need to be verified here.
-                    match (&*input_data, &**input_conf) {
-                        (InputItem::Text(value), InputConfig::Text(config)) => {
-                            if parent_checked
-                                && !value.is_empty()
-                                && let Some(validator) = config.validation
-                            {
-                                match validator(value) {
-                                    Ok(()) => {
-                                        self.verification.insert(item_index, Verification::Valid);
-                                    }
-                                    Err(msg) => {
-                                        self.verification.insert(
-                                            item_index,
-                                            Verification::Invalid(InvalidMessage::InvalidCustom(
-                                                msg,
-                                            )),
-                                        );
-                                        rtn = false;
-                                    }
-                                }
-                            }
-                        }
-                        (InputItem::Unsigned32(value), InputConfig::Unsigned32(config)) => {
-                            if let Some(value) = value.as_ref()
-                                && parent_checked
-                            {
-                                if *value >= config.min && *value <= config.max {
-                                    self.verification.insert(item_index, Verification::Valid);
-                                } else {
-                                    self.verification.insert(
-                                        item_index,
-                                        Verification::Invalid(InvalidMessage::InvalidInput),
-                                    );
-                                    rtn = false;
-                                }
-                            }
-                        }
-                        (InputItem::Unsigned16(value), InputConfig::Unsigned16(config)) => {
-                            if let Some(value) = value.as_ref()
-                                && parent_checked
-                            {
-                                if *value >= config.min && *value <= config.max {
-                                    self.verification.insert(item_index, Verification::Valid);
-                                } else {
-                                    self.verification.insert(
-                                        item_index,
-                                        Verification::Invalid(InvalidMessage::InvalidInput),
-                                    );
-                                    rtn = false;
-                                }
-                            }
-                        }
-                        (InputItem::Unsigned8(value), InputConfig::Unsigned8(config)) => {
-                            if let Some(value) = value.as_ref()
-                                && parent_checked
-                            {
-                                if *value >= config.min && *value <= config.max {
-                                    self.verification.insert(item_index, Verification::Valid);
-                                } else {
-                                    self.verification.insert(
-                                        item_index,
-                                        Verification::Invalid(InvalidMessage::InvalidInput),
-                                    );
-                                    rtn = false;
-                                }
-                            }
-                        }
-                        (InputItem::Percentage(value), InputConfig::Percentage(config)) => {
-                            if let Some(value) = value.as_ref()
-                                && parent_checked
-                            {
-                                if *value >= config.min.unwrap_or(0.0)
-                                    && *value <= config.max.unwrap_or(1.0)
-                                {
-                                    self.verification.insert(item_index, Verification::Valid);
-                                } else {
-                                    self.verification.insert(
-                                        item_index,
-                                        Verification::Invalid(InvalidMessage::InvalidInput),
-                                    );
-                                    rtn = false;
-                                }
+                    if parent_checked && let Some(result) = verify_leaf(&input_data, input_conf) {
+                        match result {
+                            Ok(()) => {
+                                self.verification
+                                    .insert(item_index.clone(), Verification::Valid);
                             }
-                        }
-                        (InputItem::DomainName(domain), InputConfig::DomainName(_)) => {
-                            if parent_checked && !domain.is_empty() {
-                                if domain.is_valid() {
-                                    self.verification.insert(item_index, Verification::Valid);
-                                } else {
-                                    self.verification.insert(
-                                        item_index,
-                                        Verification::Invalid(InvalidMessage::InvalidDomain),
-                                    );
-                                    rtn = false;
-                                }
+                            Err(message) => {
+                                self.verification
+                                    .insert(item_index.clone(), Verification::Invalid(message));
+                                rtn = false;
                             }
                         }
+                    }
+                    match (&*input_data, &**input_conf) {
                         (InputItem::Password(pwd), InputConfig::Password(_)) => {
                             if parent_checked
                                 && let Some(cnf_pwd) = self.confirm_password.get(&(item_index))
@@ -822,65 +739,14 @@ where
                                 }
                             }
                         }
-                        (InputItem::Nic(nics), InputConfig::Nic(_)) => {
+                        (InputItem::Nic(nics), InputConfig::Nic(config)) => {
                             if parent_checked {
-                                for (i, nic) in nics.iter().enumerate() {
-                                    let nic_index = cal_index(Some(&item_index), i);
-                                    if !nic.interface.is_empty() || !nic.gateway.is_empty() {
-                                        if nic.name.is_empty() {
-                                            self.verification_nic.insert(
-                                                (nic_index.clone(), 0),
-                                                Verification::Invalid(
-                                                    InvalidMessage::InterfaceNameRequired,
-                                                ),
-                                            );
-                                            rtn = false;
-                                        }
-                                        if nic.interface.is_empty() {
-                                            self.verification_nic.insert(
-                                                (nic_index.clone(), 1),
-                                                Verification::Invalid(
-                                                    InvalidMessage::InterfaceRequired,
-                                                ),
-                                            );
-                                            rtn = false;
-                                        } else if Ipv4Net::from_str(&nic.interface).is_err() {
-                                            self.verification_nic.insert(
-                                                (nic_index.clone(), 1),
-                                                Verification::Invalid(
-                                                    InvalidMessage::WrongInterface,
-                                                ),
-                                            );
-                                            rtn = false;
-                                        }
-                                        if nic.gateway.is_empty() {
-                                            self.verification_nic.insert(
-                                                (nic_index.clone(), 2),
-                                                Verification::Invalid(
-                                                    InvalidMessage::GatewayRequired,
-                                                ),
-                                            );
-                                            rtn = false;
-                                        } else if Ipv4Addr::from_str(&nic.gateway).is_err() {
-                                            self.verification_nic.insert(
-                                                (nic_index, 2),
-                                                Verification::Invalid(InvalidMessage::WrongGateway),
-                                            );
-                                            rtn = false;
-                                        }
-                                    } else if !nic.name.is_empty() {
-                                        self.verification_nic.insert(
-                                            (nic_index.clone(), 1),
-                                            Verification::Invalid(
-                                                InvalidMessage::InterfaceRequired,
-                                            ),
-                                        );
-                                        self.verification_nic.insert(
-                                            (nic_index, 2),
-                                            Verification::Invalid(InvalidMessage::GatewayRequired),
-                                        );
-                                        rtn = false;
-                                    }
+                                for (i, field, message) in verify_nics(nics, config) {
+                                    self.verification_nic.insert(
+                                        (cal_index(Some(&item_index), i), field),
+                                        Verification::Invalid(message),
+                                    );
+                                    rtn = false;
                                 }
                             }
                         }
@@ -1556,6 +1422,73 @@ where
     }
 }
 
+/// Verifies an item that has no children, such as `Text` or `Unsigned32`.
+///
+/// Returns `None` if the item is empty or is not of such a kind.
+fn verify_leaf(
+    input_data: &InputItem,
+    input_conf: &InputConfig,
+) -> Option<Result<(), InvalidMessage>> {
+    let valid = match (input_data, input_conf) {
+        (InputItem::Text(value), InputConfig::Text(config)) => {
+            if value.is_empty() {
+                return None;
+            }
+            let validator = config.validation?;
+            return Some(validator(value).map_err(InvalidMessage::InvalidCustom));
+        }
+        (InputItem::Unsigned32(value), InputConfig::Unsigned32(config)) => {
+            let value = value.as_ref()?;
+            *value >= config.min && *value <= config.max
+        }
+        (InputItem::Unsigned16(value), InputConfig::Unsigned16(config)) => {
+            let value = value.as_ref()?;
+            *value >= config.min && *value <= config.max
+        }
+        (InputItem::Unsigned8(value), InputConfig::Unsigned8(config)) => {
+            let value = value.as_ref()?;
+            *value >= config.min && *value <= config.max
+        }
+        (InputItem::Percentage(value), InputConfig::Percentage(config)) => {
+            let value = value.as_ref()?;
+            *value >= config.min.unwrap_or(0.0) && *value <= config.max.unwrap_or(1.0)
+        }
+        (InputItem::DomainName(domain), InputConfig::DomainName(_)) => {
+            if domain.is_empty() {
+                return None;
+            }
+            if domain.is_valid() {
+                return Some(Ok(()));
+            }
+            return Some(Err(InvalidMessage::InvalidDomain));
+        }
+        (_, _) => return None,
+    };
+    Some(if valid {
+        Ok(())
+    } else {
+        Err(InvalidMessage::InvalidInput)
+    })
+}
+
+/// Verifies a single item with the same rules as `Input` does, for editing
+/// a cell of a list in place.
+///
+/// # Errors
+///
+/// Returns the text key of the message to show if the item is invalid.
+pub(crate) fn verify_cell(input_data: &InputItem, input_conf: &InputConfig) -> Result<(), String> {
+    if input_conf.required() && input_data.is_empty() {
+        return Err("This field is required.".to_string());
+    }
+    match verify_leaf(input_data, input_conf) {
+        Some(Err(InvalidMessage::InvalidCustom(msg))) => Err(msg),
+        Some(Err(InvalidMessage::InvalidDomain)) => Err("Invalid domain name".to_string()),
+        Some(Err(_)) => Err("Invalid input".to_string()),
+        Some(Ok(())) | None => Ok(()),
+    }
+}
+
 #[must_use]
 pub fn invalid_password(password: &str) -> Option<Kind> {
     let analyzed = analyzer::analyze(password);
@@ -1604,3 +1537,87 @@ pub fn invalid_password(password: &str) -> Option<Kind> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::verify_cell;
+    use crate::{
+        InputConfig, InputEssential, InputItem, PercentageConfig, PercentageItem, TextConfig,
+        TextItem, Unsigned16Config, Unsigned16Item,
+    };
+
+    fn ess(required: bool) -> InputEssential {
+        InputEssential {
+            title: String::new(),
+            notice: "",
+            required,
+        }
+    }
+
+    #[test]
+    fn cell() {
+        let conf = InputConfig::Unsigned16(Unsigned16Config {
+            ess: ess(true),
+            min: 1,
+            max: 100,
+            width: None,
+            preset: None,
+        });
+        assert!(
+            verify_cell(
+                &InputItem::Unsigned16(Unsigned16Item::new(Some(100))),
+                &conf
+            )
+            .is_ok()
+        );
+        assert_eq!(
+            verify_cell(
+                &InputItem::Unsigned16(Unsigned16Item::new(Some(101))),
+                &conf
+            ),
+            Err("Invalid input".to_string())
+        );
+        assert_eq!(
+            verify_cell(&InputItem::Unsigned16(Unsigned16Item::new(None)), &conf),
+            Err("This field is required.".to_string())
+        );
+
+        let conf = InputConfig::Percentage(PercentageConfig {
+            ess: ess(false),
+            min: None,
+            max: Some(0.5),
+            num_decimals: None,
+            width: None,
+            preset: None,
+        });
+        assert!(verify_cell(&InputItem::Percentage(PercentageItem::new(None)), &conf).is_ok());
+        assert!(
+            verify_cell(
+                &InputItem::Percentage(PercentageItem::new(Some(0.6))),
+                &conf
+            )
+            .is_err()
+        );
+
+        let conf = InputConfig::Text(TextConfig {
+            ess: ess(false),
+            length: None,
+            width: None,
+            preset: None,
+            unique: false,
+            immutable: false,
+            validation: Some(|v| {
+                if v.starts_with('-') {
+                    Err("Must not start with a hyphen".to_string())
+                } else {
+                    Ok(())
+                }
+            }),
+        });
+        assert!(verify_cell(&InputItem::Text(TextItem::new("a".to_string())), &conf).is_ok());
+        assert_eq!(
+            verify_cell(&InputItem::Text(TextItem::new("-a".to_string())), &conf),
+            Err("Must not start with a hyphen".to_string())
+        );
+    }
+}
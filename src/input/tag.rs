@@ -7,20 +7,20 @@ use json_gettext::get_text;
 use wasm_bindgen::JsCast;
 use web_sys::{HtmlInputElement, KeyboardEvent};
 use yew::{
-    Component, Context, Html, Properties, TargetCast, events::InputEvent, html,
+    Callback, Component, Context, Html, Properties, TargetCast, events::InputEvent, html,
     virtual_dom::AttrValue,
 };
 
 use crate::click_outside::{toggle_visibility, visible_tag_select};
-use crate::{InputTagGroup, Texts, Theme, language::Language, text};
+use crate::{InputTagGroup, TagInfo, TagOperation, Texts, Theme, language::Language, text};
 
 pub struct Model<T> {
     id: String,
-    prev_list: Rc<HashMap<String, String>>,
+    prev_list: Rc<HashMap<String, TagInfo>>,
     input: String,
     message: Option<&'static str>,
     view_order: Vec<String>, // String = key of tag
-    search_list: Vec<(String, String)>,
+    search_list: Vec<(String, TagInfo)>,
     search_cursor: Option<usize>,
     edit: Option<String>, // String = key of tag
     edit_message: Option<&'static str>,
@@ -62,8 +62,12 @@ where
     pub language: Language,
     #[prop_or(None)]
     pub parent_message: Option<T::Message>,
-    pub prev_list: Rc<HashMap<String, String>>,
+    pub prev_list: Rc<HashMap<String, TagInfo>>,
     pub input_data: Rc<RefCell<InputTagGroup>>,
+    /// Called with each operation that users request, after it is recorded in
+    /// `InputTagGroup::operations`
+    #[prop_or(None)]
+    pub on_operation: Option<Callback<TagOperation>>,
     #[prop_or(None)]
     pub input_notice: Option<&'static str>,
     #[prop_or(None)]
@@ -145,12 +149,13 @@ where
             }
             Message::Enter => {
                 if !self.input.is_empty() {
+                    let mut operation = None;
                     let send_msg = if let Ok(mut data) = ctx.props().input_data.try_borrow_mut() {
                         if let Some((key, _)) = ctx
                             .props()
                             .prev_list
                             .iter()
-                            .find(|(_, v)| *v == &self.input)
+                            .find(|(_, v)| v.name == self.input)
                         {
                             if data.old.contains(key) {
                                 self.message = Some(EXIST_MSG);
@@ -161,9 +166,16 @@ where
                                 self.input = String::new();
                                 true
                             }
+                        } else if data.operations.iter().any(
+                            |o| matches!(o, TagOperation::Add(info) if info.name == self.input),
+                        ) {
+                            self.message = Some(EXIST_MSG);
+                            false
                         } else {
-                            data.new = Some(self.input.clone());
-                            self.input = String::new();
+                            let op =
+                                TagOperation::Add(TagInfo::new(std::mem::take(&mut self.input)));
+                            data.operations.push(op.clone());
+                            operation = Some(op);
                             true
                         }
                     } else {
@@ -173,6 +185,7 @@ where
                     if send_msg {
                         Self::buffer_to_input(ctx);
                     }
+                    Self::emit_operation(ctx, operation);
                 }
             }
             Message::Keyboard(keyboard) => match keyboard.as_str() {
@@ -249,19 +262,23 @@ where
                 self.edit_message = None;
             }
             Message::DeleteTag(key) => {
+                let operation = TagOperation::Delete(key);
                 if let Ok(mut data) = ctx.props().input_data.try_borrow_mut() {
-                    data.delete = Some(key);
+                    if data.operations.contains(&operation) {
+                        return false;
+                    }
+                    data.operations.push(operation.clone());
                 }
-                // TODO: revive this if necessary
-                // self.reset_search_list(ctx);
+                self.reset_search_list(ctx);
                 Self::buffer_to_input(ctx);
+                Self::emit_operation(ctx, Some(operation));
             }
             Message::InputEdit(input) => {
                 self.input_edit = input;
             }
             Message::EditDone => {
-                let send_msg = if self.input_edit.is_empty() {
-                    false
+                let operation = if self.input_edit.is_empty() {
+                    None
                 } else if let (Ok(mut input), Some(key)) =
                     (ctx.props().input_data.try_borrow_mut(), self.edit.as_ref())
                 {
@@ -269,20 +286,34 @@ where
                         .props()
                         .prev_list
                         .iter()
-                        .any(|(_, v)| v == &self.input_edit)
+                        .any(|(_, v)| v.name == self.input_edit)
                     {
                         self.edit_message = Some(EXIST_MSG);
-                        false
+                        None
                     } else {
                         self.edit_message = None;
-                        input.edit = Some((key.clone(), self.input_edit.clone()));
-                        true
+                        // keep the color and the description
+                        let info = TagInfo {
+                            name: self.input_edit.clone(),
+                            ..ctx.props().prev_list.get(key).cloned().unwrap_or_default()
+                        };
+                        let operation = TagOperation::Edit(key.clone(), info);
+                        // A later edit of the same tag replaces the pending one.
+                        if let Some(pending) = input.operations.iter_mut().find(
+                            |o| matches!(o, TagOperation::Edit(pending_key, _) if pending_key == key),
+                        ) {
+                            *pending = operation.clone();
+                        } else {
+                            input.operations.push(operation.clone());
+                        }
+                        Some(operation)
                     }
                 } else {
-                    false
+                    None
                 };
-                if send_msg {
+                if operation.is_some() {
                     Self::buffer_to_input(ctx);
+                    Self::emit_operation(ctx, operation);
                     self.input_edit = String::new();
                     self.edit = None;
                 }
@@ -323,20 +354,37 @@ where
         let theme = ctx.props().theme;
         if let Ok(data) = ctx.props().input_data.try_borrow() {
             html! {
-                for self.view_order.iter().map(|key| {
-                    if data.old.contains(key) {
-                        Self::view_item(ctx, key, ctx.props().prev_list.get(key).unwrap_or(key), theme)
-                    } else {
-                        html! {}
-                    }
-                })
+                <>
+                {
+                    for self.view_order.iter().map(|key| {
+                        if data.old.contains(key) {
+                            let fallback = TagInfo::new(key.clone());
+                            let info = ctx.props().prev_list.get(key).unwrap_or(&fallback);
+                            Self::view_item(ctx, key, info, theme)
+                        } else {
+                            html! {}
+                        }
+                    })
+                }
+                {
+                    // new tags waiting for the backend
+                    for data.operations.iter().filter_map(|o| match o {
+                        TagOperation::Add(info) => Some(html! {
+                            <div class="tag-group-input-item tag-group-input-item-pending">
+                                { info.name.clone() }
+                            </div>
+                        }),
+                        _ => None,
+                    })
+                }
+                </>
             }
         } else {
             html! {}
         }
     }
 
-    fn view_item(ctx: &Context<Self>, key: &str, tag: &str, theme: Option<Theme>) -> Html {
+    fn view_item(ctx: &Context<Self>, key: &str, tag: &TagInfo, theme: Option<Theme>) -> Html {
         let onclick_unselect = |key: String| {
             ctx.link()
                 .callback(move |_| Message::UnselectTag(key.clone()))
@@ -349,8 +397,9 @@ where
         let delete_img = Theme::path(&theme, delete_img_file);
 
         html! {
-            <div class="tag-group-input-item">
-                { tag }
+            <div class="tag-group-input-item" title={tag.description.clone()}>
+                { view_color(tag) }
+                { tag.name.clone() }
                 <img src={delete_img} class="tag-input-close" onclick={onclick_unselect(key.to_string())} />
             </div>
         }
@@ -447,7 +496,7 @@ where
                                     <div class="tag-group-input-select-item-edit-text">
                                         <input type="text"
                                             class="tag-select-edit-tag"
-                                            placeholder={v.clone()}
+                                            placeholder={v.name.clone()}
                                             oninput={oninput}
                                             onkeyup={onkeyup}
                                         />
@@ -500,8 +549,14 @@ where
                         html! {
                             <div class={class}>
                                 <div class="tag-group-input-select-item">
-                                    <div class="tag-group-input-select-item-text" onclick={ onclick_item(k.clone()) }>
-                                        { v.clone() }
+                                    <div class="tag-group-input-select-item-text" title={v.description.clone()} onclick={ onclick_item(k.clone()) }>
+                                        { view_color(v) }
+                                        { v.name.clone() }
+                                        if let Some(description) = &v.description {
+                                            <div class="tag-group-input-select-item-description">
+                                                { description }
+                                            </div>
+                                        }
                                     </div>
                                     if cfg!(feature = "pumpkin") {
                                         <img src={edit_img} class="tag-select-edit" onclick={ onclick_edit(k.clone()) } />
@@ -540,16 +595,19 @@ where
             .prev_list
             .iter()
             .map(|(k, v)| (k.clone(), v.clone()))
-            .collect::<Vec<(String, String)>>();
+            .collect::<Vec<(String, TagInfo)>>();
         if let Ok(data) = ctx.props().input_data.try_borrow() {
-            self.search_list.retain(|(k, _)| !data.old.contains(k));
+            self.search_list.retain(|(k, _)| {
+                !data.old.contains(k) && !data.operations.contains(&TagOperation::Delete(k.clone()))
+            });
         }
         if !self.input.is_empty() {
             let input = self.input.to_lowercase();
             self.search_list
-                .retain(|(_, v)| v.to_lowercase().contains(&input));
+                .retain(|(_, v)| v.name.to_lowercase().contains(&input));
         }
-        self.search_list.sort_unstable_by(|a, b| a.1.cmp(&b.1));
+        self.search_list
+            .sort_unstable_by(|a, b| a.1.name.cmp(&b.1.name));
         self.search_cursor = Some(0);
     }
 
@@ -561,6 +619,14 @@ where
         }
     }
 
+    fn emit_operation(ctx: &Context<Self>, operation: Option<TagOperation>) {
+        if let (Some(on_operation), Some(operation)) =
+            (ctx.props().on_operation.as_ref(), operation)
+        {
+            on_operation.emit(operation);
+        }
+    }
+
     fn buffer_to_input(ctx: &Context<Self>) {
         if let (Some(parent), Some(msg)) =
             (ctx.link().get_parent(), ctx.props().parent_message.as_ref())
@@ -569,3 +635,13 @@ where
         }
     }
 }
+
+pub(crate) fn view_color(tag: &TagInfo) -> Html {
+    if let Some(color) = tag.css_color() {
+        html! {
+            <span class="tag-color" style={format!("background-color: {color};")}></span>
+        }
+    } else {
+        html! {}
+    }
+}
@@ -10,7 +10,7 @@ use web_sys::{Event, HtmlInputElement};
 use yew::{Component, Context, Html, events::InputEvent, html, html::TargetCast};
 
 use super::{
-    InputItem, cal_index,
+    InputItem, TagInfo, cal_index,
     component::{InputSecondId, InvalidMessage, Message, Model},
 };
 use crate::{
@@ -1199,7 +1199,7 @@ where
         &self,
         ctx: &Context<Self>,
         ess: &InputEssential,
-        prev_list: &HashMap<String, String>,
+        prev_list: &HashMap<String, TagInfo>,
         input_data: &Rc<RefCell<InputItem>>,
         base_index: Option<&BigUint>,
         layer_index: usize,
@@ -1209,6 +1209,8 @@ where
         let txt = ctx.props().txt.txt.clone();
         if let Some(buffer) = self.tag_buffer.get(&my_index) {
             let prev_list = Rc::new(prev_list.clone());
+            let id = my_index.clone();
+            let item = input_data.clone();
             html! {
                 <div class="input-tag-group">
                     <div class="input-contents-item-general-title">
@@ -1221,6 +1223,8 @@ where
                         input_data={Rc::clone(buffer)}
                         input_notice={Some(ess.notice)}
                         parent_message={Some(Message::InputTagGroup(my_index.clone(), input_data.clone()))}
+                        on_operation={ctx.link().callback(move |operation| Message::InputTagOperation(id.clone(), operation, item.clone()))}
+                        rerender_serial={self.rerender_serial_tag}
                         {theme}
                     />
                     { self.view_required_msg(ctx, &my_index) }
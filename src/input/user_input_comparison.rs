@@ -1,29 +1,39 @@
-use std::{cell::RefCell, collections::HashMap, net::IpAddr, rc::Rc, str::FromStr};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    rc::Rc,
+    str::FromStr,
+};
 
 use json_gettext::get_text;
 use num_bigint::BigUint;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
-use yew::{Component, Context, Html, events::InputEvent, html};
+use yew::{Component, Context, Html, classes, events::InputEvent, html};
 
 use super::{
-    Comparison, ComparisonItem, ComparisonKind, InputItem, Value as ComparisonValue, ValueKind,
-    cal_index,
+    Comparison, ComparisonExpression, ComparisonKind, InputItem, ParseComparisonError,
+    Value as ComparisonValue, ValueKind, cal_index,
     component::{Message, Model},
     user_input::view_asterisk,
 };
-use crate::{InputEssential, Item, SelectSearchableKind, VecSelect, ViewString, text};
+use crate::{
+    InputEssential, Item, SelectSearchableKind, VecSelect, ViewString, language::translate, text,
+};
 
 impl<T> Model<T>
 where
     T: Clone + Component + PartialEq,
     <T as Component>::Message: Clone + PartialEq,
 {
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
     pub(super) fn view_comparison(
         &self,
         ctx: &Context<Self>,
         ess: &InputEssential,
+        compound: bool,
+        allow_text: bool,
         input_data: &Rc<RefCell<InputItem>>,
         base_index: Option<&BigUint>,
         layer_index: usize,
@@ -111,6 +121,23 @@ where
         } else {
             (false, None)
         };
+        let typed = allow_text
+            .then(|| self.comparison_text_buffer.get(&my_index))
+            .flatten();
+        let mode_toggle = if allow_text {
+            let (data_id, input_data) = (my_index.clone(), input_data.clone());
+            let onclick = ctx.link().callback(move |_| {
+                Message::InputComparisonTextMode(data_id.clone(), input_data.clone())
+            });
+            let label = if typed.is_some() { "Form" } else { "Text" };
+            html! {
+                <div class="input-comparison-mode" onclick={onclick}>
+                    { translate(&txt, ctx.props().language, label) }
+                </div>
+            }
+        } else {
+            html! {}
+        };
         html! {
             <div class="input-comparison-outer">
                 {
@@ -124,27 +151,35 @@ where
                         }
                     }
                 }
-                <div class="input-comparison">
-                    <VecSelect<Self>
-                        txt={ctx.props().txt.clone()}
-                        language={ctx.props().language}
-                        id={format!("VecSelect-{}-{layer_index}", base_index.map_or_else(String::new, ToString::to_string))}
-                        title={title}
-                        kind_last={SelectSearchableKind::Single}
-                        empty_msg={empty_msg}
-                        top_width={top_width}
-                        max_width={max_width}
-                        max_height={max_height}
-                        allow_empty={allow_empty}
-                        sized_value={sized_value}
-                        list={list}
-                        selected={selected}
-                        parent_message={parent_message}
-                        show_required_msg={show_required_msg}
-                        required_msg_html={required_msg_html}
-                    />
-                    { self.view_comparison_value(ctx, input_data, &my_index) }
-                </div>
+                if let Some((typed, error)) = typed {
+                    { Self::view_comparison_text(ctx, input_data, &my_index, typed, error.as_ref(), mode_toggle) }
+                } else {
+                    <div class="input-comparison">
+                        <VecSelect<Self>
+                            txt={ctx.props().txt.clone()}
+                            language={ctx.props().language}
+                            id={format!("VecSelect-{}-{layer_index}", base_index.map_or_else(String::new, ToString::to_string))}
+                            title={title}
+                            kind_last={SelectSearchableKind::Single}
+                            empty_msg={empty_msg}
+                            top_width={top_width}
+                            max_width={max_width}
+                            max_height={max_height}
+                            allow_empty={allow_empty}
+                            sized_value={sized_value}
+                            list={list}
+                            selected={selected}
+                            parent_message={parent_message}
+                            show_required_msg={show_required_msg}
+                            required_msg_html={required_msg_html}
+                        />
+                        { self.view_comparison_value(ctx, input_data, &my_index) }
+                        { mode_toggle }
+                    </div>
+                }
+                if compound {
+                    { Self::view_comparison_expression(ctx, input_data, &my_index) }
+                }
                 if !cfg!(feature = "pumpkin") {
                     { self.view_required_msg(ctx, &my_index) }
                 }
@@ -152,6 +187,141 @@ where
         }
     }
 
+    fn view_comparison_text(
+        ctx: &Context<Self>,
+        input_data: &Rc<RefCell<InputItem>>,
+        data_id: &BigUint,
+        typed: &str,
+        error: Option<&ParseComparisonError>,
+        mode_toggle: Html,
+    ) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let (data_id, input_data) = (data_id.clone(), input_data.clone());
+        let oninput = ctx.link().callback(move |e: InputEvent| {
+            e.target()
+                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+                .map_or(Message::InputError, |input| {
+                    Message::InputComparisonText(data_id.clone(), input.value(), input_data.clone())
+                })
+        });
+        let error = error.map(|error| {
+            // the typed text with the character at the error marked
+            let chars = typed.chars().collect::<Vec<_>>();
+            let position = error.position().min(chars.len());
+            let before = chars[..position].iter().collect::<String>();
+            let at = chars
+                .get(position)
+                .map_or_else(|| "\u{a0}".to_string(), ToString::to_string);
+            let after = chars
+                .get(position + 1..)
+                .unwrap_or_default()
+                .iter()
+                .collect::<String>();
+            html! {
+                <div class="input-comparison-text-error">
+                    <div class="input-comparison-text-error-marker">
+                        { before }
+                        <span class="input-comparison-text-error-position">{ at }</span>
+                        { after }
+                    </div>
+                    <div class="input-comparison-text-error-msg">
+                        { translate(&txt, ctx.props().language, error.reason()) }
+                    </div>
+                </div>
+            }
+        });
+        html! {
+            <div class="input-comparison-text">
+                <div class="input-comparison">
+                    <input type="text"
+                        class={classes!("frontary-input-text", error.is_some().then_some("input-comparison-text-invalid"))}
+                        placeholder="10 < x <= 20"
+                        oninput={oninput}
+                        value={typed.to_string()}
+                    />
+                    { mode_toggle }
+                </div>
+                { error.unwrap_or_default() }
+            </div>
+        }
+    }
+
+    /// Switches a comparison input between the form and text modes. The text
+    /// starts as the comparison being edited.
+    pub(super) fn toggle_comparison_text_mode(
+        &mut self,
+        data_id: &BigUint,
+        input_data: &Rc<RefCell<InputItem>>,
+    ) {
+        if self.comparison_text_buffer.remove(data_id).is_some() {
+            return;
+        }
+        let typed = match input_data.try_borrow().as_deref() {
+            Ok(InputItem::Comparison(data)) => data
+                .as_ref()
+                .map(|comparison| ComparisonExpression::Predicate(comparison.clone()).to_string()),
+            _ => None,
+        };
+        self.comparison_text_buffer
+            .insert(data_id.clone(), (typed.unwrap_or_default(), None));
+    }
+
+    /// Parses the text typed in text mode, and updates the comparison and the
+    /// form with it if it is valid.
+    pub(super) fn input_comparison_text(
+        &mut self,
+        data_id: &BigUint,
+        typed: String,
+        input_data: &Rc<RefCell<InputItem>>,
+    ) {
+        let (comparison, error) = if typed.trim().is_empty() {
+            (None, None)
+        } else {
+            match parse_offered_comparison(&typed) {
+                Ok(comparison) => (Some(comparison), None),
+                Err(error) => (None, Some(error)),
+            }
+        };
+        if let Some(comparison) = &comparison {
+            self.comparison_to_form(data_id, comparison);
+            self.required_msg.remove(data_id);
+        } else {
+            self.clear_comparison_value(data_id, input_data);
+        }
+        if let Ok(mut data) = input_data.try_borrow_mut()
+            && let InputItem::Comparison(data) = &mut *data
+        {
+            **data = comparison;
+        }
+        self.comparison_text_buffer
+            .insert(data_id.clone(), (typed, error));
+    }
+
+    /// Selects the kinds and fills in the values of `comparison` in the form.
+    fn comparison_to_form(&self, data_id: &BigUint, comparison: &Comparison) {
+        let select = |buffer: Option<&Rc<RefCell<Option<HashSet<String>>>>>, key: String| {
+            if let Some(mut buffer) = buffer.and_then(|b| b.try_borrow_mut().ok()) {
+                *buffer = Some(HashSet::from([key]));
+            }
+        };
+        select(
+            self.comparison_value_kind_buffer.get(data_id),
+            comparison.value_kind().to_string(),
+        );
+        select(
+            self.comparison_value_cmp_buffer.get(data_id),
+            comparison.comparison_kind().to_string(),
+        );
+        if let Some((first, second)) = self.comparison_value_buffer.get(data_id) {
+            if let Ok(mut first) = first.try_borrow_mut() {
+                *first = Some(comparison.first());
+            }
+            if let Ok(mut second) = second.try_borrow_mut() {
+                *second = comparison.second();
+            }
+        }
+    }
+
     fn view_comparison_value(
         &self,
         ctx: &Context<Self>,
@@ -434,8 +604,10 @@ where
             None
         };
 
-        if let Ok(mut data) = input_data.try_borrow_mut() {
-            *data = InputItem::Comparison(ComparisonItem::new(set));
+        if let Ok(mut data) = input_data.try_borrow_mut()
+            && let InputItem::Comparison(data) = &mut *data
+        {
+            **data = set;
         }
     }
 
@@ -497,24 +669,178 @@ where
             if let (Some(first), Some(second)) = (&*first, &*second)
                 && let Ok(data) = Comparison::try_new(cmp, first.clone(), Some(second.clone()))
             {
-                *input_data = ComparisonItem::new(Some(data));
+                **input_data = Some(data);
                 self.required_msg.remove(data_id);
             }
         } else if let Some(first) = &*first
             && let Ok(data) = Comparison::try_new(cmp, first.clone(), None)
         {
-            *input_data = ComparisonItem::new(Some(data));
+            **input_data = Some(data);
             self.required_msg.remove(data_id);
         }
     }
 
+    /// Adds the comparison being edited to the group at `path`, and clears
+    /// its values for the next comparison.
+    pub(super) fn add_comparison_predicate(
+        &mut self,
+        data_id: &BigUint,
+        path: &[usize],
+        input_data: &Rc<RefCell<InputItem>>,
+    ) {
+        let comparison = match input_data.try_borrow().as_deref() {
+            Ok(InputItem::Comparison(data)) => data.as_ref().cloned(),
+            _ => None,
+        };
+        let Some(comparison) = comparison else {
+            return;
+        };
+        edit_comparison_expression(input_data, |expression| {
+            expression.push(path, ComparisonExpression::Predicate(comparison));
+        });
+        self.clear_comparison_value(data_id, input_data);
+        if let Some(typed) = self.comparison_text_buffer.get_mut(data_id) {
+            *typed = (String::new(), None);
+        }
+        self.required_msg.remove(data_id);
+    }
+
+    fn view_comparison_expression(
+        ctx: &Context<Self>,
+        input_data: &Rc<RefCell<InputItem>>,
+        data_id: &BigUint,
+    ) -> Html {
+        let (root, can_add) = match input_data.try_borrow().as_deref() {
+            Ok(InputItem::Comparison(data)) => (
+                data.expression()
+                    .cloned()
+                    .unwrap_or_else(|| ComparisonExpression::And(Vec::new())),
+                data.is_some(),
+            ),
+            _ => return html! {},
+        };
+        html! {
+            <div class="input-comparison-expression">
+                { Self::view_comparison_expression_node(ctx, input_data, data_id, &root, &[], None, can_add) }
+            </div>
+        }
+    }
+
+    /// Shows the node at `path`. `negation` is the path of the NOT node
+    /// wrapping it, if any, which is shown as a toggle on the node itself.
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+    fn view_comparison_expression_node(
+        ctx: &Context<Self>,
+        input_data: &Rc<RefCell<InputItem>>,
+        data_id: &BigUint,
+        node: &ComparisonExpression,
+        path: &[usize],
+        negation: Option<&[usize]>,
+        can_add: bool,
+    ) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let language = ctx.props().language;
+        let message = |f: fn(BigUint, Vec<usize>, Rc<RefCell<InputItem>>) -> Message,
+                       path: &[usize]| {
+            let (data_id, path, input_data) = (data_id.clone(), path.to_vec(), input_data.clone());
+            ctx.link()
+                .callback(move |_| f(data_id.clone(), path.clone(), input_data.clone()))
+        };
+        let outer = negation.unwrap_or(path);
+        let not_toggle = html! {
+            <div class={classes!("input-comparison-expression-not", negation.is_some().then_some("active"))}
+                onclick={message(Message::InputComparisonToggleNot, outer)}
+            >
+                { "NOT" }
+            </div>
+        };
+        let remove = if outer.is_empty() {
+            html! {}
+        } else {
+            html! {
+                <div class="input-comparison-expression-remove"
+                    onclick={message(Message::InputComparisonRemove, outer)}
+                    title={translate(&txt, language, "Delete")}
+                />
+            }
+        };
+        match node {
+            ComparisonExpression::Not(child) => {
+                let child_path = [path, &[0]].concat();
+                Self::view_comparison_expression_node(
+                    ctx,
+                    input_data,
+                    data_id,
+                    child,
+                    &child_path,
+                    Some(path),
+                    can_add,
+                )
+            }
+            ComparisonExpression::Predicate(_) => html! {
+                <div class="input-comparison-expression-predicate">
+                    { not_toggle }
+                    <div class="input-comparison-expression-text">
+                        { node.to_string() }
+                    </div>
+                    { remove }
+                </div>
+            },
+            ComparisonExpression::And(children) | ComparisonExpression::Or(children) => {
+                let operator = if matches!(node, ComparisonExpression::And(_)) {
+                    "AND"
+                } else {
+                    "OR"
+                };
+                let onclick_add = if can_add {
+                    message(Message::InputComparisonAddPredicate, path)
+                } else {
+                    yew::Callback::noop()
+                };
+                html! {
+                    <div class="input-comparison-expression-group">
+                        <div class="input-comparison-expression-group-header">
+                            { not_toggle }
+                            <div class="input-comparison-expression-operator"
+                                onclick={message(Message::InputComparisonToggleOperator, path)}
+                            >
+                                { operator }
+                            </div>
+                            <div class={classes!("input-comparison-expression-add", (!can_add).then_some("disabled"))}
+                                onclick={onclick_add}
+                            >
+                                { translate(&txt, language, "Add condition") }
+                            </div>
+                            <div class="input-comparison-expression-add"
+                                onclick={message(Message::InputComparisonAddGroup, path)}
+                            >
+                                { translate(&txt, language, "Add group") }
+                            </div>
+                            { remove }
+                        </div>
+                        <div class="input-comparison-expression-children">
+                        {
+                            for children.iter().enumerate().map(|(index, child)| {
+                                let child_path = [path, &[index]].concat();
+                                Self::view_comparison_expression_node(ctx, input_data, data_id, child, &child_path, None, can_add)
+                            })
+                        }
+                        </div>
+                    </div>
+                }
+            }
+        }
+    }
+
     pub(super) fn clear_comparison_value(
         &self,
         data_id: &BigUint,
         input_data: &Rc<RefCell<InputItem>>,
     ) {
-        if let Ok(mut data) = input_data.try_borrow_mut() {
-            *data = InputItem::Comparison(ComparisonItem::new(None));
+        if let Ok(mut data) = input_data.try_borrow_mut()
+            && let InputItem::Comparison(data) = &mut *data
+        {
+            **data = None;
         }
         let Some((first, second)) = self.comparison_value_buffer.get(data_id) else {
             return;
@@ -528,20 +854,67 @@ where
     }
 }
 
+/// Applies `edit` to the expression of a comparison input, starting from an
+/// empty AND group if there is none.
+pub(super) fn edit_comparison_expression(
+    input_data: &Rc<RefCell<InputItem>>,
+    edit: impl FnOnce(&mut ComparisonExpression),
+) {
+    if let Ok(mut data) = input_data.try_borrow_mut()
+        && let InputItem::Comparison(data) = &mut *data
+    {
+        edit(
+            data.expression_mut()
+                .get_or_insert_with(|| ComparisonExpression::And(Vec::new())),
+        );
+    }
+}
+
+/// Returns whether the form offers the kinds of `comparison`.
+fn is_offered(comparison: &Comparison) -> bool {
+    let contain = matches!(
+        comparison.comparison_kind(),
+        ComparisonKind::Contain | ComparisonKind::NotContain
+    );
+    match comparison.value_kind() {
+        ValueKind::String => contain,
+        ValueKind::Integer | ValueKind::Float => !contain,
+        _ => false,
+    }
+}
+
+/// Parses a comparison of the kinds the form offers, e.g. `x Contains 10` as a
+/// string rather than an integer.
+///
+/// If none of them fits, the error is at the furthest position any of them
+/// could be parsed to, usually the value of an unsupported kind.
+fn parse_offered_comparison(typed: &str) -> Result<Comparison, ParseComparisonError> {
+    let comparison = typed.parse::<Comparison>()?;
+    if is_offered(&comparison) {
+        return Ok(comparison);
+    }
+    let mut position = 0;
+    for kind in [ValueKind::String, ValueKind::Integer, ValueKind::Float] {
+        match Comparison::parse_with_kind(typed, kind) {
+            Ok(comparison) if is_offered(&comparison) => return Ok(comparison),
+            Ok(_) => (),
+            Err(error) => position = position.max(error.position()),
+        }
+    }
+    Err(ParseComparisonError::new(
+        position,
+        "Unsupported comparison",
+    ))
+}
+
 #[inline]
 fn value_kind(kind: ValueKind) -> Item {
-    Item {
-        id: kind.to_string(),
-        value: ViewString::Raw(kind.to_string()),
-    }
+    Item::new(kind.to_string(), ViewString::Raw(kind.to_string()))
 }
 
 #[inline]
 fn cmp_kind(kind: ComparisonKind) -> Item {
-    Item {
-        id: kind.to_string(),
-        value: ViewString::Raw(kind.to_string()),
-    }
+    Item::new(kind.to_string(), ViewString::Raw(kind.to_string()))
 }
 
 #[inline]
@@ -583,3 +956,21 @@ fn cmp_statement_symbol(kind: ComparisonKind) -> &'static str {
         ComparisonKind::NotContain => " !Contains ",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_offered_comparison;
+    use crate::{Comparison, input::Value};
+
+    #[test]
+    fn offered_comparison() {
+        assert_eq!(
+            parse_offered_comparison("x Contains 10"),
+            Ok(Comparison::Contain(Value::String(Some("10".to_string()))))
+        );
+        let position = |text: &str| parse_offered_comparison(text).map_err(|e| e.position());
+        assert_eq!(position("x < \"a\""), Err(4));
+        assert_eq!(position("x = 10.0.0.1"), Err(4));
+        assert_eq!(position("0 < x < true"), Err(8));
+    }
+}
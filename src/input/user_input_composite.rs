@@ -679,7 +679,7 @@ where
                                                                         InputConfig::Comparison(config) => {
                                                                             let mut ess = config.ess.clone();
                                                                             ess.required = false;
-                                                                            self.view_comparison(ctx, &ess, each_item, Some(&row_rep_index), col_index, true)
+                                                                            self.view_comparison(ctx, &ess, config.compound, config.text, each_item, Some(&row_rep_index), col_index, true)
                                                                         }
                                                                         InputConfig::VecSelect(config) => {
                                                                             self.view_vec_select(ctx, &config.ess, &config.items_ess_list, config.last, config.full_width, &config.widths, &config.max_widths,
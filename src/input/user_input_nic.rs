@@ -1,21 +1,40 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::{cell::RefCell, net::Ipv4Addr, rc::Rc, str::FromStr};
 
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 use json_gettext::get_text;
 use num_bigint::BigUint;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
-use yew::{Component, Context, Html, classes, events::InputEvent, html};
+use yew::{Callback, Component, Context, Html, classes, events::InputEvent, html};
 
 use super::{
-    InputItem, cal_index,
+    InputItem, NicConfig, cal_index,
     component::{InvalidMessage, Message, Model},
     user_input::view_asterisk,
 };
-use crate::{InputEssential, InputNic, Theme, ViewString, input::component::Verification, text};
+use crate::{
+    InputEssential, InputNic, Theme, ViewString, input::component::Verification,
+    language::translate, text,
+};
 
 const INTERFACE_NOTICE: &str = "x.x.x.x/x";
 const GATEWAY_NOTICE: &str = "x.x.x.x";
+const IPV6_NOTICE: &str = "x:x::x/x";
+const MTU_NOTICE: &str = "1500";
+const VLAN_ID_NOTICE: &str = "1-4094";
+
+// the fields of a NIC in the keys of `Model::verification_nic`
+pub(super) const NAME: usize = 0;
+pub(super) const INTERFACE: usize = 1;
+pub(super) const GATEWAY: usize = 2;
+pub(super) const IPV6: usize = 3;
+pub(super) const MTU: usize = 4;
+pub(super) const VLAN_ID: usize = 5;
+
+const MIN_MTU: u16 = 68;
+const VLAN_IDS: std::ops::RangeInclusive<u16> = 1..=4094;
+
+type NicMessage = fn(BigUint, usize, String, Rc<RefCell<InputItem>>) -> Message;
 
 impl<T> Model<T>
 where
@@ -25,13 +44,26 @@ where
     pub(super) fn view_nic(
         &self,
         ctx: &Context<Self>,
-        ess: &InputEssential,
+        config: &NicConfig,
         input_data: &Rc<RefCell<InputItem>>,
         base_index: Option<&BigUint>,
         layer_index: usize,
     ) -> Html {
         let txt = ctx.props().txt.txt.clone();
+        let language = ctx.props().language;
         let input_data_clone = input_data.clone();
+        let data_id = cal_index(base_index, layer_index);
+        let optional_heading = |enabled: bool, class: &'static str, title: &str| {
+            if enabled {
+                html! {
+                    <th class={classes!("input-nic-heading", "input-nic-border", class)}>
+                        { translate(&txt, language, title) }
+                    </th>
+                }
+            } else {
+                html! {}
+            }
+        };
 
         if let Ok(input_data) = input_data.try_borrow() {
             if let InputItem::Nic(input_data) = &*input_data {
@@ -39,19 +71,22 @@ where
                 html! {
                     <div class="input-item">
                         <div class="input-contents-item-title">
-                            { text!(txt, ctx.props().language, ess.title()) }{ view_asterisk(ess.required) }
+                            { text!(txt, language, config.ess.title()) }{ view_asterisk(config.ess.required) }
                         </div>
                         <table class="input-nic">
                             <tr>
                                 <th class={classes!("input-nic-heading", "input-nic-heading-name")}>
-                                    { text!(txt, ctx.props().language, "Interface Name") }
+                                    { text!(txt, language, "Interface Name") }
                                 </th>
                                 <th class={classes!("input-nic-heading", "input-nic-border", "input-nic-heading-ip")}>
-                                    { text!(txt, ctx.props().language, "IP Address of Interface") }
+                                    { text!(txt, language, "IP Address of Interface") }
                                 </th>
                                 <th class={classes!("input-nic-heading", "input-nic-border", "input-nic-heading-ip")}>
-                                    { text!(txt, ctx.props().language, "IP Address of Gateway") }
+                                    { text!(txt, language, "IP Address of Gateway") }
                                 </th>
+                                { optional_heading(config.ipv6, "input-nic-heading-ip", "IPv6 Address of Interface") }
+                                { optional_heading(config.mtu, "input-nic-heading-number", "MTU") }
+                                { optional_heading(config.vlan_id, "input-nic-heading-number", "VLAN ID") }
                                 <th class="input-nic-heading-delete">
                                 </th>
                                 <th class="input-nic-heading-add">
@@ -60,11 +95,11 @@ where
 
                         {
                             for input_data.iter().enumerate().map(|(index, d)| {
-                                self.view_nic_each(ctx, &input_data_clone, index, base_index, layer_index, index + 1 == num, d)
+                                self.view_nic_each(ctx, config, &input_data_clone, index, &data_id, index + 1 == num, d)
                             })
                         }
                         </table>
-                        { self.view_required_msg(ctx, &cal_index(base_index, layer_index)) }
+                        { self.view_required_msg(ctx, &data_id) }
                     </div>
                 }
             } else {
@@ -156,110 +191,98 @@ where
     fn view_nic_each(
         &self,
         ctx: &Context<Self>,
+        config: &NicConfig,
         input_data: &Rc<RefCell<InputItem>>,
         nic_index: usize,
-        base_index: Option<&BigUint>,
-        layer_index: usize,
+        data_id: &BigUint,
         is_last: bool,
         nic: &InputNic,
     ) -> Html {
-        let my_index = cal_index(base_index, layer_index) + nic_index;
-        let input_data_clone_1 = input_data.clone();
-        let input_data_clone_2 = input_data.clone();
-        let input_data_clone_3 = input_data.clone();
-        let input_data_clone_4 = input_data.clone();
-        let input_data_clone_5 = input_data.clone();
-        let my_index_clone_1 = my_index.clone();
-        let my_index_clone_2 = my_index.clone();
-        let my_index_clone_3 = my_index.clone();
-        let my_index_clone_4 = my_index.clone();
-        let my_index_clone_5 = my_index.clone();
-
-        let oninput_name = ctx.link().callback(move |e: InputEvent| {
-            e.target()
-                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
-                .map_or(Message::InputError, |input| {
-                    Message::InputNicName(
-                        my_index_clone_1.clone(),
-                        nic_index,
-                        input.value(),
-                        input_data_clone_1.clone(),
-                    )
-                })
-        });
-        let oninput_interface = ctx.link().callback(move |e: InputEvent| {
-            e.target()
-                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
-                .map_or(Message::InputError, |input| {
-                    Message::InputNicInterface(
-                        my_index_clone_2.clone(),
-                        nic_index,
-                        input.value(),
-                        input_data_clone_2.clone(),
-                    )
-                })
-        });
-        let oninput_gateway = ctx.link().callback(move |e: InputEvent| {
-            e.target()
-                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
-                .map_or(Message::InputError, |input| {
-                    Message::InputNicGateway(
-                        my_index_clone_3.clone(),
-                        nic_index,
-                        input.value(),
-                        input_data_clone_3.clone(),
-                    )
-                })
-        });
-        let onclick_delete = ctx.link().callback(move |_| {
-            Message::InputNicDelete(
-                my_index_clone_4.clone(),
-                nic_index,
-                input_data_clone_4.clone(),
-            )
-        });
-        let onclick_add = ctx.link().callback(move |_| {
-            Message::InputNicAdd(
-                my_index_clone_5.clone(),
-                nic_index,
-                input_data_clone_5.clone(),
-            )
-        });
+        let oninput = |message: NicMessage| {
+            let (data_id, input_data) = (data_id.clone(), input_data.clone());
+            ctx.link().callback(move |e: InputEvent| {
+                e.target()
+                    .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+                    .map_or(Message::InputError, |input| {
+                        message(
+                            data_id.clone(),
+                            nic_index,
+                            input.value(),
+                            input_data.clone(),
+                        )
+                    })
+            })
+        };
+        let onclick = |message: fn(BigUint, usize, Rc<RefCell<InputItem>>) -> Message| {
+            let (data_id, input_data) = (data_id.clone(), input_data.clone());
+            ctx.link()
+                .callback(move |_| message(data_id.clone(), nic_index, input_data.clone()))
+        };
         let txt = ctx.props().txt.txt.clone();
-        let name_holder = text!(txt, ctx.props().language, "Name").to_string();
+        let language = ctx.props().language;
+        let name_holder = text!(txt, language, "Name").to_string();
 
-        let (name_msg, interface_msg, gateway_msg) = (
-            self.verification_nic
-                .get(&(cal_index(Some(&my_index), nic_index), 0)),
-            self.verification_nic
-                .get(&(cal_index(Some(&my_index), nic_index), 1)),
-            self.verification_nic
-                .get(&(cal_index(Some(&my_index), nic_index), 2)),
-        );
-        let name_msg =
-            if let Some(Verification::Invalid(InvalidMessage::InterfaceNameRequired)) = name_msg {
-                Some("Required")
-            } else {
-                None
-            };
-        let interface_msg = if let Some(Verification::Invalid(InvalidMessage::InterfaceRequired)) =
-            interface_msg
-        {
-            Some("Required")
-        } else if let Some(Verification::Invalid(InvalidMessage::WrongInterface)) = interface_msg {
-            Some("Wrong input")
-        } else {
-            None
+        let nic_id = cal_index(Some(data_id), nic_index);
+        let msg = |field: usize| {
+            nic_message(self.verification_nic.get(&(nic_id.clone(), field)))
+                .map(|m| translate(&txt, language, m))
         };
-        let gateway_msg =
-            if let Some(Verification::Invalid(InvalidMessage::GatewayRequired)) = gateway_msg {
-                Some("Required")
-            } else if let Some(Verification::Invalid(InvalidMessage::WrongGateway)) = gateway_msg {
-                Some("Wrong input")
-            } else {
-                None
-            };
-        let msg = name_msg.is_some() || interface_msg.is_some() || gateway_msg.is_some();
+        let fields = [
+            (
+                NAME,
+                true,
+                "name",
+                nic.name.clone(),
+                name_holder,
+                Message::InputNicName as NicMessage,
+            ),
+            (
+                INTERFACE,
+                true,
+                "interface",
+                nic.interface.clone(),
+                INTERFACE_NOTICE.to_string(),
+                Message::InputNicInterface,
+            ),
+            (
+                GATEWAY,
+                true,
+                "gateway",
+                nic.gateway.clone(),
+                GATEWAY_NOTICE.to_string(),
+                Message::InputNicGateway,
+            ),
+            (
+                IPV6,
+                config.ipv6,
+                "ipv6",
+                nic.ipv6.clone(),
+                IPV6_NOTICE.to_string(),
+                Message::InputNicIpv6,
+            ),
+            (
+                MTU,
+                config.mtu,
+                "mtu",
+                nic.mtu.clone(),
+                MTU_NOTICE.to_string(),
+                Message::InputNicMtu,
+            ),
+            (
+                VLAN_ID,
+                config.vlan_id,
+                "vlan-id",
+                nic.vlan_id.clone(),
+                VLAN_ID_NOTICE.to_string(),
+                Message::InputNicVlanId,
+            ),
+        ];
+        let msgs = fields
+            .iter()
+            .map(|(field, enabled, ..)| if *enabled { msg(*field) } else { None })
+            .collect::<Vec<_>>();
+        // every cell leaves room for a message if any cell has one
+        let show_msg = msgs.iter().any(Option::is_some);
         let (class, class_delete) = if is_last {
             ("input-nic-input-last", "input-nic-delete-last")
         } else {
@@ -269,78 +292,16 @@ where
         html! {
             <>
                 <tr>
-                    <td class={class}>
-                        <div class="input-nic-input-outer">
-                            <div class="input-nic-input-name">
-                                <input type="text"
-                                    class={classes!("input-nic", "input-nic-name")}
-                                    value={nic.name.clone()}
-                                    placeholder={name_holder}
-                                    oninput={oninput_name}
-                                />
-                            {
-                                if msg {
-                                    html! {
-                                        <div class="input-nic-msg">
-                                            { name_msg.map_or_else(String::new, |m| text!(txt, ctx.props().language, m).to_string()) }
-                                        </div>
-                                    }
-                                } else {
-                                    html! {}
-                                }
-                            }
-                            </div>
-                        </div>
-                    </td>
-                    <td class={class}>
-                        <div class="input-nic-input-outer">
-                            <div class="input-nic-input-interface">
-                                <input type="text"
-                                    class={classes!("input-nic", "input-nic-interface")}
-                                    value={nic.interface.clone()}
-                                    placeholder={INTERFACE_NOTICE}
-                                    oninput={oninput_interface}
-                                />
-                            {
-                                if msg {
-                                    html! {
-                                        <div class="input-nic-msg">
-                                            { interface_msg.map_or_else(String::new, |m| text!(txt, ctx.props().language, m).to_string()) }
-                                        </div>
-                                    }
-                                } else {
-                                    html! {}
-                                }
-                            }
-                            </div>
-                        </div>
-                    </td>
-                    <td class={class}>
-                        <div class="input-nic-input-outer">
-                            <div class="input-nic-input-gateway">
-                                <input type="text"
-                                    class={classes!("input-nic", "input-nic-gateway")}
-                                    placeholder={GATEWAY_NOTICE}
-                                    value={nic.gateway.clone()}
-                                    oninput={oninput_gateway}
-                                />
-                                {
-                                    if msg {
-                                        html! {
-                                            <div class="input-nic-msg">
-                                                { gateway_msg.map_or_else(String::new, |m| text!(txt, ctx.props().language, m).to_string()) }
-                                            </div>
-                                        }
-                                    } else {
-                                        html! {}
-                                    }
-                                }
-                            </div>
-                        </div>
-                    </td>
+                {
+                    for fields.into_iter().zip(msgs).filter(|((_, enabled, ..), _)| *enabled).map(
+                        |((_, _, name, value, placeholder, message), msg)| {
+                            view_nic_cell(class, name, value, placeholder, oninput(message), show_msg, msg)
+                        },
+                    )
+                }
                     <td class={class_delete}>
                         <div class="input-nic-delete-outer">
-                            <div class="input-nic-delete" onclick={onclick_delete}>
+                            <div class="input-nic-delete" onclick={onclick(Message::InputNicDelete)}>
                             </div>
                         </div>
                     </td>
@@ -348,8 +309,8 @@ where
                     {
                         if is_last {
                             html! {
-                                <div class="input-add-item" onclick={onclick_add}>
-                                    { text!(txt, ctx.props().language, "+ Add") }
+                                <div class="input-add-item" onclick={onclick(Message::InputNicAdd)}>
+                                    { text!(txt, language, "+ Add") }
                                 </div>
                             }
                         } else {
@@ -362,3 +323,258 @@ where
         }
     }
 }
+
+/// Shows the input of a field of a NIC. `show_msg` leaves room for a message
+/// even if this field has none, so that the fields of a NIC stay aligned.
+fn view_nic_cell(
+    class: &'static str,
+    name: &'static str,
+    value: String,
+    placeholder: String,
+    oninput: Callback<InputEvent>,
+    show_msg: bool,
+    msg: Option<String>,
+) -> Html {
+    html! {
+        <td class={class}>
+            <div class="input-nic-input-outer">
+                <div class={format!("input-nic-input-{name}")}>
+                    <input type="text"
+                        class={classes!("input-nic", format!("input-nic-{name}"))}
+                        value={value}
+                        placeholder={placeholder}
+                        oninput={oninput}
+                    />
+                {
+                    if show_msg {
+                        html! {
+                            <div class="input-nic-msg">
+                                { msg.unwrap_or_default() }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                </div>
+            </div>
+        </td>
+    }
+}
+
+fn nic_message(verification: Option<&Verification>) -> Option<&'static str> {
+    let Some(Verification::Invalid(message)) = verification else {
+        return None;
+    };
+    match message {
+        InvalidMessage::InterfaceNameRequired
+        | InvalidMessage::InterfaceRequired
+        | InvalidMessage::GatewayRequired => Some("Required"),
+        InvalidMessage::WrongInterface
+        | InvalidMessage::WrongGateway
+        | InvalidMessage::WrongIpv6Interface
+        | InvalidMessage::WrongMtu
+        | InvalidMessage::WrongVlanId => Some("Wrong input"),
+        InvalidMessage::DuplicateInterfaceName => Some("Duplicate name"),
+        InvalidMessage::OverlappingInterface => Some("Overlapping network"),
+        InvalidMessage::GatewayOutsideInterface => Some("Not in the interface network"),
+        InvalidMessage::GatewayReserved => Some("Network or broadcast address"),
+        _ => None,
+    }
+}
+
+/// Checks every NIC with any field filled in, and returns the problems found
+/// with the index of the NIC and the field.
+///
+/// Besides the format of each field, the gateway must be a host address in
+/// the network of the interface, names must be unique, and the networks of
+/// different NICs must not overlap.
+pub(super) fn verify_nics(
+    nics: &[InputNic],
+    config: &NicConfig,
+) -> Vec<(usize, usize, InvalidMessage)> {
+    let mut problems = Vec::new();
+    let mut networks: Vec<(usize, usize, IpNet)> = Vec::new();
+    for (i, nic) in nics.iter().enumerate() {
+        if [
+            &nic.name,
+            &nic.interface,
+            &nic.gateway,
+            &nic.ipv6,
+            &nic.mtu,
+            &nic.vlan_id,
+        ]
+        .iter()
+        .all(|field| field.is_empty())
+        {
+            continue;
+        }
+
+        if nic.name.is_empty() {
+            problems.push((i, NAME, InvalidMessage::InterfaceNameRequired));
+        } else if nics
+            .iter()
+            .enumerate()
+            .any(|(j, other)| j != i && other.name == nic.name)
+        {
+            problems.push((i, NAME, InvalidMessage::DuplicateInterfaceName));
+        }
+
+        let interface = if nic.interface.is_empty() {
+            problems.push((i, INTERFACE, InvalidMessage::InterfaceRequired));
+            None
+        } else if let Ok(interface) = Ipv4Net::from_str(&nic.interface) {
+            networks.push((i, INTERFACE, IpNet::V4(interface.trunc())));
+            Some(interface)
+        } else {
+            problems.push((i, INTERFACE, InvalidMessage::WrongInterface));
+            None
+        };
+
+        if nic.gateway.is_empty() {
+            problems.push((i, GATEWAY, InvalidMessage::GatewayRequired));
+        } else if let Ok(gateway) = Ipv4Addr::from_str(&nic.gateway) {
+            if let Some(interface) = interface {
+                if !interface.contains(&gateway) {
+                    problems.push((i, GATEWAY, InvalidMessage::GatewayOutsideInterface));
+                } else if interface.prefix_len() < 31
+                    && (gateway == interface.network() || gateway == interface.broadcast())
+                {
+                    // /31 and /32 networks have no network or broadcast address
+                    problems.push((i, GATEWAY, InvalidMessage::GatewayReserved));
+                }
+            }
+        } else {
+            problems.push((i, GATEWAY, InvalidMessage::WrongGateway));
+        }
+
+        if config.ipv6 && !nic.ipv6.is_empty() {
+            if let Ok(ipv6) = Ipv6Net::from_str(&nic.ipv6) {
+                networks.push((i, IPV6, IpNet::V6(ipv6.trunc())));
+            } else {
+                problems.push((i, IPV6, InvalidMessage::WrongIpv6Interface));
+            }
+        }
+        if config.mtu
+            && !nic.mtu.is_empty()
+            && !nic.mtu.parse::<u16>().is_ok_and(|mtu| mtu >= MIN_MTU)
+        {
+            problems.push((i, MTU, InvalidMessage::WrongMtu));
+        }
+        if config.vlan_id
+            && !nic.vlan_id.is_empty()
+            && !nic
+                .vlan_id
+                .parse::<u16>()
+                .is_ok_and(|id| VLAN_IDS.contains(&id))
+        {
+            problems.push((i, VLAN_ID, InvalidMessage::WrongVlanId));
+        }
+    }
+
+    for (i, field, network) in &networks {
+        if networks.iter().any(|(j, _, other)| {
+            j != i && (network.contains(&other.network()) || other.contains(&network.network()))
+        }) {
+            problems.push((*i, *field, InvalidMessage::OverlappingInterface));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GATEWAY, INTERFACE, IPV6, MTU, NAME, VLAN_ID, verify_nics};
+    use crate::{InputEssential, InputNic, NicConfig, input::component::InvalidMessage};
+
+    fn nic(name: &str, interface: &str, gateway: &str) -> InputNic {
+        InputNic {
+            name: name.to_string(),
+            interface: interface.to_string(),
+            gateway: gateway.to_string(),
+            ..InputNic::default()
+        }
+    }
+
+    fn config() -> NicConfig {
+        NicConfig {
+            ess: InputEssential {
+                title: String::new(),
+                notice: "",
+                required: false,
+            },
+            ipv6: true,
+            mtu: true,
+            vlan_id: true,
+        }
+    }
+
+    #[test]
+    fn gateway() {
+        let nics = [
+            nic("eth0", "192.168.0.10/24", "192.168.0.1"),
+            nic("eth1", "10.0.0.10/24", "10.0.1.1"),
+            nic("eth2", "172.16.0.10/24", "172.16.0.255"),
+            nic("eth3", "172.17.0.10/24", "172.17.0.0"),
+            nic("eth4", "172.18.0.0/31", "172.18.0.1"),
+            nic("eth5", "172.19.0.1/24", "x"),
+            InputNic::default(),
+        ];
+        assert_eq!(
+            verify_nics(&nics, &config()),
+            vec![
+                (1, GATEWAY, InvalidMessage::GatewayOutsideInterface),
+                (2, GATEWAY, InvalidMessage::GatewayReserved),
+                (3, GATEWAY, InvalidMessage::GatewayReserved),
+                (5, GATEWAY, InvalidMessage::WrongGateway),
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut nics = vec![
+            nic("eth0", "192.168.0.10/24", "192.168.0.1"),
+            nic("eth0", "10.0.0.10/8", "10.0.0.1"),
+            nic("eth2", "10.1.0.10/16", "10.1.0.1"),
+        ];
+        nics[0].ipv6 = "2001:db8::1/64".to_string();
+        nics[2].ipv6 = "2001:db8::2/48".to_string();
+        assert_eq!(
+            verify_nics(&nics, &config()),
+            vec![
+                (0, NAME, InvalidMessage::DuplicateInterfaceName),
+                (1, NAME, InvalidMessage::DuplicateInterfaceName),
+                (0, IPV6, InvalidMessage::OverlappingInterface),
+                (1, INTERFACE, InvalidMessage::OverlappingInterface),
+                (2, INTERFACE, InvalidMessage::OverlappingInterface),
+                (2, IPV6, InvalidMessage::OverlappingInterface),
+            ]
+        );
+    }
+
+    #[test]
+    fn options() {
+        let mut nics = vec![nic("eth0", "", ""), nic("eth1", "10.0.0.10/24", "10.0.0.1")];
+        nics[1].ipv6 = "2001:db8::1".to_string();
+        nics[1].mtu = "67".to_string();
+        nics[1].vlan_id = "4095".to_string();
+        assert_eq!(
+            verify_nics(&nics, &config()),
+            vec![
+                (0, INTERFACE, InvalidMessage::InterfaceRequired),
+                (0, GATEWAY, InvalidMessage::GatewayRequired),
+                (1, IPV6, InvalidMessage::WrongIpv6Interface),
+                (1, MTU, InvalidMessage::WrongMtu),
+                (1, VLAN_ID, InvalidMessage::WrongVlanId),
+            ]
+        );
+        nics[1].mtu = "9000".to_string();
+        nics[1].vlan_id = "100".to_string();
+        let disabled = NicConfig {
+            ipv6: false,
+            ..config()
+        };
+        assert_eq!(verify_nics(&nics, &disabled).len(), 2);
+    }
+}
@@ -44,10 +44,7 @@ where
         let list_clone = Rc::new(list.to_vec());
         let mut list = list
             .iter()
-            .map(|(id, value)| Item {
-                id: id.clone(),
-                value: value.clone(),
-            })
+            .map(|(id, value)| Item::new(id.clone(), value.clone()))
             .collect::<Vec<Item>>();
         list.sort_unstable_by(|a, b| {
             let a_v = a.value.to_string();
@@ -179,10 +176,7 @@ where
                             k.clone(),
                             Rc::new(RefCell::new(
                                 v.iter()
-                                    .map(|(a, b)| Item {
-                                        id: a.clone(),
-                                        value: b.clone(),
-                                    })
+                                    .map(|(a, b)| Item::new(a.clone(), b.clone()))
                                     .collect::<Vec<Item>>(),
                             )),
                         )
@@ -4,6 +4,7 @@
 //! for working with localized text in the UI.
 
 use gloo_storage::{LocalStorage, Result as GlooResult, Storage};
+use json_gettext::{JSONGetText, get_text};
 use serde::{Deserialize, Serialize};
 
 /// Type alias for text parsing results
@@ -95,6 +96,12 @@ pub fn set(lang: Language) {
     let _rtn = LocalStorage::set(STORAGE_KEY, lang);
 }
 
+/// Returns the text for `key` in `language`, or `key` itself if it has no
+/// translation.
+pub(crate) fn translate(txt: &JSONGetText<'static>, language: Language, key: &str) -> String {
+    get_text!(txt, language.tag(), key).map_or_else(|| key.to_string(), |text| text.to_string())
+}
+
 #[macro_export]
 macro_rules! text {
     ($c:ident, $l:expr, $k:expr) => {{
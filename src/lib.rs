@@ -34,6 +34,7 @@
 //! - [`Checkbox`] - Checkbox components with various states
 //! - [`Input`] - Generic input components and configurations
 //! - [`WholeList`] - Data list components with sorting and filtering
+//! - [`LayeredList`] - Data lists nested to any depth
 //! - [`Modal`] - Modal dialog components
 //! - [`Notification`] - Notification and alert components
 //! - [`language`] - Internationalization support
@@ -42,6 +43,7 @@
 
 mod checkbox;
 mod click_outside;
+mod dialog;
 mod input;
 mod ip_range_input;
 pub mod language;
@@ -53,6 +55,7 @@ mod password;
 mod port_range_input;
 mod radio;
 mod radio_separate;
+mod sanitize;
 mod select;
 mod sort;
 pub mod static_files;
@@ -81,15 +84,21 @@ pub use crate::click_outside::{
     ClickOutsideHandle, listen_click_outside, toggle_visibility, toggle_visibility_complex,
     visible_tag_select,
 };
+pub use crate::dialog::{
+    Dialog, Model as DialogProvider, PromptInput, Request as DialogRequest,
+    Response as DialogResponse, ResponseFuture as DialogResponseFuture,
+    Validator as PromptValidator, use_dialog,
+};
 pub use crate::input::{
     CheckboxChildrenConfig, CheckboxConfig, CheckboxItem, ChildrenPosition, Comparison,
-    ComparisonConfig, ComparisonItem, ComparisonKind, DomainNameConfig, DomainNameItem,
-    Essential as InputEssential, FileConfig, FileItem, Float64Config, Float64Item, GroupConfig,
-    GroupItem, HostNetworkGroupConfig, HostNetworkGroupItem, HostNetworkHtml, HostNetworkKind,
-    InputConfig, InputHostNetworkGroup, InputItem, InputNic, InputTag, InputTagGroup,
-    Model as Input, NicConfig, NicItem, PasswordConfig, PasswordItem, PercentageConfig,
-    PercentageItem, RadioConfig, RadioItem, SelectMultipleConfig, SelectMultipleItem,
-    SelectSingleConfig, SelectSingleItem, Tag, TagConfig, TagItem, TextConfig, TextItem,
+    ComparisonConfig, ComparisonExpression, ComparisonItem, ComparisonKind, DomainNameConfig,
+    DomainNameItem, ENCODING_VERSION, EncodingError, Essential as InputEssential, FileConfig,
+    FileItem, Float64Config, Float64Item, GroupConfig, GroupItem, HostNetworkGroupConfig,
+    HostNetworkGroupItem, HostNetworkHtml, HostNetworkKind, InputConfig, InputHostNetworkGroup,
+    InputItem, InputNic, InputTag, InputTagGroup, MatchError, Model as Input, NicConfig, NicItem,
+    ParseComparisonError, PasswordConfig, PasswordItem, PercentageConfig, PercentageItem,
+    RadioConfig, RadioItem, SelectMultipleConfig, SelectMultipleItem, SelectSingleConfig,
+    SelectSingleItem, Tag, TagConfig, TagInfo, TagItem, TagOperation, TextConfig, TextItem,
     Unsigned8Config, Unsigned8Item, Unsigned16Config, Unsigned16Item, Unsigned32Config,
     Unsigned32Item, Value as ComparisonValue, ValueKind, VecSelectConfig, VecSelectItem,
     gen_default_items_from_confs, invalid_password, view_asterisk,
@@ -97,18 +106,24 @@ pub use crate::input::{
 pub use crate::ip_range_input::Model as IpRangeInput;
 pub use crate::language::Language;
 pub use crate::list::{
-    CheckboxColumn, ColWidths, Column, ComparisonColumn, DataType, DisplayInfo, DomainNameColumn,
-    FileColumn, Float64Column, GroupColumn, HostNetworkGroupColumn, Kind, ListItem, MessageType,
-    ModalDisplay, NicColumn, PercentageColumn, RadioColumn, SelectMultipleColumn,
-    SelectSingleColumn, SortColumn, SortListKind, TagColumn, TextColumn, Unsigned8Column,
-    Unsigned16Column, Unsigned32Column, VecSelectColumn, WholeList,
+    Aggregation, AggregationScope, CheckboxColumn, ColWidths, Column, ComparisonColumn, DataType,
+    DisplayInfo, DomainNameColumn, FileColumn, Float64Column, GroupColumn, HostNetworkGroupColumn,
+    Kind, LayeredAction, LayeredItem, LayeredList, ListItem, ListLevel, MessageType, ModalDisplay,
+    NicColumn, PercentageColumn, RadioColumn, RowAction, SelectMultipleColumn, SelectSingleColumn,
+    SortColumn, SortListKind, TagColumn, TextColumn, Unsigned8Column, Unsigned16Column,
+    Unsigned32Column, VecSelectColumn, WholeList,
 };
 pub use crate::modal::{
     AlignButton as ModalAlign, Model as Modal, MsgType as ModalType, TextStyle as ModalTextStyle,
 };
 pub use crate::notification::{
-    Category as NotificationCategory, CommonError, Model as Notification, NotificationItem,
-    NotificationType, TIMEOUT_SECS, gen_notifications,
+    Action as NotificationAction, Category as NotificationCategory, CommonError,
+    DEFAULT_HISTORY_CAPACITY as NOTIFICATION_HISTORY_CAPACITY, DefaultErrorMapper, ErrorMapper,
+    GraphQLError, GraphQLErrorExtensions, History as NotificationHistory,
+    HistoryEntry as NotificationHistoryEntry, HistoryFilter as NotificationHistoryFilter,
+    HistoryPanel as NotificationHistoryPanel, HttpStatusClass, Model as Notification,
+    NotificationItem, NotificationType, PathSegment as GraphQLPathSegment, ResponseError,
+    TIMEOUT_SECS, gen_error_notification, gen_notifications,
 };
 pub use crate::pages::{Info as PagesInfo, Model as Pages};
 pub(crate) use crate::password::{PASSWORD_MIN_LEN, is_adjacent};
@@ -116,9 +131,12 @@ pub use crate::password::{Requirement as PasswordRequirement, check_password_req
 pub use crate::port_range_input::{Model as PortRangeInput, PortRange};
 pub use crate::radio::Model as Radio;
 pub use crate::radio_separate::Model as RadioSeparate;
+pub use crate::sanitize::sanitize_html;
 pub use crate::select::complex::{Kind as SelectComplexKind, Model as SelectComplex};
 pub use crate::select::mini::{Kind as SelectMiniKind, Model as SelectMini};
-pub use crate::select::searchable::{Kind as SelectSearchableKind, Model as SelectSearchable};
+pub use crate::select::searchable::{
+    Kind as SelectSearchableKind, Model as SelectSearchable, RemoteQuery as SelectRemoteQuery,
+};
 pub use crate::select::vec_searchable::Model as VecSelect;
 pub use crate::sort::{Model as Sort, Status as SortStatus};
 pub use crate::tab_menu::Model as TabMenu;
@@ -129,7 +147,7 @@ pub use crate::theme::Theme;
 ///
 /// Used by the password validation system to indicate specific reasons
 /// why a password failed validation.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum InvalidPasswordKind {
     /// Password contains whitespace characters
     HasSpace,
@@ -164,13 +182,20 @@ pub fn alert(msg: &str) {
 
 /// Actions available in context menus and action buttons.
 ///
-/// Used throughout the UI to represent common user actions.
+/// Used throughout the UI to represent common user actions. More actions may
+/// be added, so a `match` on it needs a wildcard arm.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum MoreAction {
     /// Edit the selected item
     Edit,
     /// Delete the selected item
     Delete,
+    /// An action defined by users, identified by its id. The id is a
+    /// `&'static str` so that `MoreAction` stays `Copy`; ids are meant to be
+    /// constants of the application, and data known only at runtime, such as
+    /// the key of a row, is passed along with the action instead.
+    Custom(&'static str),
 }
 
 /// Binary state actions for toggleable components.
@@ -189,7 +214,7 @@ pub enum OnOffAction {
 /// Used throughout the UI to support internationalization. The string
 /// can either be a key that gets looked up in translation files or
 /// raw text that is displayed as-is.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ViewString {
     /// A translation key to be looked up in language files
     Key(String),
@@ -750,6 +775,10 @@ pub struct Item {
     id: String,
     /// Display value (translation key or raw text)
     value: ViewString,
+    /// Identifier of the parent item in a tree of options
+    parent: Option<String>,
+    /// Header of the group the item is listed under
+    group: Option<ViewString>,
 }
 
 impl Item {
@@ -761,7 +790,32 @@ impl Item {
     /// * `value` - Display value (translation key or raw text)
     #[must_use]
     pub fn new(id: String, value: ViewString) -> Self {
-        Self { id, value }
+        Self {
+            id,
+            value,
+            parent: None,
+            group: None,
+        }
+    }
+
+    /// Places the item under the item whose identifier is `parent`.
+    ///
+    /// Select components show such items as a tree that can be expanded and
+    /// collapsed.
+    #[must_use]
+    pub fn with_parent(mut self, parent: String) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Lists the item under the header `group`.
+    ///
+    /// Only the group of a top-level item is used; its descendants are listed
+    /// with it.
+    #[must_use]
+    pub fn with_group(mut self, group: ViewString) -> Self {
+        self.group = Some(group);
+        self
     }
 
     /// Returns the item's unique identifier.
@@ -789,6 +843,18 @@ impl Item {
     pub fn value_txt(&self, txt: &JSONGetText<'static>, language: Language) -> String {
         self.value.to_string_txt(txt, language)
     }
+
+    /// Returns the identifier of the parent item, if any.
+    #[must_use]
+    pub fn parent(&self) -> Option<&String> {
+        self.parent.as_ref()
+    }
+
+    /// Returns the header of the group the item is listed under, if any.
+    #[must_use]
+    pub fn group(&self) -> Option<&ViewString> {
+        self.group.as_ref()
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
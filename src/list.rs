@@ -1,4 +1,5 @@
 #![allow(clippy::module_name_repetitions)]
+mod layered;
 mod whole;
 
 use std::{
@@ -8,16 +9,30 @@ use std::{
 
 use itertools::Itertools;
 use jiff::Timestamp;
-pub use whole::{MessageType, Model as WholeList, SortColumn, SortListKind};
+pub use layered::{LayeredAction, LayeredItem, ListLevel, Model as LayeredList};
+pub use whole::{
+    Aggregation, AggregationScope, MessageType, Model as WholeList, RowAction, SortColumn,
+    SortListKind,
+};
 
 use crate::{
     Theme, ViewString,
     checkbox::CheckStatus,
-    input::{Comparison, InputNic},
+    input::{Comparison, ComparisonExpression, InputNic},
 };
 
 const NUM_OF_DECIMALS_DEFAULT: usize = 2;
 
+#[cfg(not(feature = "test"))]
+const DEFAULT_NUM_PER_PAGE: usize = 10;
+#[cfg(not(feature = "test"))]
+const DEFAULT_NUM_PAGES: usize = 10;
+
+#[cfg(feature = "test")]
+const DEFAULT_NUM_PER_PAGE: usize = 2;
+#[cfg(feature = "test")]
+const DEFAULT_NUM_PAGES: usize = 2;
+
 #[derive(Clone, PartialEq)]
 pub struct ListItem {
     pub columns: Vec<Column>,
@@ -96,6 +111,8 @@ pub struct FileColumn {
 #[derive(Clone, PartialEq)]
 pub struct ComparisonColumn {
     pub comparison: Option<Comparison>,
+    /// Displayed instead of `comparison` if present
+    pub expression: Option<ComparisonExpression>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -214,7 +231,9 @@ impl std::fmt::Display for Column {
             }
             Self::File(d) => write!(formatter, "{}", d.filename),
             Self::Comparison(d) => {
-                if let Some(d) = d.comparison.as_ref() {
+                if let Some(d) = d.expression.as_ref() {
+                    write!(formatter, "{d}")
+                } else if let Some(d) = d.comparison.as_ref() {
                     write!(formatter, "{d}")
                 } else {
                     Ok(())
@@ -230,6 +249,12 @@ impl std::fmt::Display for Column {
     }
 }
 
+/// The kind of `WholeList`.
+///
+/// `LayeredFirst` and `LayeredSecond` are limited to two levels. They are
+/// superseded by `LayeredList` and will be deprecated along with the props of
+/// `WholeList` for the second level, such as `title_second` and
+/// `input_second_data`.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Kind {
     Flat,
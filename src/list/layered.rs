@@ -0,0 +1,321 @@
+//! A list whose rows can have rows of the next level, to any depth.
+//!
+//! Unlike `WholeList` with `Kind::LayeredFirst` and `Kind::LayeredSecond`,
+//! which is limited to two levels with separate props for each, every level of
+//! `LayeredList` is described by a `ListLevel` of its own.
+
+mod component;
+mod view;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+pub use component::Model;
+use jiff::Timestamp;
+
+use super::{Column, DisplayInfo, SortColumn};
+use crate::{CheckStatus, InputConfig, InputItem, PagesInfo, SortStatus};
+
+/// The settings of a level of `LayeredList`
+#[derive(Clone, PartialEq)]
+pub struct ListLevel {
+    /// Only the first line of `widths` is shown.
+    pub display_info: Rc<DisplayInfo>,
+    pub input_conf: Vec<Rc<InputConfig>>,
+    /// Filled by users before `LayeredAction::Add` or `LayeredAction::Edit`
+    /// of a row in this level is sent
+    pub input_data: Vec<Rc<RefCell<InputItem>>>,
+    /// The text key of the title of the add window, also shown on the add
+    /// button of this level
+    pub input_add_title: &'static str,
+    pub input_edit_title: &'static str,
+    pub input_width: u32,
+    pub input_height: u32,
+    pub num_per_page: usize,
+    /// The initial sort. `None` means the latest first.
+    pub sort: Option<SortColumn>,
+}
+
+/// A row of `LayeredList` with the rows of the next level
+#[derive(Clone, Default, PartialEq)]
+pub struct LayeredItem {
+    pub columns: Vec<Column>,
+    pub children: HashMap<String, LayeredItem>, // (key of a row, item of the row)
+    pub creation_time: Option<Timestamp>,
+}
+
+/// A change requested by users in `LayeredList`.
+///
+/// A row is identified by its path, the keys of its ancestors from the top
+/// level followed by its own key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayeredAction {
+    /// Adds a row with `ListLevel::input_data` under the row at the path. An
+    /// empty path means the top level.
+    Add(Vec<String>),
+    /// Replaces the row at the path with `ListLevel::input_data`.
+    Edit(Vec<String>),
+    /// Deletes the rows at the paths along with their descendants.
+    Delete(Vec<Vec<String>>),
+}
+
+/// Returns the rows under the row at the path. An empty path means the top
+/// level.
+fn children_at<'a>(
+    data: &'a HashMap<String, LayeredItem>,
+    path: &[String],
+) -> Option<&'a HashMap<String, LayeredItem>> {
+    path.iter()
+        .try_fold(data, |items, key| items.get(key).map(|item| &item.children))
+}
+
+fn find<'a>(data: &'a HashMap<String, LayeredItem>, path: &[String]) -> Option<&'a LayeredItem> {
+    let (key, parent) = path.split_last()?;
+    children_at(data, parent)?.get(key)
+}
+
+/// Returns the check status of a row. Only the rows without children are
+/// checked themselves, and the status of the others follows their children.
+fn check_status(
+    checked: &HashSet<Vec<String>>,
+    path: &[String],
+    item: &LayeredItem,
+) -> CheckStatus {
+    if item.children.is_empty() {
+        return if checked.contains(path) {
+            CheckStatus::Checked
+        } else {
+            CheckStatus::Unchecked
+        };
+    }
+    group_check_status(checked, path, &item.children)
+}
+
+/// Returns the check status of all the rows under the parent path.
+fn group_check_status(
+    checked: &HashSet<Vec<String>>,
+    parent: &[String],
+    items: &HashMap<String, LayeredItem>,
+) -> CheckStatus {
+    let mut path = parent.to_vec();
+    let mut statuses = items.iter().map(|(key, item)| {
+        path.push(key.clone());
+        let status = check_status(checked, &path, item);
+        path.pop();
+        status
+    });
+    let Some(first) = statuses.next() else {
+        return CheckStatus::Unchecked;
+    };
+    if first == CheckStatus::Indeterminate {
+        return first;
+    }
+    if statuses.all(|s| s == first) {
+        first
+    } else {
+        CheckStatus::Indeterminate
+    }
+}
+
+/// Checks or unchecks the row at the path and all its descendants.
+fn set_checked(
+    checked: &mut HashSet<Vec<String>>,
+    path: &mut Vec<String>,
+    item: &LayeredItem,
+    check: bool,
+) {
+    if item.children.is_empty() {
+        if check {
+            checked.insert(path.clone());
+        } else {
+            checked.remove(path.as_slice());
+        }
+        return;
+    }
+    for (key, child) in &item.children {
+        path.push(key.clone());
+        set_checked(checked, path, child, check);
+        path.pop();
+    }
+}
+
+/// Returns the paths of the checked rows whose parents are not checked, that
+/// is, the rows to delete when the checked rows are deleted.
+fn checked_roots(
+    checked: &HashSet<Vec<String>>,
+    parent: &mut Vec<String>,
+    items: &HashMap<String, LayeredItem>,
+    roots: &mut Vec<Vec<String>>,
+) {
+    for (key, item) in items {
+        parent.push(key.clone());
+        match check_status(checked, parent, item) {
+            CheckStatus::Checked => roots.push(parent.clone()),
+            CheckStatus::Indeterminate => checked_roots(checked, parent, &item.children, roots),
+            CheckStatus::Unchecked => (),
+        }
+        parent.pop();
+    }
+}
+
+/// Sorts the keys of rows in the same way as `WholeList` does.
+fn sorted_keys(items: &HashMap<String, LayeredItem>, sort: Option<SortColumn>) -> Vec<String> {
+    let mut keys = items
+        .iter()
+        .map(|(key, item)| {
+            let value = sort.map_or_else(String::new, |sort| {
+                item.columns
+                    .get(sort.index)
+                    .map_or_else(String::new, ToString::to_string)
+            });
+            (key, value, item.creation_time)
+        })
+        .collect::<Vec<_>>();
+    match sort {
+        None => keys.sort_unstable_by(|a, b| {
+            if let (Some(a_time), Some(b_time)) = (a.2, b.2) {
+                b_time.cmp(&a_time)
+            } else {
+                b.0.cmp(a.0)
+            }
+        }),
+        Some(sort) => keys.sort_by(|a, b| {
+            let order = if sort.status == SortStatus::Descending {
+                b.1.cmp(&a.1)
+            } else {
+                a.1.cmp(&b.1)
+            };
+            order
+                .then_with(|| a.2.cmp(&b.2).reverse())
+                .then_with(|| a.0.cmp(b.0))
+        }),
+    }
+    keys.into_iter().map(|(key, _, _)| key.clone()).collect()
+}
+
+/// Fits the pages to `len` rows, keeping the current page if possible.
+fn fit_pages(info: PagesInfo, len: usize, num_per_page: usize, num_pages: usize) -> PagesInfo {
+    let num_per_page = num_per_page.max(1);
+    let num_pages = num_pages.max(1);
+    let total = if len == 0 {
+        0
+    } else {
+        (len - 1) / num_per_page + 1
+    };
+    let current = info.current.clamp(1, total.max(1));
+    let start = if (info.start..=info.end).contains(&current) {
+        info.start
+    } else {
+        (current - 1) / num_pages * num_pages + 1
+    };
+    let end = (start + num_pages - 1).min(total).max(start);
+    PagesInfo {
+        current,
+        total,
+        start,
+        end,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::{
+        LayeredItem, check_status, checked_roots, children_at, find, fit_pages, group_check_status,
+        set_checked,
+    };
+    use crate::{CheckStatus, PagesInfo};
+
+    fn tree() -> HashMap<String, LayeredItem> {
+        let leaf = LayeredItem::default();
+        let network = LayeredItem {
+            children: HashMap::from([
+                ("h1".to_string(), leaf.clone()),
+                ("h2".to_string(), leaf.clone()),
+            ]),
+            ..LayeredItem::default()
+        };
+        let customer = LayeredItem {
+            children: HashMap::from([
+                ("n1".to_string(), network.clone()),
+                ("n2".to_string(), network),
+            ]),
+            ..LayeredItem::default()
+        };
+        HashMap::from([("c1".to_string(), customer), ("c2".to_string(), leaf)])
+    }
+
+    fn path(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn check_propagation() {
+        let data = tree();
+        assert_eq!(
+            children_at(&data, &path(&["c1", "n1"])).map(HashMap::len),
+            Some(2)
+        );
+        assert!(find(&data, &path(&["c1", "n3"])).is_none());
+
+        let mut checked = HashSet::new();
+        let network = &data["c1"].children["n1"];
+        set_checked(&mut checked, &mut path(&["c1", "n1"]), network, true);
+        assert_eq!(checked.len(), 2);
+        let c1 = path(&["c1"]);
+        let customer = &data["c1"];
+        assert_eq!(
+            check_status(&checked, &c1, customer),
+            CheckStatus::Indeterminate
+        );
+        assert_eq!(
+            group_check_status(&checked, &[], &data),
+            CheckStatus::Indeterminate
+        );
+
+        let mut roots = Vec::new();
+        checked_roots(&checked, &mut Vec::new(), &data, &mut roots);
+        assert_eq!(roots, vec![path(&["c1", "n1"])]);
+
+        checked.insert(path(&["c1", "n2", "h1"]));
+        checked.insert(path(&["c1", "n2", "h2"]));
+        assert_eq!(check_status(&checked, &c1, customer), CheckStatus::Checked);
+        let mut roots = Vec::new();
+        checked_roots(&checked, &mut Vec::new(), &data, &mut roots);
+        assert_eq!(roots, vec![c1.clone()]);
+
+        set_checked(&mut checked, &mut c1.clone(), customer, false);
+        assert!(checked.is_empty());
+    }
+
+    #[test]
+    fn pages() {
+        let info = fit_pages(PagesInfo::default(), 25, 10, 2);
+        assert_eq!(
+            (info.current, info.total, info.start, info.end),
+            (1, 3, 1, 2)
+        );
+        let info = fit_pages(
+            PagesInfo {
+                current: 3,
+                total: 3,
+                start: 3,
+                end: 3,
+            },
+            15,
+            10,
+            2,
+        );
+        assert_eq!(
+            (info.current, info.total, info.start, info.end),
+            (2, 2, 1, 2)
+        );
+        let info = fit_pages(info, 0, 10, 2);
+        assert_eq!(
+            (info.current, info.total, info.start, info.end),
+            (1, 0, 1, 1)
+        );
+    }
+}
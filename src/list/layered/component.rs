@@ -0,0 +1,359 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use yew::{Callback, Component, Context, Html, Properties, html, virtual_dom::AttrValue};
+
+use super::{
+    LayeredAction, LayeredItem, ListLevel, check_status, checked_roots, children_at, find,
+    fit_pages, group_check_status, set_checked, sorted_keys,
+};
+use crate::{
+    CheckStatus, Input, InputItem, MoreAction, PagesInfo, SortStatus, Texts, Theme,
+    gen_default_items_from_confs,
+    language::Language,
+    list::{DEFAULT_NUM_PAGES, ListItem, SortColumn},
+};
+
+/// The row whose input window is open
+#[derive(Clone, PartialEq, Eq)]
+pub(super) enum InputTarget {
+    Add(Vec<String>), // the path of the parent
+    Edit(Vec<String>),
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Model {
+    pub(super) expanded: HashSet<Vec<String>>, // paths of the rows showing their children
+    pub(super) checked: HashSet<Vec<String>>,  // paths of the checked rows without children
+    // pages of the rows under a parent, by the path of the parent
+    pub(super) pages: HashMap<Vec<String>, Rc<RefCell<PagesInfo>>>,
+    pub(super) sorts: Vec<Option<SortColumn>>, // by level
+    pub(super) more_action: Rc<RefCell<Option<MoreAction>>>,
+    pub(super) input: Option<InputTarget>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Message {
+    ToggleExpand(Vec<String>),
+    CheckItem(Vec<String>),
+    CheckAll(Vec<String>),   // the path of the parent
+    ClickSort(usize, usize), // (level, index of a column)
+    MovePage,
+    DoMoreAction(Vec<String>),
+    InputAdd(Vec<String>), // the path of the parent
+    InputEscape,
+    Save,
+    DeleteChecked,
+    CancelChecked,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub txt: Texts,
+    pub language: Language,
+    pub title: &'static str,
+    /// The settings of each level from the top. Rows in the last level cannot
+    /// be expanded.
+    pub levels: Rc<Vec<ListLevel>>,
+    pub data: Rc<HashMap<String, LayeredItem>>, // (key of a row, item of the row)
+    #[prop_or(DEFAULT_NUM_PAGES)]
+    pub num_pages: usize,
+    /// Called when users add, edit or delete rows. The values of a row added
+    /// or edited are in `ListLevel::input_data` of its level.
+    #[prop_or(None)]
+    pub on_action: Option<Callback<LayeredAction>>,
+    #[prop_or(None)]
+    pub theme: Option<Theme>,
+}
+
+impl Component for Model {
+    type Message = Message;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut s = Self {
+            expanded: HashSet::new(),
+            checked: HashSet::new(),
+            pages: HashMap::new(),
+            sorts: ctx.props().levels.iter().map(|level| level.sort).collect(),
+            more_action: Rc::new(RefCell::new(None)),
+            input: None,
+        };
+        s.sync(ctx);
+        s
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().levels != old_props.levels {
+            self.sorts = ctx.props().levels.iter().map(|level| level.sort).collect();
+            self.input = None;
+        }
+        self.sync(ctx);
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let data = Rc::clone(&ctx.props().data);
+        match msg {
+            Message::ToggleExpand(path) => {
+                if !self.expanded.remove(&path) {
+                    self.expanded.insert(path);
+                }
+                self.sync(ctx);
+            }
+            Message::CheckItem(mut path) => {
+                let Some(item) = find(&data, &path) else {
+                    return false;
+                };
+                let check = check_status(&self.checked, &path, item) != CheckStatus::Checked;
+                set_checked(&mut self.checked, &mut path, item, check);
+            }
+            Message::CheckAll(mut parent) => {
+                let Some(items) = children_at(&data, &parent) else {
+                    return false;
+                };
+                let page = self
+                    .page_keys(ctx, &parent, items)
+                    .into_iter()
+                    .filter_map(|key| items.get_key_value(&key))
+                    .map(|(key, item)| (key.clone(), item.clone()))
+                    .collect::<HashMap<_, _>>();
+                let check =
+                    group_check_status(&self.checked, &parent, &page) != CheckStatus::Checked;
+                for (key, item) in &page {
+                    parent.push(key.clone());
+                    set_checked(&mut self.checked, &mut parent, item, check);
+                    parent.pop();
+                }
+            }
+            Message::ClickSort(level, index) => {
+                let Some(sort) = self.sorts.get_mut(level) else {
+                    return false;
+                };
+                let status = match sort {
+                    Some(sort) if sort.index == index && sort.status == SortStatus::Ascending => {
+                        SortStatus::Descending
+                    }
+                    _ => SortStatus::Ascending,
+                };
+                *sort = Some(SortColumn { index, status });
+            }
+            Message::MovePage => (),
+            Message::DoMoreAction(path) => {
+                let action = self
+                    .more_action
+                    .try_borrow_mut()
+                    .ok()
+                    .and_then(|mut action| action.take());
+                match action {
+                    Some(MoreAction::Edit) => {
+                        Self::fill_input_data(ctx, &path);
+                        self.input = Some(InputTarget::Edit(path));
+                    }
+                    Some(MoreAction::Delete) => {
+                        Self::emit(ctx, LayeredAction::Delete(vec![path]));
+                        return false;
+                    }
+                    Some(MoreAction::Custom(_)) | None => return false,
+                }
+            }
+            Message::InputAdd(parent) => {
+                if let Some(level) = ctx.props().levels.get(parent.len()) {
+                    let default_items = gen_default_items_from_confs(&level.input_conf);
+                    for (from, to) in default_items.iter().zip(level.input_data.iter()) {
+                        if let (Ok(from), Ok(mut to)) = (from.try_borrow(), to.try_borrow_mut()) {
+                            *to = from.clone();
+                        }
+                    }
+                    self.input = Some(InputTarget::Add(parent));
+                }
+            }
+            Message::InputEscape => {
+                self.input = None;
+            }
+            Message::Save => {
+                let action = match self.input.take() {
+                    Some(InputTarget::Add(parent)) => LayeredAction::Add(parent),
+                    Some(InputTarget::Edit(path)) => LayeredAction::Edit(path),
+                    None => return false,
+                };
+                Self::emit(ctx, action);
+            }
+            Message::DeleteChecked => {
+                let mut roots = Vec::new();
+                checked_roots(&self.checked, &mut Vec::new(), &data, &mut roots);
+                self.checked.clear();
+                if !roots.is_empty() {
+                    Self::emit(ctx, LayeredAction::Delete(roots));
+                }
+            }
+            Message::CancelChecked => {
+                self.checked.clear();
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                { Self::view_title(ctx) }
+                { self.view_action_bar(ctx) }
+                <div class="list-layered">
+                    { self.view_level(ctx, &[], &ctx.props().data) }
+                </div>
+                { self.view_input(ctx) }
+            </>
+        }
+    }
+}
+
+impl Model {
+    /// Removes the states of the rows that no longer exist and fits the pages
+    /// of the shown levels to the data.
+    fn sync(&mut self, ctx: &Context<Self>) {
+        let data = &ctx.props().data;
+        let levels = &ctx.props().levels;
+        self.expanded
+            .retain(|path| path.len() < levels.len() && find(data, path).is_some());
+        self.checked
+            .retain(|path| find(data, path).is_some_and(|item| item.children.is_empty()));
+
+        let mut shown = vec![Vec::new()];
+        shown.extend(self.expanded.iter().cloned());
+        self.pages.retain(|parent, _| shown.contains(parent));
+        for parent in shown {
+            let (Some(items), Some(level)) = (children_at(data, &parent), levels.get(parent.len()))
+            else {
+                continue;
+            };
+            let info = self
+                .pages
+                .entry(parent)
+                .or_insert_with(|| Rc::new(RefCell::new(PagesInfo::default())));
+            if let Ok(mut info) = info.try_borrow_mut() {
+                *info = fit_pages(
+                    *info,
+                    items.len(),
+                    level.num_per_page,
+                    ctx.props().num_pages,
+                );
+            }
+        }
+    }
+
+    /// Returns the keys of the rows under the parent in the current page.
+    pub(super) fn page_keys(
+        &self,
+        ctx: &Context<Self>,
+        parent: &[String],
+        items: &HashMap<String, LayeredItem>,
+    ) -> Vec<String> {
+        let level = parent.len();
+        let keys = sorted_keys(items, self.sorts.get(level).copied().flatten());
+        let num_per_page = ctx
+            .props()
+            .levels
+            .get(level)
+            .map_or(1, |level| level.num_per_page.max(1));
+        let current = self
+            .pages
+            .get(parent)
+            .and_then(|info| info.try_borrow().ok().map(|info| info.current))
+            .unwrap_or(1);
+        keys.into_iter()
+            .skip((current.max(1) - 1) * num_per_page)
+            .take(num_per_page)
+            .collect()
+    }
+
+    /// Fills `input_data` of the level of the row with the columns of the row.
+    fn fill_input_data(ctx: &Context<Self>, path: &[String]) {
+        let (Some(item), Some(level)) = (
+            find(&ctx.props().data, path),
+            path.len()
+                .checked_sub(1)
+                .and_then(|l| ctx.props().levels.get(l)),
+        ) else {
+            return;
+        };
+        let mut columns = item.columns.iter();
+        for input in &level.input_data {
+            let Ok(mut input) = input.try_borrow_mut() else {
+                continue;
+            };
+            // Passwords are not shown in lists, so they are entered again.
+            if let InputItem::Password(_) = *input {
+                input.clear();
+                continue;
+            }
+            if let Some(column) = columns.next() {
+                *input = column.into();
+            }
+        }
+    }
+
+    fn emit(ctx: &Context<Self>, action: LayeredAction) {
+        if let Some(on_action) = ctx.props().on_action.as_ref() {
+            on_action.emit(action);
+        }
+    }
+
+    fn view_input(&self, ctx: &Context<Self>) -> Html {
+        let Some(target) = self.input.as_ref() else {
+            return html! {};
+        };
+        let (parent, input_id, level) = match target {
+            InputTarget::Add(parent) => (parent.as_slice(), None, parent.len()),
+            InputTarget::Edit(path) => {
+                let Some((key, parent)) = path.split_last() else {
+                    return html! {};
+                };
+                (parent, Some(AttrValue::from(key.clone())), parent.len())
+            }
+        };
+        let Some(level) = ctx.props().levels.get(level) else {
+            return html! {};
+        };
+        // the rows of the same parent, to check if a new value is unique
+        let siblings = children_at(&ctx.props().data, parent)
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|(key, item)| {
+                        (
+                            key.clone(),
+                            ListItem {
+                                columns: item.columns.clone(),
+                                sub_items: Vec::new(),
+                                creation_time: item.creation_time,
+                            },
+                        )
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
+        let title = if input_id.is_some() {
+            level.input_edit_title
+        } else {
+            level.input_add_title
+        };
+        html! {
+            <Input<Self>
+                txt={ctx.props().txt.clone()}
+                language={ctx.props().language}
+                data={Some(Rc::new(siblings))}
+                {title}
+                width={level.input_width}
+                height={level.input_height}
+                input_conf={level.input_conf.clone()}
+                {input_id}
+                input_data={level.input_data.clone()}
+                action_message={Message::Save}
+                escape_message={Message::InputEscape}
+                theme={ctx.props().theme}
+            />
+        }
+    }
+}
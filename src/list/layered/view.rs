@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use yew::{Context, Html, classes, html};
+
+use super::{
+    LayeredItem, ListLevel, check_status,
+    component::{Message, Model},
+    group_check_status,
+};
+use crate::{
+    Checkbox, MoreAction, Pages, SelectMini, SelectMiniKind, Sort, SortStatus, Theme, ViewString,
+    language::translate,
+    list::{ColWidths, Column},
+};
+
+impl Model {
+    pub(super) fn view_title(ctx: &Context<Self>) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let language = ctx.props().language;
+        let add_title = ctx
+            .props()
+            .levels
+            .first()
+            .map(|level| translate(&txt, language, level.input_add_title));
+        let ext = if cfg!(feature = "pumpkin") {
+            "svg"
+        } else {
+            "png"
+        };
+        let add_img = Theme::path(&ctx.props().theme, &format!("list-add.{ext}"));
+        let onclick_add = ctx.link().callback(|_| Message::InputAdd(Vec::new()));
+        html! {
+            <div class="list-header">
+                <div class="list-title">
+                    { translate(&txt, language, ctx.props().title) }
+                </div>
+                if let Some(add_title) = add_title {
+                    <div class="list-add" onclick={onclick_add}>
+                        <img src={add_img} class="list-add" />
+                        { add_title }
+                    </div>
+                }
+            </div>
+        }
+    }
+
+    pub(super) fn view_action_bar(&self, ctx: &Context<Self>) -> Html {
+        if self.checked.is_empty() {
+            return html! {};
+        }
+        let txt = ctx.props().txt.txt.clone();
+        let language = ctx.props().language;
+        html! {
+            <div class="list-layered-action-bar">
+                <div class="list-layered-action-bar-count">
+                    { format!("{} {}", self.checked.len(), translate(&txt, language, "selected")) }
+                </div>
+                <button
+                    type="button"
+                    class={classes!("list-layered-action-bar-button", "list-layered-action-bar-delete")}
+                    onclick={ctx.link().callback(|_| Message::DeleteChecked)}
+                >
+                    { translate(&txt, language, "Delete") }
+                </button>
+                <button
+                    type="button"
+                    class="list-layered-action-bar-button"
+                    onclick={ctx.link().callback(|_| Message::CancelChecked)}
+                >
+                    { translate(&txt, language, "Cancel") }
+                </button>
+            </div>
+        }
+    }
+
+    /// Renders the rows under the parent as a table, and the children of the
+    /// expanded rows as nested tables recursively.
+    pub(super) fn view_level(
+        &self,
+        ctx: &Context<Self>,
+        parent: &[String],
+        items: &HashMap<String, LayeredItem>,
+    ) -> Html {
+        let Some(level) = ctx.props().levels.get(parent.len()) else {
+            return html! {};
+        };
+        let expandable = ctx.props().levels.len() > parent.len() + 1;
+        let num_titles = level.display_info.titles.len();
+        let colspan = (num_titles + usize::from(expandable) + 2).to_string();
+        let keys = self.page_keys(ctx, parent, items);
+
+        html! {
+            <table class={classes!("list-layered", format!("list-layered-level-{}", parent.len()))}>
+                { self.view_head(ctx, parent, level, expandable, &keys, items) }
+                {
+                    for keys.iter().filter_map(|key| items.get(key).map(|item| (key, item))).map(|(key, item)| {
+                        let mut path = parent.to_vec();
+                        path.push(key.clone());
+                        let expanded = self.expanded.contains(&path);
+                        html! {
+                            <>
+                                { self.view_row(ctx, &path, level, expandable, item) }
+                                if expanded {
+                                    <tr class="list-layered-children">
+                                        <td colspan={colspan.clone()} class="list-layered-children">
+                                            { self.view_level(ctx, &path, &item.children) }
+                                        </td>
+                                    </tr>
+                                }
+                            </>
+                        }
+                    })
+                }
+                { self.view_footer(ctx, parent, level, &colspan) }
+            </table>
+        }
+    }
+
+    fn view_head(
+        &self,
+        ctx: &Context<Self>,
+        parent: &[String],
+        level: &ListLevel,
+        expandable: bool,
+        keys: &[String],
+        items: &HashMap<String, LayeredItem>,
+    ) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let depth = parent.len();
+        let page = keys
+            .iter()
+            .filter_map(|key| items.get_key_value(key))
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect::<HashMap<_, _>>();
+        let check_status = group_check_status(&self.checked, parent, &page);
+        let onclick_all = {
+            let parent = parent.to_vec();
+            ctx.link()
+                .callback(move |_| Message::CheckAll(parent.clone()))
+        };
+        let sort = self.sorts.get(depth).copied().flatten();
+        let theme = ctx.props().theme;
+        html! {
+            <tr class="list-layered-head">
+                if expandable {
+                    <td class={classes!("list-layered-head-check", "list-layered-head-expand")}></td>
+                }
+                <td class="list-layered-head-check">
+                    <div onclick={onclick_all}>
+                        <Checkbox status={check_status} {theme} />
+                    </div>
+                </td>
+                {
+                    for level.display_info.titles.iter().enumerate().map(|(index, title)| {
+                        let sort_status = sort.map_or(SortStatus::Unsorted, |s| if s.index == index {
+                            s.status
+                        } else {
+                            SortStatus::Unsorted
+                        });
+                        let onclick_sort = ctx.link().callback(move |_| Message::ClickSort(depth, index));
+                        html! {
+                            <td
+                                class="list-layered-head-title"
+                                style={Self::column_style(level, index)}
+                                onclick={onclick_sort}
+                            >
+                                <div class="list-layered-head-title-inner">
+                                    <span>{ translate(&txt, ctx.props().language, title) }</span>
+                                    <Sort status={sort_status} />
+                                </div>
+                            </td>
+                        }
+                    })
+                }
+                <td class="list-layered-head-last-column"></td>
+            </tr>
+        }
+    }
+
+    fn view_row(
+        &self,
+        ctx: &Context<Self>,
+        path: &[String],
+        level: &ListLevel,
+        expandable: bool,
+        item: &LayeredItem,
+    ) -> Html {
+        let theme = ctx.props().theme;
+        let status = check_status(&self.checked, path, item);
+        let onclick_check = {
+            let path = path.to_vec();
+            ctx.link()
+                .callback(move |_| Message::CheckItem(path.clone()))
+        };
+        let list_top = if cfg!(feature = "pumpkin") { 34 } else { 28 };
+        let more_action_list = Rc::new(vec![
+            ViewString::Key("Edit".to_string()),
+            ViewString::Key("Delete".to_string()),
+        ]);
+        let value_candidates = Rc::new(vec![MoreAction::Edit, MoreAction::Delete]);
+        html! {
+            <tr class="list-layered-row">
+                if expandable {
+                    { self.view_expand_toggle(ctx, path, item) }
+                }
+                <td class="list-layered-check">
+                    <div onclick={onclick_check}>
+                        <Checkbox {status} {theme} />
+                    </div>
+                </td>
+                {
+                    for (0..level.display_info.titles.len()).map(|index| html! {
+                        <td class="list-layered-column" style={Self::column_style(level, index)}>
+                            { item.columns.get(index).map_or_else(|| html! {}, |column| Self::view_column(ctx, column)) }
+                        </td>
+                    })
+                }
+                <td class="list-layered-more-action">
+                    <SelectMini::<MoreAction, Self>
+                        txt={ctx.props().txt.clone()}
+                        language={ctx.props().language}
+                        parent_message={Message::DoMoreAction(path.to_vec())}
+                        id={format!("more-action-layered-{}", path.join("-"))}
+                        active={true}
+                        list={more_action_list}
+                        candidate_values={value_candidates}
+                        selected_value={Rc::clone(&self.more_action)}
+                        selected_value_cache={self.more_action.try_borrow().ok().and_then(|x| *x)}
+                        align_left={false}
+                        {list_top}
+                        kind={SelectMiniKind::MoreAction}
+                        {theme}
+                    />
+                </td>
+            </tr>
+        }
+    }
+
+    fn view_expand_toggle(&self, ctx: &Context<Self>, path: &[String], item: &LayeredItem) -> Html {
+        let expanded = self.expanded.contains(path);
+        let file_name = if expanded {
+            "collapse-list"
+        } else {
+            "expand-list"
+        };
+        let ext = if cfg!(feature = "pumpkin") {
+            "svg"
+        } else {
+            "png"
+        };
+        let img = Theme::path(&ctx.props().theme, &format!("{file_name}.{ext}"));
+        let style = format!("background-image: url('{img}');");
+        let onclick = {
+            let path = path.to_vec();
+            ctx.link()
+                .callback(move |_| Message::ToggleExpand(path.clone()))
+        };
+        let label = translate(&ctx.props().txt.txt, ctx.props().language, "Expand");
+        html! {
+            <td class="list-layered-expand">
+                <button
+                    type="button"
+                    class="list-layered-expand"
+                    title={label.clone()}
+                    aria-label={label}
+                    aria-expanded={expanded.to_string()}
+                    {style}
+                    {onclick}
+                >
+                </button>
+                if !item.children.is_empty() {
+                    <div class="list-layered-expand-count">{ item.children.len() }</div>
+                }
+            </td>
+        }
+    }
+
+    /// The button to add a row under the parent and the pages of the rows.
+    /// The add button of the top level is in the title.
+    fn view_footer(
+        &self,
+        ctx: &Context<Self>,
+        parent: &[String],
+        level: &ListLevel,
+        colspan: &str,
+    ) -> Html {
+        let pages_info = self.pages.get(parent).cloned();
+        let show_pages = pages_info
+            .as_ref()
+            .and_then(|info| info.try_borrow().ok().map(|info| info.total > 1))
+            .unwrap_or(false);
+        if parent.is_empty() && !show_pages {
+            return html! {};
+        }
+        let onclick_add = {
+            let parent = parent.to_vec();
+            ctx.link()
+                .callback(move |_| Message::InputAdd(parent.clone()))
+        };
+        html! {
+            <tr class="list-layered-footer">
+                <td colspan={colspan.to_string()} class="list-layered-footer">
+                    <div class="list-layered-footer-inner">
+                        if !parent.is_empty() {
+                            <div class="list-layered-add" onclick={onclick_add}>
+                                { format!("+ {}", translate(&ctx.props().txt.txt, ctx.props().language, level.input_add_title)) }
+                            </div>
+                        }
+                        if let (true, Some(pages_info)) = (show_pages, pages_info) {
+                            <Pages::<Self>
+                                txt={ctx.props().txt.clone()}
+                                language={ctx.props().language}
+                                parent_message={Message::MovePage}
+                                pages_info_cache={pages_info.try_borrow().ok().map(|info| *info)}
+                                {pages_info}
+                                num_pages={ctx.props().num_pages}
+                            />
+                        }
+                    </div>
+                </td>
+            </tr>
+        }
+    }
+
+    fn column_style(level: &ListLevel, index: usize) -> String {
+        let info = &level.display_info;
+        let width = match info.widths.first() {
+            Some(ColWidths::Pixel(widths)) => widths.get(index).map(|width| {
+                width.unwrap_or_else(|| {
+                    info.width_full
+                        .saturating_sub(widths.iter().filter_map(|w| *w).sum::<u32>())
+                })
+            }),
+            Some(ColWidths::Ratio(widths)) => {
+                return widths
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .map_or_else(String::new, |w| {
+                        format!("width: {:.0}%;", (w * 100.0).trunc())
+                    });
+            }
+            None => None,
+        };
+        width.map_or_else(String::new, |width| format!("width: {width}px;"))
+    }
+
+    fn view_column(ctx: &Context<Self>, column: &Column) -> Html {
+        let txt = &ctx.props().txt.txt;
+        match column {
+            Column::Text(column) => html! { column.text.to_string_txt(txt, ctx.props().language) },
+            Column::SelectSingle(column) => column.selected.as_ref().map_or_else(
+                || html! {},
+                |(_, value)| html! { value.to_string_txt(txt, ctx.props().language) },
+            ),
+            _ => html! { column.to_string() },
+        }
+    }
+}
@@ -1,22 +1,26 @@
+mod aggregation;
 mod component;
 mod function;
+mod layout;
 mod view;
 
+pub use aggregation::{Aggregation, AggregationScope};
 use component::Message;
 pub use component::Model;
+use component::PendingDelete;
 pub use component::SortColumn;
 pub use component::SortListKind;
 use component::ViewInputStatus;
 
-#[cfg(not(feature = "test"))]
-const DEFAULT_NUM_PER_PAGE: usize = 10;
-#[cfg(not(feature = "test"))]
-const DEFAULT_NUM_PAGES: usize = 10;
+use super::{DEFAULT_NUM_PAGES, DEFAULT_NUM_PER_PAGE};
+use crate::{MoreAction, ViewString};
 
-#[cfg(feature = "test")]
-const DEFAULT_NUM_PER_PAGE: usize = 2;
-#[cfg(feature = "test")]
-const DEFAULT_NUM_PAGES: usize = 2;
+/// The width in pixels of the column to expand rows, as styled by the theme,
+/// for pinning the columns after it
+const EXPAND_COLUMN_WIDTH: u32 = if cfg!(feature = "pumpkin") { 52 } else { 31 };
+/// The width in pixels of the check column, as styled by the theme, for
+/// pinning the columns after it
+const CHECK_COLUMN_WIDTH: u32 = if cfg!(feature = "pumpkin") { 39 } else { 40 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MessageType {
@@ -24,6 +28,7 @@ pub enum MessageType {
     Edit,
     Delete,
 
+    // superseded by `LayeredAction` of `LayeredList`, and to be deprecated
     AddSecond,
     EditSecond,
     DeleteSecond,
@@ -32,3 +37,86 @@ pub enum MessageType {
     EditTag,
     DeleteTag,
 }
+
+/// An action in the more-action menu of a row of `WholeList`.
+#[derive(Clone, PartialEq)]
+pub struct RowAction {
+    /// `MoreAction::Edit` and `MoreAction::Delete` work as built in. Other
+    /// actions are sent to the parent through `on_row_action`.
+    pub action: MoreAction,
+    pub label: ViewString,
+    /// The path of an icon image. `None` shows the built-in icon of `Edit` and
+    /// `Delete`, and no icon for other actions.
+    pub icon: Option<String>,
+    /// A disabled action is shown but cannot be selected.
+    pub disabled: bool,
+    /// If set, users are asked to confirm with this message before the action
+    /// is taken.
+    pub confirm: Option<ViewString>,
+}
+
+impl RowAction {
+    #[must_use]
+    pub fn new(action: MoreAction, label: ViewString) -> Self {
+        Self {
+            action,
+            label,
+            icon: None,
+            disabled: false,
+            confirm: None,
+        }
+    }
+
+    #[must_use]
+    pub fn edit() -> Self {
+        Self::new(MoreAction::Edit, ViewString::Key("Edit".to_string()))
+    }
+
+    #[must_use]
+    pub fn delete() -> Self {
+        Self::new(MoreAction::Delete, ViewString::Key("Delete".to_string()))
+    }
+
+    fn step(&self) -> ActionStep {
+        if self.disabled {
+            ActionStep::Ignore
+        } else if self.confirm.is_some() {
+            ActionStep::Confirm
+        } else {
+            ActionStep::Take
+        }
+    }
+}
+
+/// What happens when users select a `RowAction`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ActionStep {
+    Ignore,
+    Confirm,
+    Take,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActionStep, RowAction};
+    use crate::{MoreAction, ViewString};
+
+    #[test]
+    fn row_action_step() {
+        let mut action = RowAction::new(
+            MoreAction::Custom("duplicate"),
+            ViewString::Raw("Duplicate".to_string()),
+        );
+        assert_eq!(action.step(), ActionStep::Take);
+
+        action.confirm = Some(ViewString::Raw("Duplicate the row?".to_string()));
+        assert_eq!(action.step(), ActionStep::Confirm);
+
+        // a disabled action is never taken, even with a confirmation
+        action.disabled = true;
+        assert_eq!(action.step(), ActionStep::Ignore);
+
+        assert_eq!(RowAction::edit().step(), ActionStep::Take);
+        assert_eq!(RowAction::delete().step(), ActionStep::Take);
+    }
+}
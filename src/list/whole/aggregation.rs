@@ -0,0 +1,192 @@
+//! Aggregations of numeric columns shown in the footer of `WholeList`.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use num_traits::ToPrimitive;
+
+use crate::list::{Column, Float64Column, NUM_OF_DECIMALS_DEFAULT, PercentageColumn};
+
+/// An aggregation of the values of a column
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Aggregation {
+    Total,
+    Average,
+    Min,
+    Max,
+}
+
+impl Aggregation {
+    /// The text key of the label
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            Self::Total => "Total",
+            Self::Average => "Average",
+            Self::Min => "Min",
+            Self::Max => "Max",
+        }
+    }
+}
+
+/// The rows aggregated in the footer of `WholeList`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AggregationScope {
+    /// All rows in `data`
+    #[default]
+    All,
+    /// The rows with the keys, such as those matching the filter of the
+    /// parent, in all pages
+    Filtered(Rc<HashSet<String>>),
+    /// The checked rows in all pages
+    Checked,
+}
+
+/// Returns the keys of the rows in `scope` in the order of `sorted_keys`.
+pub(super) fn scoped_keys<'a>(
+    scope: &AggregationScope,
+    sorted_keys: &'a [String],
+    checked: &HashSet<String>,
+    all_pages_checked: bool,
+) -> Vec<&'a String> {
+    sorted_keys
+        .iter()
+        .filter(|key| match scope {
+            AggregationScope::All => true,
+            AggregationScope::Filtered(keys) => keys.contains(*key),
+            AggregationScope::Checked => all_pages_checked || checked.contains(*key),
+        })
+        .collect()
+}
+
+/// How an aggregated value is shown, following the type of the column
+#[derive(Clone, Copy)]
+enum Format {
+    Unsigned,
+    Float64,
+    Percentage(Option<usize>), // decimals
+}
+
+/// Returns the aggregated value of the columns formatted as the columns are.
+/// Returns `None` if there is no value or the columns are not numeric.
+pub(super) fn aggregate<'a>(
+    aggregation: Aggregation,
+    columns: impl IntoIterator<Item = &'a Column>,
+) -> Option<String> {
+    let mut format = None;
+    let mut values = Vec::new();
+    for column in columns {
+        let (value, f) = match column {
+            Column::Unsigned32(c) => (c.value.map(f64::from), Format::Unsigned),
+            Column::Unsigned16(c) => (c.value.map(f64::from), Format::Unsigned),
+            Column::Unsigned8(c) => (c.value.map(f64::from), Format::Unsigned),
+            Column::Float64(c) => (c.value, Format::Float64),
+            Column::Percentage(c) => (c.value.map(f64::from), Format::Percentage(c.decimals)),
+            _ => return None,
+        };
+        format.get_or_insert(f);
+        values.extend(value);
+    }
+    if values.is_empty() {
+        return None;
+    }
+
+    let value = match aggregation {
+        Aggregation::Total => values.iter().sum(),
+        Aggregation::Average => values.iter().sum::<f64>() / values.len().to_f64()?,
+        Aggregation::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+        Aggregation::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    };
+    let text = match format? {
+        Format::Unsigned if aggregation == Aggregation::Average => {
+            format!("{value:.NUM_OF_DECIMALS_DEFAULT$}")
+        }
+        Format::Unsigned => format!("{value:.0}"),
+        Format::Float64 => Column::Float64(Float64Column { value: Some(value) }).to_string(),
+        Format::Percentage(decimals) => Column::Percentage(PercentageColumn {
+            value: value.to_f32(),
+            decimals,
+        })
+        .to_string(),
+    };
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    use super::{Aggregation, AggregationScope, aggregate, scoped_keys};
+    use crate::list::{Column, Float64Column, PercentageColumn, TextColumn, Unsigned32Column};
+
+    #[test]
+    fn aggregation() {
+        let numbers = [Some(3), None, Some(8), Some(1)]
+            .map(|value| Column::Unsigned32(Unsigned32Column { value }));
+        assert_eq!(
+            aggregate(Aggregation::Total, &numbers).as_deref(),
+            Some("12")
+        );
+        assert_eq!(
+            aggregate(Aggregation::Average, &numbers).as_deref(),
+            Some("4.00")
+        );
+        assert_eq!(aggregate(Aggregation::Min, &numbers).as_deref(), Some("1"));
+        assert_eq!(aggregate(Aggregation::Max, &numbers).as_deref(), Some("8"));
+
+        let floats =
+            [Some(1.5), None, Some(2.25)].map(|value| Column::Float64(Float64Column { value }));
+        assert_eq!(
+            aggregate(Aggregation::Total, &floats).as_deref(),
+            Some("3.75")
+        );
+        assert_eq!(
+            aggregate(Aggregation::Average, &floats).as_deref(),
+            Some("1.875")
+        );
+        assert_eq!(aggregate(Aggregation::Min, &floats).as_deref(), Some("1.5"));
+        assert_eq!(
+            aggregate(Aggregation::Max, &floats).as_deref(),
+            Some("2.25")
+        );
+
+        let percentages = [0.25, 0.5].map(|value| {
+            Column::Percentage(PercentageColumn {
+                value: Some(value),
+                decimals: Some(1),
+            })
+        });
+        assert_eq!(
+            aggregate(Aggregation::Average, &percentages).as_deref(),
+            Some("37.5%")
+        );
+
+        let texts = [Column::Text(TextColumn {
+            text: crate::ViewString::Raw("1".to_string()),
+            display: None,
+        })];
+        assert_eq!(aggregate(Aggregation::Total, &texts), None);
+        assert_eq!(aggregate(Aggregation::Total, &[]), None);
+    }
+
+    #[test]
+    fn scope() {
+        let sorted_keys = ["a", "b", "c", "d"].map(String::from);
+        let checked = HashSet::from(["c".to_string(), "a".to_string()]);
+        let keys = |scope: &AggregationScope, all_pages_checked: bool| {
+            scoped_keys(scope, &sorted_keys, &checked, all_pages_checked)
+                .into_iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(keys(&AggregationScope::All, false), ["a", "b", "c", "d"]);
+        let filtered = Rc::new(HashSet::from(["d".to_string(), "b".to_string()]));
+        assert_eq!(
+            keys(&AggregationScope::Filtered(filtered), false),
+            ["b", "d"]
+        );
+        assert_eq!(keys(&AggregationScope::Checked, false), ["a", "c"]);
+        assert_eq!(keys(&AggregationScope::Checked, true), ["a", "b", "c", "d"]);
+    }
+}
@@ -1,14 +1,21 @@
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::time::Duration;
 use std::{cell::RefCell, marker::PhantomData};
 
 use json_gettext::get_text;
-use yew::{Component, Context, Html, Properties, html, virtual_dom::AttrValue};
+use web_sys::HtmlElement;
+use yew::{Callback, Component, Context, Html, NodeRef, Properties, html, virtual_dom::AttrValue};
 
-use super::{DEFAULT_NUM_PAGES, DEFAULT_NUM_PER_PAGE, MessageType};
+use super::{
+    ActionStep, Aggregation, AggregationScope, DEFAULT_NUM_PAGES, DEFAULT_NUM_PER_PAGE,
+    MessageType, RowAction,
+    function::{toggle_checked, toggle_expanded},
+    layout::ColumnLayout,
+};
 use crate::{
-    CheckStatus, Input, InputConfig, InputItem, InputTag, MoreAction, PagesInfo, SelectMini,
-    SelectMiniKind, SortStatus, Texts, Theme, ViewString,
+    CheckStatus, Input, InputConfig, InputItem, InputTag, MoreAction, NotificationItem, PagesInfo,
+    SelectMini, SelectMiniKind, SortStatus, TagOperation, Texts, Theme, ViewString,
     input::InputSecondId,
     language::Language,
     list::{DataType, DisplayInfo, Kind, ListItem},
@@ -52,6 +59,64 @@ pub struct Model<T> {
     phantom: PhantomData<T>,
 
     pub(super) modal: Option<(String, String)>, // (String, String) = (title,  content)
+    pub(super) confirm_action: Option<PendingAction>,
+    // whether all rows across pages are checked, not only those in `checked`
+    pub(super) all_pages_checked: bool,
+    pub(super) editing: Option<CellEdit>,
+
+    pub(super) layout: ColumnLayout,
+    pub(super) column_chooser: bool, // whether the column chooser is open
+    pub(super) dragging: Option<usize>, // the index of the column being dragged
+    pub(super) resizing: Option<(usize, i32, u32)>, // (index of a column, x where it started, width where it started)
+
+    pub(super) expanded_details: HashSet<String>, // keys of the rows showing `row_detail`
+
+    pub(super) manual: bool, // whether rows are in the order set by users
+    pub(super) manual_order: Vec<String>,
+    pub(super) dragging_row: Option<String>, // the key of the row being dragged
+    pub(super) focused_row: Option<String>,  // the key of the row moved with the keys
+    pub(super) row_handle: NodeRef,          // the handle of `focused_row`
+
+    pub(super) pending_delete: Option<PendingDelete>,
+    pub(super) pending_delete_serial: usize,
+    pub(super) undo_notices: Rc<RefCell<Vec<(usize, NotificationItem)>>>, // by serial of `PendingDelete`
+    pub(super) undo_notices_serial: usize, // to rerender `undo_notices`
+}
+
+/// Rows deleted by users, hidden until the deletion is sent to the parent
+#[derive(Clone, PartialEq)]
+pub(super) struct PendingDelete {
+    pub(super) serial: usize,
+    pub(super) keys: Vec<String>,
+    // the states before the deletion, restored when users undo it
+    pub(super) checked: HashSet<String>, // the keys of the deleted rows that were checked
+    pub(super) pages_info: Option<PagesInfo>,
+}
+
+/// A cell being edited in place
+#[derive(Clone, PartialEq)]
+pub(super) struct CellEdit {
+    pub(super) key: String,
+    pub(super) column: usize,
+    pub(super) value: String,
+    pub(super) invalid: Option<String>, // the text key of the message
+    pub(super) editor: NodeRef,
+    pub(super) focused: bool, // whether `editor` has been focused
+}
+
+/// An action waiting for users to confirm it
+#[derive(Clone, PartialEq)]
+pub(super) enum PendingAction {
+    Row(String, RowAction), // (key of a row, action)
+    Bulk(RowAction),
+}
+
+impl PendingAction {
+    pub(super) fn action(&self) -> &RowAction {
+        match self {
+            Self::Row(_, action) | Self::Bulk(action) => action,
+        }
+    }
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -83,6 +148,30 @@ pub enum Message {
     CancelChecked,
 
     DoMoreAction(String),
+    DoBulkAction(usize), // index of `bulk_actions`
+    CheckAllPages,
+    ConfirmAction,
+    CancelAction,
+    EditCell(String, usize), // (key of a row, index of a column)
+    InputCell(String),
+    SaveCell,
+    CancelCell,
+    ToggleCell(String, usize), // (key of a row, index of a column)
+    ToggleColumnChooser,
+    ToggleColumn(usize),
+    ResetLayout,
+    DragColumn(Option<usize>),
+    DropColumn(usize),
+    StartResize(usize, i32, u32), // (index of a column, x, width)
+    Resize(i32),
+    EndResize,
+    ToggleDetail(String),
+    DragRow(Option<String>),
+    DropRow(String),
+    DropRowToPage(bool),   // true: the next page, false: the previous page
+    MoveRow(String, bool), // (key of a row, true: down, false: up)
+    CommitDelete(usize),   // serial of `PendingDelete`
+    UndoDelete(usize),     // serial of `PendingDelete`
     SortList,
     SetSecondSortDefault,
     ClickButton(Option<(String, String)>),
@@ -94,6 +183,8 @@ pub enum SortListKind {
     LatestFirst,
     Ascending,
     Descending,
+    /// The order set by users, available only in `Kind::Flat`
+    Manual,
 }
 
 #[derive(Clone, PartialEq, Properties)]
@@ -108,6 +199,8 @@ where
     // id:: 1st layer: customer/network, 2nd layer: customer-{item's id}
     pub id: AttrValue,
     pub title: &'static str,
+    /// Like the other props for the second level, superseded by `LayeredList`
+    /// and to be deprecated
     #[prop_or(None)]
     pub title_second: Option<&'static str>,
     #[prop_or(None)]
@@ -145,6 +238,9 @@ where
     pub input_data: Vec<Rc<RefCell<InputItem>>>,
     #[prop_or(None)]
     pub input_data_tag: Option<Rc<RefCell<InputTag>>>,
+    /// Passed to `Input::on_tag_operation`
+    #[prop_or(None)]
+    pub on_tag_operation: Option<Callback<TagOperation>>,
     pub input_add_title: &'static str,
     pub input_edit_title: &'static str,
     pub input_width: u32,
@@ -166,6 +262,90 @@ where
     pub input_second_type: Option<Vec<Rc<InputConfig>>>,
 
     pub messages: HashMap<MessageType, T::Message>,
+    /// Returns the actions of the row with the given key. `None` means
+    /// `RowAction::edit` and `RowAction::delete` for every row.
+    #[prop_or(None)]
+    pub row_actions: Option<Callback<String, Vec<RowAction>>>,
+    /// Called with the key of the row and the action when users take a
+    /// `MoreAction::Custom` action
+    #[prop_or(None)]
+    pub on_row_action: Option<Callback<(String, MoreAction)>>,
+    /// The actions on the checked rows shown in the action bar.
+    /// `MoreAction::Delete` works as built in, and other actions are sent to
+    /// the parent through `on_bulk_action`.
+    #[prop_or_default]
+    pub bulk_actions: Vec<RowAction>,
+    /// Called with the keys of the checked rows and the action when users take
+    /// a bulk action other than `MoreAction::Delete`
+    #[prop_or(None)]
+    pub on_bulk_action: Option<Callback<(Vec<String>, MoreAction)>>,
+    /// The indices of the columns users can edit in place with the editor of
+    /// the matching `input_conf`, where `Password` has no column as in
+    /// `MoreAction::Edit`. Supported are `Text`, `DomainName`,
+    /// `Unsigned32`, `Unsigned16`, `Unsigned8`, `Float64`, `Percentage`,
+    /// `SelectSingle`, and `Checkbox` without children.
+    #[prop_or_default]
+    pub inline_edit: Vec<usize>,
+    /// Called with the key of the row, the index of the column and the new
+    /// value when users save a cell edited in place
+    #[prop_or(None)]
+    pub on_cell_save: Option<Callback<(String, usize, InputItem)>>,
+    /// Whether users can show, hide, reorder and resize the columns of the
+    /// first line. The layout is stored in local storage per `id`. This
+    /// doesn't work in `Kind::LayeredSecond`.
+    #[prop_or(false)]
+    pub column_settings: bool,
+    /// Returns the content shown below the row with the given key, such as a
+    /// chart or a nested `WholeList`. It is called only for the expanded rows.
+    /// Rows can be expanded only in `Kind::Flat`.
+    #[prop_or(None)]
+    pub row_detail: Option<Callback<String, Html>>,
+    /// Called with the key of the row and whether it is expanded when users
+    /// expand or collapse a row, e.g., to load the content of `row_detail`
+    #[prop_or(None)]
+    pub on_row_expand: Option<Callback<(String, bool)>>,
+    /// The keys of rows in the order set by users, used in
+    /// `SortListKind::Manual`. Rows not in it follow, the latest first.
+    #[prop_or_default]
+    pub manual_order: Vec<String>,
+    /// Called with the keys of all rows in the new order when users move a row
+    /// in `SortListKind::Manual`
+    #[prop_or(None)]
+    pub on_reorder: Option<Callback<Vec<String>>>,
+    /// The height of the list in pixels, within which rows scroll under the
+    /// head staying at the top. `None` lets the head scroll away with the
+    /// page.
+    #[prop_or(None)]
+    pub sticky_header: Option<u32>,
+    /// The number of columns of the first line pinned on the left along with
+    /// the check column while the list scrolls horizontally. The more-action
+    /// column is pinned on the right. Columns are pinned only in `Kind::Flat`
+    /// and only as far as their widths are known in pixels. With the
+    /// `pumpkin` feature, the table resolves the widths of the cells, so only
+    /// the first column is pinned.
+    #[prop_or(None)]
+    pub pinned_columns: Option<usize>,
+    /// The time during which users can undo a deletion. If set along with
+    /// `on_delete`, deleted rows are hidden at once with an "Undo"
+    /// notification, and sent to `on_delete` instead of
+    /// `MessageType::Delete` or `MessageType::DeleteSecond` only when the
+    /// time passes, or at once when the list goes away or its `id` changes.
+    #[prop_or(None)]
+    pub undo_delete: Option<Duration>,
+    /// Called with the keys of the rows deleted when `undo_delete` passes
+    #[prop_or(None)]
+    pub on_delete: Option<Callback<Vec<String>>>,
+    /// Called with the key of a row of `Kind::LayeredFirst` and the keys of
+    /// its second-level rows deleted when `undo_delete` passes
+    #[prop_or(None)]
+    pub on_delete_second: Option<Callback<(String, Vec<String>)>>,
+    /// The aggregations shown in the footer, by the index of a column. Only
+    /// `Unsigned32`, `Unsigned16`, `Unsigned8`, `Float64` and `Percentage`
+    /// columns of the first line of a `Kind::Flat` list are aggregated.
+    #[prop_or_default]
+    pub aggregations: HashMap<usize, Vec<Aggregation>>,
+    #[prop_or_default]
+    pub aggregation_scope: AggregationScope,
     #[prop_or(None)]
     pub theme: Option<Theme>,
     #[prop_or(Rc::new(RefCell::new(None)))]
@@ -202,7 +382,35 @@ where
             phantom: PhantomData,
 
             modal: None,
+            confirm_action: None,
+            all_pages_checked: false,
+            editing: None,
+
+            layout: Self::load_layout(ctx),
+            column_chooser: false,
+            dragging: None,
+            resizing: None,
+
+            expanded_details: HashSet::new(),
+
+            manual: ctx
+                .props()
+                .selected_sort_kind
+                .try_borrow()
+                .is_ok_and(|kind| *kind == Some(SortListKind::Manual)),
+            manual_order: ctx.props().manual_order.clone(),
+            dragging_row: None,
+            focused_row: None,
+            row_handle: NodeRef::default(),
+
+            pending_delete: None,
+            pending_delete_serial: 0,
+            undo_notices: Rc::new(RefCell::new(Vec::new())),
+            undo_notices_serial: 0,
         };
+        if s.manual {
+            s.sort = None;
+        }
         s.initiate_pages_info(ctx);
         s.reset_sort_second_layer(ctx);
         s.set_sort_list_kind(ctx);
@@ -211,16 +419,34 @@ where
     }
 
     #[allow(clippy::too_many_lines)]
-    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
         // even if the page or sort changes in Flat or LayeredFirst, this `change` is not called. Instead `update` is called.
         let data_changed = self.data_cache != *ctx.props().data;
         let id_changed = self.id_cache != ctx.props().id.as_ref();
 
         let sort_changed = self.sort != ctx.props().sort;
+        if data_changed || id_changed {
+            self.all_pages_checked = false;
+            self.editing = None;
+        }
+        if id_changed {
+            // The deletion is sent at once, as when the list goes away, to the
+            // callback of the list the rows belong to.
+            if let Some(pending) = self.pending_delete.take() {
+                self.remove_undo_notice(pending.serial);
+                if let Some(on_delete) = old_props.on_delete.as_ref() {
+                    on_delete.emit(pending.keys);
+                }
+            }
+            self.expanded_details.clear();
+        } else if data_changed {
+            self.expanded_details
+                .retain(|key| ctx.props().data.contains_key(key));
+        }
         if self.data_cache.len() < ctx.props().data.len() {
             // if an item is added, only sort by latest if LatestFirst is available
             self.initiate_pages_info(ctx); // go to the first page
-            if self.sort.is_none() {
+            if self.sort.is_none() && !self.manual {
                 if ctx
                     .props()
                     .visible_sort_options
@@ -238,6 +464,13 @@ where
             self.set_sort_list_kind(ctx);
         }
 
+        if ctx.props().manual_order != old_props.manual_order {
+            self.manual_order.clone_from(&ctx.props().manual_order);
+            if self.manual && !data_changed && !id_changed {
+                self.sort_keys(ctx);
+            }
+        }
+
         match ctx.props().kind {
             Kind::Flat => {
                 // id_changed: in the case when the menu changes like customer -> network
@@ -355,6 +588,8 @@ where
 
         if id_changed {
             self.id_cache = ctx.props().id.as_ref().into();
+            self.layout = Self::load_layout(ctx);
+            self.column_chooser = false;
         }
         if data_changed {
             self.data_cache.clone_from(&(*ctx.props().data));
@@ -386,6 +621,134 @@ where
                     self.set_first_layer_input_id(ctx);
                 }
             }
+            Message::ToggleColumnChooser => {
+                self.column_chooser = !self.column_chooser;
+            }
+            Message::ToggleColumn(column) => {
+                if !self.layout.toggle(column) {
+                    return false;
+                }
+                self.save_layout(ctx);
+            }
+            Message::ResetLayout => {
+                self.reset_layout(ctx);
+            }
+            Message::DragColumn(column) => {
+                self.dragging = column;
+                return false;
+            }
+            Message::DropColumn(to) => {
+                let Some(from) = self.dragging.take() else {
+                    return false;
+                };
+                if !self.layout.move_column(from, to) {
+                    return false;
+                }
+                self.save_layout(ctx);
+            }
+            Message::StartResize(column, x, width) => {
+                self.resizing = Some((column, x, width));
+            }
+            Message::Resize(x) => {
+                let Some((column, start, width)) = self.resizing else {
+                    return false;
+                };
+                let width = i64::from(width) + i64::from(x) - i64::from(start);
+                self.layout
+                    .resize(column, u32::try_from(width.max(0)).unwrap_or_default());
+            }
+            Message::EndResize => {
+                if self.resizing.take().is_none() {
+                    return false;
+                }
+                self.save_layout(ctx);
+            }
+            Message::ToggleDetail(key) => {
+                let expanded = toggle_expanded(&mut self.expanded_details, &key);
+                if let Some(on_row_expand) = ctx.props().on_row_expand.as_ref() {
+                    on_row_expand.emit((key, expanded));
+                }
+            }
+            Message::DragRow(key) => {
+                self.dragging_row = key;
+            }
+            Message::DropRow(to) => {
+                let Some(from) = self.dragging_row.take() else {
+                    return false;
+                };
+                let (Some(from), Some(to)) = (
+                    self.sorted_keys.iter().position(|k| *k == from),
+                    self.sorted_keys.iter().position(|k| *k == to),
+                ) else {
+                    return true;
+                };
+                self.move_row(ctx, from, to);
+            }
+            Message::DropRowToPage(next) => {
+                let Some(from) = self.dragging_row.take() else {
+                    return false;
+                };
+                let Some(from) = self.sorted_keys.iter().position(|k| *k == from) else {
+                    return true;
+                };
+                // the first row of the next page or the last row of the previous page
+                let (start, end) = self.item_range(ctx);
+                let to = if next {
+                    Some(end)
+                } else {
+                    start.checked_sub(2)
+                };
+                if let Some(to) = to {
+                    self.move_row(ctx, from, to);
+                }
+            }
+            Message::MoveRow(key, down) => {
+                let Some(from) = self.sorted_keys.iter().position(|k| *k == key) else {
+                    return false;
+                };
+                let to = if down {
+                    Some(from + 1)
+                } else {
+                    from.checked_sub(1)
+                };
+                if !to.is_some_and(|to| self.move_row(ctx, from, to)) {
+                    return false;
+                }
+                // The handle loses focus if its row is moved in the DOM.
+                self.focused_row = Some(key);
+            }
+            Message::CommitDelete(serial) => {
+                if self
+                    .pending_delete
+                    .as_ref()
+                    .is_none_or(|pending| pending.serial != serial)
+                {
+                    return false;
+                }
+                self.commit_delete(ctx);
+            }
+            Message::UndoDelete(serial) => {
+                if self
+                    .pending_delete
+                    .as_ref()
+                    .is_none_or(|pending| pending.serial != serial)
+                {
+                    return false;
+                }
+                let Some(pending) = self.pending_delete.take() else {
+                    return false;
+                };
+                self.remove_undo_notice(serial);
+                self.checked.extend(pending.checked);
+                self.update_parent_check_status(ctx);
+                self.sort_keys(ctx);
+                if let (Ok(mut info), Some(prev)) =
+                    (ctx.props().pages_info.try_borrow_mut(), pending.pages_info)
+                {
+                    *info = prev;
+                }
+                self.pages_info = pending.pages_info;
+            }
             Message::SortList => {
                 let sort = if let Ok(sort_input) = self.sort_list_kind.try_borrow() {
                     if let Some(kind) = *sort_input
@@ -393,8 +756,11 @@ where
                     {
                         *sort_kind = Some(kind);
                     }
+                    if sort_input.is_some() {
+                        self.manual = *sort_input == Some(SortListKind::Manual);
+                    }
                     match *sort_input {
-                        Some(SortListKind::LatestFirst) => {
+                        Some(SortListKind::LatestFirst | SortListKind::Manual) => {
                             self.sort = None;
                             true
                         }
@@ -427,6 +793,7 @@ where
             }
             Message::ClickSort(index) => {
                 self.checked.clear();
+                self.manual = false;
                 let next_sort = self.sort.map_or(
                     Some(SortColumn {
                         index,
@@ -457,11 +824,12 @@ where
                 if self.check_status(ctx) == CheckStatus::Unchecked {
                     ctx.link().send_message(Message::ClearOtherLayerChecked);
                 }
-                if self.checked.contains(&key) {
-                    self.checked.remove(&key);
-                } else {
-                    self.checked.insert(key);
-                }
+                toggle_checked(
+                    &mut self.checked,
+                    &mut self.all_pages_checked,
+                    &self.sorted_keys,
+                    key,
+                );
                 if ctx.props().kind == Kind::LayeredSecond {
                     self.update_parent_check_status(ctx);
                     // HIGHLIGHT: should return true
@@ -470,6 +838,7 @@ where
             Message::CheckAll => {
                 // This can occur except in LayeredSecond
                 let check_status = self.check_status(ctx);
+                self.all_pages_checked = false;
                 let clear = match check_status {
                     CheckStatus::Unchecked => {
                         self.check_all(ctx, true);
@@ -533,7 +902,9 @@ where
             Message::Render => (),
             Message::MovePage => {
                 self.pages_info = ctx.props().pages_info.try_borrow().ok().map(|info| *info);
-                ctx.link().send_message(Message::ClearChecked);
+                if !self.all_pages_checked {
+                    ctx.link().send_message(Message::ClearChecked);
+                }
                 if ctx.props().kind == Kind::LayeredFirst {
                     self.set_first_layer_input_id(ctx);
                     ctx.link().send_message(Message::ResetCheckSecond);
@@ -558,6 +929,7 @@ where
             },
             Message::ClearChecked => {
                 self.checked.clear();
+                self.all_pages_checked = false;
                 if ctx.props().kind == Kind::LayeredSecond
                     && let Some(parent) = ctx.link().get_parent()
                 {
@@ -610,6 +982,10 @@ where
                 // In order to avoid this, rerender whenever Edit happens.
             }
             Message::Delete(key) => {
+                if Self::can_undo_delete(ctx) {
+                    self.soft_delete(ctx, vec![key]);
+                    return true;
+                }
                 if let (Some(parent), Some(msg), Ok(mut ids)) = (
                     ctx.link().get_parent(),
                     ctx.props().messages.get(&MessageType::Delete),
@@ -656,78 +1032,113 @@ where
                 return false;
             }
             Message::DoMoreAction(key) => {
-                if let Ok(mut action) = self.more_action.try_borrow_mut() {
-                    match *action {
-                        Some(MoreAction::Delete) => {
-                            match ctx.props().kind {
-                                Kind::LayeredSecond => {
-                                    if let Ok(mut second) =
-                                        ctx.props().input_second_keys.try_borrow_mut()
-                                    {
-                                        *second = Some(vec![key]);
-                                    }
-                                    ctx.link().send_message(Message::DeleteSecond);
-                                }
-                                Kind::LayeredFirst | Kind::Flat => {
-                                    if let Ok(mut second) =
-                                        ctx.props().input_second_keys.try_borrow_mut()
-                                    {
-                                        *second = None;
-                                    }
-                                    ctx.link().send_message(Message::Delete(key));
-                                }
-                            }
-                            *action = None;
-                            return false;
-                        }
-                        Some(MoreAction::Edit) => {
-                            let key_for_second = key.clone();
-                            if let Some(current) = ctx.props().data.get(&key) {
-                                match ctx.props().kind {
-                                    Kind::LayeredSecond => {
-                                        if let Some(input) = ctx.props().input_second_data.as_ref()
-                                        {
-                                            for (index, item) in input.iter().enumerate() {
-                                                if let (Some(list), Ok(mut item)) = (
-                                                    current.columns.get(index),
-                                                    item.try_borrow_mut(),
-                                                ) {
-                                                    *item = list.into();
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Kind::LayeredFirst | Kind::Flat => {
-                                        if let Ok(mut id) = ctx.props().input_ids.try_borrow_mut() {
-                                            let mut index: usize = 0;
-                                            for item in &ctx.props().input_data {
-                                                if let (Some(col), Ok(mut item)) = (
-                                                    current.columns.get(index),
-                                                    item.try_borrow_mut(),
-                                                ) {
-                                                    // HIGHLIGHT: Currently, the only exception is InputItem::Password. But so might be some more.
-                                                    if let InputItem::Password(_) = *item {
-                                                        item.clear();
-                                                        continue;
-                                                    }
-                                                    *item = col.into();
-                                                    index += 1;
-                                                }
-                                            }
-                                            *id = vec![key];
-                                        }
-                                    }
-                                }
-                            }
-                            if let Ok(mut second) = ctx.props().input_second_keys.try_borrow_mut() {
-                                *second = Some(vec![key_for_second]);
-                            }
-                            *action = None;
-                            self.view_input_status = ViewInputStatus::Edit;
+                let action = self
+                    .more_action
+                    .try_borrow_mut()
+                    .ok()
+                    .and_then(|mut action| action.take());
+                let row_action = action.and_then(|action| {
+                    Self::row_actions(ctx, &key)
+                        .into_iter()
+                        .find(|a| a.action == action)
+                });
+                let Some(row_action) = row_action else {
+                    return false;
+                };
+                match row_action.step() {
+                    ActionStep::Ignore => return false,
+                    ActionStep::Confirm => {
+                        self.confirm_action = Some(PendingAction::Row(key, row_action));
+                    }
+                    ActionStep::Take => return self.do_row_action(ctx, key, row_action.action),
+                }
+            }
+            Message::DoBulkAction(index) => {
+                let Some(bulk_action) = ctx.props().bulk_actions.get(index) else {
+                    return false;
+                };
+                match bulk_action.step() {
+                    ActionStep::Ignore => return false,
+                    ActionStep::Confirm => {
+                        self.confirm_action = Some(PendingAction::Bulk(bulk_action.clone()));
+                    }
+                    ActionStep::Take => {
+                        self.do_bulk_action(ctx, bulk_action.action);
+                        return false;
+                    }
+                }
+            }
+            Message::CheckAllPages => {
+                if self.check_status(ctx) == CheckStatus::Unchecked {
+                    ctx.link().send_message(Message::ClearOtherLayerChecked);
+                }
+                self.all_pages_checked = true;
+            }
+            Message::ConfirmAction => match self.confirm_action.take() {
+                Some(PendingAction::Row(key, row_action)) => {
+                    self.do_row_action(ctx, key, row_action.action);
+                }
+                Some(PendingAction::Bulk(bulk_action)) => {
+                    self.do_bulk_action(ctx, bulk_action.action);
+                }
+                None => (),
+            },
+            Message::CancelAction => {
+                self.confirm_action = None;
+            }
+            Message::EditCell(key, column) => {
+                if self
+                    .editing
+                    .as_ref()
+                    .is_some_and(|edit| edit.key == key && edit.column == column)
+                {
+                    return false;
+                }
+                let Some(value) = Self::cell_value(ctx, &key, column) else {
+                    return false;
+                };
+                self.editing = Some(CellEdit {
+                    key,
+                    column,
+                    value,
+                    invalid: None,
+                    editor: NodeRef::default(),
+                    focused: false,
+                });
+            }
+            Message::InputCell(value) => {
+                let Some(edit) = self.editing.as_mut() else {
+                    return false;
+                };
+                edit.value = value;
+                // rerender only to hide the message of the invalid input
+                return edit.invalid.take().is_some();
+            }
+            Message::SaveCell => {
+                let Some(edit) = self.editing.as_mut() else {
+                    return false;
+                };
+                match Self::cell_item(ctx, edit) {
+                    Ok(item) => {
+                        if let Some(on_cell_save) = ctx.props().on_cell_save.as_ref() {
+                            on_cell_save.emit((edit.key.clone(), edit.column, item));
                         }
-                        _ => (),
+                        self.editing = None;
                     }
+                    Err(msg) => edit.invalid = Some(msg),
+                }
+            }
+            Message::CancelCell => {
+                self.editing = None;
+            }
+            Message::ToggleCell(key, column) => {
+                let Some(item) = Self::toggled_cell_item(ctx, &key, column) else {
+                    return false;
+                };
+                if let Some(on_cell_save) = ctx.props().on_cell_save.as_ref() {
+                    on_cell_save.emit((key, column, item));
                 }
+                self.editing = None;
             }
             Message::CancelChecked => {
                 ctx.link().send_message(Message::ClearChecked);
@@ -735,16 +1146,25 @@ where
             }
             Message::DeleteChecked => match ctx.props().kind {
                 Kind::LayeredSecond => {
+                    if Self::can_undo_delete(ctx) {
+                        let keys = self.checked_keys();
+                        self.soft_delete(ctx, keys);
+                        return true;
+                    }
                     if let Ok(mut second) = ctx.props().input_second_keys.try_borrow_mut() {
-                        let keys = self.checked.iter().cloned().collect::<Vec<String>>();
-                        *second = Some(keys);
+                        *second = Some(self.checked_keys());
                     }
                     ctx.link().send_message(Message::DeleteSecond);
                     return false;
                 }
                 Kind::LayeredFirst | Kind::Flat => {
+                    if Self::can_undo_delete(ctx) {
+                        let keys = self.checked_keys();
+                        self.soft_delete(ctx, keys);
+                        return true;
+                    }
                     let send_msg = if let Ok(mut ids) = ctx.props().input_ids.try_borrow_mut() {
-                        *ids = self.checked.iter().cloned().collect::<Vec<String>>();
+                        *ids = self.checked_keys();
                         true
                     } else {
                         false
@@ -770,6 +1190,27 @@ where
         true
     }
 
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        // `autofocus` works only when a page is loaded.
+        if let Some(edit) = self.editing.as_mut()
+            && !edit.focused
+            && let Some(editor) = edit.editor.cast::<HtmlElement>()
+        {
+            let _ = editor.focus();
+            edit.focused = true;
+        }
+        if self.focused_row.take().is_some()
+            && let Some(handle) = self.row_handle.cast::<HtmlElement>()
+        {
+            let _ = handle.focus();
+        }
+    }
+
+    fn destroy(&mut self, ctx: &Context<Self>) {
+        // The deletion is not lost when users leave the list before it is sent.
+        self.commit_delete(ctx);
+    }
+
     #[allow(clippy::too_many_lines)]
     fn view(&self, ctx: &Context<Self>) -> Html {
         let txt = ctx.props().txt.txt.clone();
@@ -808,6 +1249,10 @@ where
                             .map_or("Z ➝ A".to_string(), |t| t.to_string()),
                         sort_column
                     )),
+                    SortListKind::Manual => ViewString::Raw(
+                        get_text!(txt, ctx.props().language.tag(), "Manual")
+                            .map_or("Manual".to_string(), |t| t.to_string()),
+                    ),
                 })
                 .collect::<Vec<_>>(),
         );
@@ -889,13 +1334,22 @@ where
             html! {
                 <>
                     { self.view_action_bar(ctx) }
-                    <div class="list-table">
+                    { self.view_undo_notices(ctx) }
+                    { self.view_resize_overlay(ctx) }
+                    <div
+                        class="list-table"
+                        style={ctx.props().sticky_header.map(|height| format!("max-height: {height}px; overflow-y: auto;"))}
+                    >
                         <table class="list-table">
                             { self.view_head(ctx) }
+                            { self.view_page_drop(ctx, false) }
                             { self.view_list(ctx) }
+                            { self.view_page_drop(ctx, true) }
+                            { self.view_aggregation(ctx) }
                             { self.view_pages(ctx, true) }
                         </table>
                     </div>
+                    { self.view_confirm_action(ctx) }
                     {
                         if self.view_input_status == ViewInputStatus::None {
                             html! {}
@@ -927,6 +1381,8 @@ where
                                     input_id={input_id.clone()}
                                     input_data={ctx.props().input_data.clone()}
                                     input_data_tag={tag}
+                                    on_tag_operation={ctx.props().on_tag_operation.clone()}
+                                    undo_delete={ctx.props().undo_delete}
                                     action_message={msg}
                                     escape_message={Message::InputEscape}
                                     extra_messages={messages}
@@ -961,6 +1417,8 @@ where
                     { self.view_head(ctx) }
                     { self.view_list(ctx) }
                     { self.view_pages(ctx, false) }
+                    { self.view_confirm_action(ctx) }
+                    { self.view_undo_notices(ctx) }
                     {
                         if self.view_input_status == ViewInputStatus::None {
                             html! {}
@@ -992,9 +1450,10 @@ where
                                         input_conf={ctx.props().input_conf.clone()}
                                         input_id={input_id}
                                         input_second_id={second_id}
-
                                         input_data={data.clone()}
                                         input_data_tag={tag}
+                                        on_tag_operation={ctx.props().on_tag_operation.clone()}
+                                        undo_delete={ctx.props().undo_delete}
                                         action_message={msg}
                                         escape_message={Message::InputEscape}
                                     />
@@ -1,18 +1,32 @@
 use std::cell::RefCell;
-use std::collections::HashSet;
 use std::collections::hash_map::Entry::Vacant;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::str::FromStr;
 
+use gloo_timers::callback::Timeout;
 use jiff::Timestamp;
-use yew::{Component, Context};
+use yew::{Callback, Component, Context};
 
-use super::{Message, Model, SortColumn, ViewInputStatus, component::SortListKind};
+use super::{
+    Message, Model, PendingDelete, RowAction, SortColumn, ViewInputStatus,
+    component::{CellEdit, SortListKind},
+    layout::ColumnLayout,
+};
 use crate::{
-    InputItem, gen_default_items_from_confs,
-    list::{DataType, Kind},
+    CheckboxItem, DomainNameItem, Float64Item, InputConfig, InputItem, MoreAction, PercentageItem,
+    SelectSingleItem, TextItem, Unsigned8Item, Unsigned16Item, Unsigned32Item, ViewString,
+    gen_default_items_from_confs,
+    input::verify_cell,
+    language::translate,
+    list::{ColWidths, Column, DataType, Kind, NUM_OF_DECIMALS_DEFAULT},
+    notification::{gen_undo_notification, timer_delay},
     {CheckStatus, PagesInfo, SortStatus},
 };
 
+const INVALID_MSG: &str = "Invalid input";
+const EXISTING_MSG: &str = "The input already exists.";
+
 impl<T> Model<T>
 where
     T: Clone + Component + PartialEq,
@@ -43,13 +57,26 @@ where
         }
     }
 
+    /// Returns the number of rows, except those waiting to be deleted.
+    fn num_rows(&self, ctx: &Context<Self>) -> usize {
+        let pending = self.pending_delete.as_ref().map_or(0, |pending| {
+            pending
+                .keys
+                .iter()
+                .filter(|key| ctx.props().data.contains_key(*key))
+                .count()
+        });
+        ctx.props().data.len() - pending
+    }
+
     pub(super) fn initiate_pages_info(&mut self, ctx: &Context<Self>) {
         if ctx.props().kind != Kind::LayeredSecond {
+            let num_rows = self.num_rows(ctx);
             if let Ok(mut info) = ctx.props().pages_info.try_borrow_mut() {
-                let total = if ctx.props().data.is_empty() {
+                let total = if num_rows == 0 {
                     0 // HIGHLIGHT: total has 0 with no data, but start and end have 1 with no data
                 } else {
-                    (ctx.props().data.len() - 1) / ctx.props().num_per_page + 1
+                    (num_rows - 1) / ctx.props().num_per_page + 1
                 };
                 *info = PagesInfo {
                     current: 1,
@@ -82,11 +109,12 @@ where
     }
 
     pub(super) fn update_pages_info(&mut self, ctx: &Context<Self>) {
+        let num_rows = self.num_rows(ctx);
         if let Ok(mut info) = ctx.props().pages_info.try_borrow_mut() {
-            if ctx.props().data.is_empty() {
+            if num_rows == 0 {
                 *info = PagesInfo::default();
             } else {
-                let total = (ctx.props().data.len() - 1) / ctx.props().num_per_page + 1;
+                let total = (num_rows - 1) / ctx.props().num_per_page + 1;
                 // Add the first item in both first and second layers
                 if info.total == 0 && total == 1 {
                     *info = PagesInfo::default();
@@ -166,10 +194,16 @@ where
             (Some(s.index), s.status == SortStatus::Ascending)
         });
 
+        let pending = self
+            .pending_delete
+            .as_ref()
+            .map(|pending| pending.keys.iter().collect::<HashSet<_>>())
+            .unwrap_or_default();
         let mut keys: Vec<(String, String, Option<Timestamp>)> = ctx
             .props()
             .data
             .iter()
+            .filter(|(key, _)| !pending.contains(key))
             .map(|(key, item)| {
                 index.map_or((key.clone(), key.clone(), item.creation_time), |index| {
                     (
@@ -185,10 +219,11 @@ where
 
         // Only apply "Latest First" sorting if no specific column is sorted AND LatestFirst is available
         let should_apply_latest_first = index.is_none()
-            && ctx
-                .props()
-                .visible_sort_options
-                .contains(&SortListKind::LatestFirst);
+            && (self.manual
+                || ctx
+                    .props()
+                    .visible_sort_options
+                    .contains(&SortListKind::LatestFirst));
 
         if should_apply_latest_first {
             // First step: the latest item first
@@ -218,6 +253,17 @@ where
             }
         }
 
+        // In the manual order, the rows not in `manual_order` follow the others.
+        if self.manual {
+            let positions = self
+                .manual_order
+                .iter()
+                .enumerate()
+                .map(|(position, key)| (key, position))
+                .collect::<HashMap<_, _>>();
+            keys.sort_by_key(|(key, _, _)| positions.get(key).copied().unwrap_or(usize::MAX));
+        }
+
         self.sorted_keys = keys.into_iter().map(|(k, _, _)| k).collect();
         let current = self
             .sorted_keys
@@ -269,7 +315,7 @@ where
         let len = (start..=end)
             .filter_map(|index| {
                 self.sorted_keys.get(index - 1).and_then(|key| {
-                    if self.checked.contains(key) {
+                    if self.is_checked(key) {
                         Some(true)
                     } else {
                         None
@@ -306,7 +352,9 @@ where
         if ctx.props().kind != Kind::LayeredSecond
             && let Ok(mut kind) = self.sort_list_kind.try_borrow_mut()
         {
-            let desired_kind = if let Some(sort) = self.sort {
+            let desired_kind = if self.manual {
+                Some(SortListKind::Manual)
+            } else if let Some(sort) = self.sort {
                 if sort.index == 0 {
                     match sort.status {
                         SortStatus::Ascending => Some(SortListKind::Ascending),
@@ -357,6 +405,443 @@ where
             }
         }
     }
+
+    /// Takes a row action after it is confirmed if necessary. Returns whether
+    /// to render.
+    pub(super) fn do_row_action(
+        &mut self,
+        ctx: &Context<Self>,
+        key: String,
+        action: MoreAction,
+    ) -> bool {
+        match action {
+            MoreAction::Delete => {
+                match ctx.props().kind {
+                    Kind::LayeredSecond => {
+                        if Self::can_undo_delete(ctx) {
+                            self.soft_delete(ctx, vec![key]);
+                            return true;
+                        }
+                        if let Ok(mut second) = ctx.props().input_second_keys.try_borrow_mut() {
+                            *second = Some(vec![key]);
+                        }
+                        ctx.link().send_message(Message::DeleteSecond);
+                    }
+                    Kind::LayeredFirst | Kind::Flat => {
+                        if let Ok(mut second) = ctx.props().input_second_keys.try_borrow_mut() {
+                            *second = None;
+                        }
+                        ctx.link().send_message(Message::Delete(key));
+                    }
+                }
+                false
+            }
+            MoreAction::Edit => {
+                let key_for_second = key.clone();
+                if let Some(current) = ctx.props().data.get(&key) {
+                    match ctx.props().kind {
+                        Kind::LayeredSecond => {
+                            if let Some(input) = ctx.props().input_second_data.as_ref() {
+                                for (index, item) in input.iter().enumerate() {
+                                    if let (Some(list), Ok(mut item)) =
+                                        (current.columns.get(index), item.try_borrow_mut())
+                                    {
+                                        *item = list.into();
+                                    }
+                                }
+                            }
+                        }
+                        Kind::LayeredFirst | Kind::Flat => {
+                            if let Ok(mut id) = ctx.props().input_ids.try_borrow_mut() {
+                                let mut index: usize = 0;
+                                for item in &ctx.props().input_data {
+                                    if let (Some(col), Ok(mut item)) =
+                                        (current.columns.get(index), item.try_borrow_mut())
+                                    {
+                                        // HIGHLIGHT: Currently, the only exception is InputItem::Password. But so might be some more.
+                                        if let InputItem::Password(_) = *item {
+                                            item.clear();
+                                            continue;
+                                        }
+                                        *item = col.into();
+                                        index += 1;
+                                    }
+                                }
+                                *id = vec![key];
+                            }
+                        }
+                    }
+                }
+                if let Ok(mut second) = ctx.props().input_second_keys.try_borrow_mut() {
+                    *second = Some(vec![key_for_second]);
+                }
+                self.view_input_status = ViewInputStatus::Edit;
+                true
+            }
+            MoreAction::Custom(_) => {
+                if let Some(on_row_action) = ctx.props().on_row_action.as_ref() {
+                    on_row_action.emit((key, action));
+                }
+                false
+            }
+        }
+    }
+
+    /// Moves the row at the index `from` of `sorted_keys` to the index `to`,
+    /// and shows the page of the row.
+    ///
+    /// Returns `false` if nothing changes.
+    pub(super) fn move_row(&mut self, ctx: &Context<Self>, from: usize, to: usize) -> bool {
+        if from == to || from >= self.sorted_keys.len() || to >= self.sorted_keys.len() {
+            return false;
+        }
+        let key = self.sorted_keys.remove(from);
+        self.sorted_keys.insert(to, key);
+        let pending = self
+            .pending_delete
+            .as_ref()
+            .map(|pending| pending.keys.iter().collect::<HashSet<_>>())
+            .unwrap_or_default();
+        self.manual_order = merge_order(&self.manual_order, &self.sorted_keys, &pending);
+        if let Some(on_reorder) = ctx.props().on_reorder.as_ref() {
+            on_reorder.emit(self.manual_order.clone());
+        }
+        self.go_to_page(ctx, to / ctx.props().num_per_page.max(1) + 1);
+        true
+    }
+
+    fn go_to_page(&mut self, ctx: &Context<Self>, page: usize) {
+        if let Ok(mut info) = ctx.props().pages_info.try_borrow_mut() {
+            if info.current == page {
+                return;
+            }
+            let num_pages = ctx.props().num_pages.max(1);
+            info.current = page;
+            if page < info.start || page > info.end {
+                info.start = (page - 1) / num_pages * num_pages + 1;
+                info.end = (info.start + num_pages - 1).min(info.total);
+            }
+            self.pages_info = Some(*info);
+        }
+    }
+
+    /// Whether deleted rows wait for `undo_delete` before being sent to
+    /// `on_delete`
+    pub(super) fn can_undo_delete(ctx: &Context<Self>) -> bool {
+        ctx.props().undo_delete.is_some() && ctx.props().on_delete.is_some()
+    }
+
+    /// Hides the rows and sends them to `on_delete` after `undo_delete`
+    /// unless users undo the deletion. A deletion still waiting is sent at
+    /// once.
+    pub(super) fn soft_delete(&mut self, ctx: &Context<Self>, keys: Vec<String>) {
+        let Some(time) = ctx.props().undo_delete.filter(|_| !keys.is_empty()) else {
+            return;
+        };
+        self.commit_delete(ctx);
+        if self.is_manual_order(ctx) {
+            // The hidden rows keep their positions in the order.
+            self.manual_order.clone_from(&self.sorted_keys);
+        }
+        self.pending_delete_serial += 1;
+        let serial = self.pending_delete_serial;
+        let checked = take_checked(
+            &mut self.checked,
+            &mut self.all_pages_checked,
+            &self.sorted_keys,
+            &keys,
+        );
+        let message = format!(
+            "{} {}",
+            keys.len(),
+            translate(&ctx.props().txt.txt, ctx.props().language, "deleted")
+        );
+        self.pending_delete = Some(PendingDelete {
+            serial,
+            keys,
+            checked,
+            pages_info: self.pages_info,
+        });
+        self.update_parent_check_status(ctx);
+        self.sort_keys(ctx);
+        self.update_pages_info(ctx);
+
+        if let Ok(mut notices) = self.undo_notices.try_borrow_mut() {
+            notices.push((serial, gen_undo_notification(message, time)));
+            self.undo_notices_serial += 1;
+        }
+        let link = ctx.link().clone();
+        Timeout::new(timer_delay(time), move || {
+            link.send_message(Message::CommitDelete(serial));
+        })
+        .forget();
+    }
+
+    /// Sends the deletion waiting for `undo_delete` to `on_delete`.
+    pub(super) fn commit_delete(&mut self, ctx: &Context<Self>) {
+        let Some(pending) = self.pending_delete.take() else {
+            return;
+        };
+        self.remove_undo_notice(pending.serial);
+        if let Some(on_delete) = ctx.props().on_delete.as_ref() {
+            on_delete.emit(pending.keys);
+        }
+    }
+
+    pub(super) fn remove_undo_notice(&mut self, serial: usize) {
+        if let Ok(mut notices) = self.undo_notices.try_borrow_mut() {
+            notices.retain(|(s, _)| *s != serial);
+            self.undo_notices_serial += 1;
+        }
+    }
+
+    /// Sends a bulk action on the checked rows.
+    pub(super) fn do_bulk_action(&self, ctx: &Context<Self>, action: MoreAction) {
+        if action == MoreAction::Delete {
+            ctx.link().send_message(Message::DeleteChecked);
+        } else if let Some(on_bulk_action) = ctx.props().on_bulk_action.as_ref() {
+            on_bulk_action.emit((self.checked_keys(), action));
+        }
+    }
+
+    /// Returns the keys of the checked rows in the order of the list.
+    pub(super) fn checked_keys(&self) -> Vec<String> {
+        checked_in_order(&self.sorted_keys, &self.checked, self.all_pages_checked)
+    }
+
+    pub(super) fn is_checked(&self, key: &str) -> bool {
+        self.all_pages_checked || self.checked.contains(key)
+    }
+
+    /// Returns the actions of the row with the key.
+    pub(super) fn row_actions(ctx: &Context<Self>, key: &str) -> Vec<RowAction> {
+        ctx.props().row_actions.as_ref().map_or_else(
+            || vec![RowAction::edit(), RowAction::delete()],
+            |row_actions| row_actions.emit(key.to_string()),
+        )
+    }
+
+    /// Returns whether users can edit the cells of the column in place.
+    pub(super) fn is_inline_editable(ctx: &Context<Self>, column: usize) -> bool {
+        ctx.props().inline_edit.contains(&column)
+            && column_conf(&ctx.props().input_conf, column).is_some_and(|conf| match &**conf {
+                InputConfig::Text(config) => !config.immutable,
+                InputConfig::Checkbox(config) => {
+                    config.children.is_none() && config.always.is_none()
+                }
+                InputConfig::DomainName(_)
+                | InputConfig::SelectSingle(_)
+                | InputConfig::Unsigned32(_)
+                | InputConfig::Unsigned16(_)
+                | InputConfig::Unsigned8(_)
+                | InputConfig::Float64(_)
+                | InputConfig::Percentage(_) => true,
+                _ => false,
+            })
+    }
+
+    /// Returns the text to start editing the cell with, or `None` if the cell
+    /// cannot be edited in place.
+    pub(super) fn cell_value(ctx: &Context<Self>, key: &str, column: usize) -> Option<String> {
+        if !Self::is_inline_editable(ctx, column) {
+            return None;
+        }
+        let col = ctx.props().data.get(key)?.columns.get(column)?;
+        match col {
+            Column::Text(elem) => Some(elem.text.to_string()),
+            Column::DomainName(elem) => Some(elem.domain.clone()),
+            Column::SelectSingle(elem) => Some(
+                elem.selected
+                    .as_ref()
+                    .map_or_else(String::new, |(id, _)| id.clone()),
+            ),
+            Column::Unsigned32(_)
+            | Column::Unsigned16(_)
+            | Column::Unsigned8(_)
+            | Column::Float64(_) => Some(col.to_string()),
+            Column::Percentage(elem) => Some(
+                elem.value
+                    .map_or_else(String::new, |v| percentage_text(v, elem.decimals)),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Parses the text of the cell being edited in place into the item of the
+    /// column, and verifies it with the same rules as `Input`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the text key of the message to show if the text is invalid.
+    pub(super) fn cell_item(ctx: &Context<Self>, edit: &CellEdit) -> Result<InputItem, String> {
+        let conf = column_conf(&ctx.props().input_conf, edit.column)
+            .ok_or_else(|| INVALID_MSG.to_string())?;
+        let value = edit.value.trim();
+        let item = match &**conf {
+            InputConfig::Text(config) => {
+                if config.unique && Self::text_exists(ctx, edit, value) {
+                    return Err(EXISTING_MSG.to_string());
+                }
+                Some(InputItem::Text(TextItem::new(value.to_string())))
+            }
+            InputConfig::DomainName(_) => Some(InputItem::DomainName(DomainNameItem::new(value))),
+            InputConfig::SelectSingle(_) => Some(InputItem::SelectSingle(SelectSingleItem::new(
+                (!value.is_empty()).then(|| value.to_string()),
+            ))),
+            InputConfig::Unsigned32(_) => parse_cell(value)
+                .ok()
+                .map(|v| InputItem::Unsigned32(Unsigned32Item::new(v))),
+            InputConfig::Unsigned16(_) => parse_cell(value)
+                .ok()
+                .map(|v| InputItem::Unsigned16(Unsigned16Item::new(v))),
+            InputConfig::Unsigned8(_) => parse_cell(value)
+                .ok()
+                .map(|v| InputItem::Unsigned8(Unsigned8Item::new(v))),
+            InputConfig::Float64(_) => parse_cell(value)
+                .ok()
+                .map(|v| InputItem::Float64(Float64Item::new(v))),
+            InputConfig::Percentage(_) => parse_cell::<f32>(value)
+                .ok()
+                .map(|v| InputItem::Percentage(PercentageItem::new(v.map(|v| v / 100.0)))),
+            _ => None,
+        };
+        let item = item.ok_or_else(|| INVALID_MSG.to_string())?;
+        verify_cell(&item, conf)?;
+        Ok(item)
+    }
+
+    /// Returns whether a row other than the one being edited has the text in
+    /// the column, as `Input` checks for `unique`.
+    fn text_exists(ctx: &Context<Self>, edit: &CellEdit, value: &str) -> bool {
+        ctx.props().data.iter().any(|(key, item)| {
+            *key != edit.key
+                && matches!(
+                    item.columns.get(edit.column),
+                    Some(Column::Text(other)) if matches!(&other.text, ViewString::Raw(text) if text == value)
+                )
+        })
+    }
+
+    /// Returns the checkbox item of the cell with its status toggled, or
+    /// `None` if the cell cannot be toggled.
+    pub(super) fn toggled_cell_item(
+        ctx: &Context<Self>,
+        key: &str,
+        column: usize,
+    ) -> Option<InputItem> {
+        if !Self::is_inline_editable(ctx, column) {
+            return None;
+        }
+        let Some(Column::Checkbox(elem)) = ctx.props().data.get(key)?.columns.get(column) else {
+            return None;
+        };
+        let conf = column_conf(&ctx.props().input_conf, column)?;
+        let InputConfig::Checkbox(config) = &**conf else {
+            return None;
+        };
+        let status = if elem.status == CheckStatus::Checked {
+            CheckStatus::Unchecked
+        } else {
+            CheckStatus::Checked
+        };
+        let item = InputItem::Checkbox(CheckboxItem::new(status, Vec::new(), config.theme));
+        verify_cell(&item, conf).ok()?;
+        Some(item)
+    }
+
+    /// Returns the number of columns pinned on the left, only in `Kind::Flat`.
+    pub(super) fn pinned_columns(ctx: &Context<Self>) -> Option<usize> {
+        ctx.props()
+            .pinned_columns
+            .filter(|_| ctx.props().kind == Kind::Flat)
+    }
+
+    /// Whether rows are in the order set by users and can be moved
+    pub(super) fn is_manual_order(&self, ctx: &Context<Self>) -> bool {
+        self.manual && ctx.props().kind == Kind::Flat
+    }
+
+    /// Returns whether users can expand rows to show `row_detail`.
+    pub(super) fn has_row_detail(ctx: &Context<Self>) -> bool {
+        ctx.props().row_detail.is_some() && ctx.props().kind == Kind::Flat
+    }
+
+    /// Returns whether users can change the layout of the columns.
+    pub(super) fn has_column_settings(ctx: &Context<Self>) -> bool {
+        ctx.props().column_settings && ctx.props().kind != Kind::LayeredSecond
+    }
+
+    fn num_first_line_columns(ctx: &Context<Self>) -> usize {
+        ctx.props()
+            .display_info
+            .widths
+            .first()
+            .map_or(0, ColWidths::len)
+    }
+
+    pub(super) fn load_layout(ctx: &Context<Self>) -> ColumnLayout {
+        let num = Self::num_first_line_columns(ctx);
+        if Self::has_column_settings(ctx) {
+            ColumnLayout::load(&ctx.props().id, num)
+        } else {
+            ColumnLayout::new(num)
+        }
+    }
+
+    pub(super) fn save_layout(&self, ctx: &Context<Self>) {
+        if Self::has_column_settings(ctx) {
+            self.layout.save(&ctx.props().id);
+        }
+    }
+
+    pub(super) fn reset_layout(&mut self, ctx: &Context<Self>) {
+        ColumnLayout::remove(&ctx.props().id);
+        self.layout = ColumnLayout::new(Self::num_first_line_columns(ctx));
+    }
+
+    /// Returns the indices of the columns shown in the line and their widths,
+    /// applying the layout set by users to the first line.
+    pub(super) fn line_columns(&self, ctx: &Context<Self>, line: usize) -> (Vec<usize>, ColWidths) {
+        let lines = &ctx.props().display_info.widths;
+        let Some(widths) = lines.get(line) else {
+            return (Vec::new(), ColWidths::default());
+        };
+        if line > 0 || !Self::has_column_settings(ctx) {
+            let start = lines[..line].iter().map(ColWidths::len).sum::<usize>();
+            return ((start..start + widths.len()).collect(), widths.clone());
+        }
+        let columns = self.layout.visible();
+        let widths = match widths {
+            ColWidths::Pixel(ws) => ColWidths::Pixel(
+                columns
+                    .iter()
+                    .map(|c| {
+                        self.layout
+                            .widths
+                            .get(c)
+                            .copied()
+                            .or_else(|| ws.get(*c).copied().flatten())
+                    })
+                    .collect(),
+            ),
+            ColWidths::Ratio(ws) => ColWidths::Ratio(
+                columns
+                    .iter()
+                    .map(|c| ws.get(*c).copied().flatten())
+                    .collect(),
+            ),
+        };
+        (columns, widths)
+    }
+}
+
+/// Parses the text of a cell, where an empty text means no value.
+fn parse_cell<V: FromStr>(value: &str) -> Result<Option<V>, V::Err> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        value.parse().map(Some)
+    }
 }
 
 fn copy_items(from: &[Rc<RefCell<InputItem>>], to: &[Rc<RefCell<InputItem>>]) {
@@ -366,3 +851,282 @@ fn copy_items(from: &[Rc<RefCell<InputItem>>], to: &[Rc<RefCell<InputItem>>]) {
         }
     }
 }
+
+/// Returns the config of the column. `Password` has no column in `ListItem`,
+/// so the column is the index among the other configs, as in
+/// `MoreAction::Edit`.
+pub(super) fn column_conf(confs: &[Rc<InputConfig>], column: usize) -> Option<&Rc<InputConfig>> {
+    confs
+        .iter()
+        .filter(|conf| !matches!(&***conf, InputConfig::Password(_)))
+        .nth(column)
+}
+
+/// Returns the `on_delete` of the second-level list of the row with the key,
+/// which passes the deleted keys to `on_delete_second` along with the key.
+pub(super) fn on_delete_of_second(
+    on_delete_second: Option<&Callback<(String, Vec<String>)>>,
+    key: &str,
+) -> Option<Callback<Vec<String>>> {
+    let key = key.to_string();
+    on_delete_second
+        .map(|on_delete_second| on_delete_second.reform(move |keys| (key.clone(), keys)))
+}
+
+/// Returns the percentage as the list cell shows it, without the percent sign.
+fn percentage_text(value: f32, decimals: Option<usize>) -> String {
+    format!(
+        "{0:.1$}",
+        value * 100.0,
+        decimals.unwrap_or(NUM_OF_DECIMALS_DEFAULT)
+    )
+}
+
+/// Returns `previous` with the keys in `visible` put in their order, keeping
+/// the `pending` keys where they are. Visible keys not in `previous` follow
+/// the others.
+fn merge_order(previous: &[String], visible: &[String], pending: &HashSet<&String>) -> Vec<String> {
+    let in_visible = visible.iter().collect::<HashSet<_>>();
+    let mut visible = visible.iter();
+    let mut order = previous
+        .iter()
+        .filter_map(|key| {
+            if in_visible.contains(key) {
+                visible.next().cloned()
+            } else {
+                pending.contains(key).then(|| key.clone())
+            }
+        })
+        .collect::<Vec<_>>();
+    order.extend(visible.cloned());
+    order
+}
+
+/// Returns the checked keys in the order of `sorted_keys`.
+fn checked_in_order(
+    sorted_keys: &[String],
+    checked: &HashSet<String>,
+    all_pages_checked: bool,
+) -> Vec<String> {
+    sorted_keys
+        .iter()
+        .filter(|key| all_pages_checked || checked.contains(*key))
+        .cloned()
+        .collect()
+}
+
+/// Unchecks the deleted rows and keeps the others checked. Returns the deleted
+/// rows that were checked.
+fn take_checked(
+    checked: &mut HashSet<String>,
+    all_pages_checked: &mut bool,
+    sorted_keys: &[String],
+    deleted: &[String],
+) -> HashSet<String> {
+    if std::mem::take(all_pages_checked) {
+        *checked = sorted_keys.iter().cloned().collect();
+    }
+    deleted
+        .iter()
+        .filter(|key| checked.remove(*key))
+        .cloned()
+        .collect()
+}
+
+/// Checks the row if it is unchecked, or unchecks it. If the rows of all pages
+/// are checked, they are checked one by one before the row is unchecked.
+pub(super) fn toggle_checked(
+    checked: &mut HashSet<String>,
+    all_pages_checked: &mut bool,
+    sorted_keys: &[String],
+    key: String,
+) {
+    if std::mem::take(all_pages_checked) {
+        *checked = sorted_keys.iter().cloned().collect();
+    }
+    if !checked.remove(&key) {
+        checked.insert(key);
+    }
+}
+
+/// Expands the row if it is collapsed, or collapses it. Returns whether the
+/// row is expanded.
+pub(super) fn toggle_expanded(expanded: &mut HashSet<String>, key: &str) -> bool {
+    if expanded.remove(key) {
+        false
+    } else {
+        expanded.insert(key.to_string());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    use yew::Callback;
+
+    use super::{
+        checked_in_order, column_conf, merge_order, on_delete_of_second, percentage_text,
+        take_checked, toggle_checked, toggle_expanded,
+    };
+    use crate::input::{Essential, InputConfig, PasswordConfig, TextConfig};
+
+    fn essential(title: &str) -> Essential {
+        Essential {
+            title: title.to_string(),
+            notice: "",
+            required: false,
+        }
+    }
+
+    #[test]
+    fn column_conf_skips_password() {
+        let confs = vec![
+            Rc::new(InputConfig::Password(PasswordConfig {
+                ess: essential("password"),
+                length: None,
+                width: None,
+            })),
+            Rc::new(InputConfig::Text(TextConfig {
+                ess: essential("name"),
+                length: None,
+                width: None,
+                preset: None,
+                unique: false,
+                immutable: false,
+                validation: None,
+            })),
+        ];
+        let Some(InputConfig::Text(conf)) = column_conf(&confs, 0).map(|conf| &**conf) else {
+            panic!("the first column should be the text");
+        };
+        assert_eq!(conf.ess.title(), "name");
+        assert!(column_conf(&confs, 1).is_none());
+    }
+
+    #[test]
+    fn percentage_editor_text() {
+        // without float artifacts such as "7.000001"
+        assert_eq!(percentage_text(0.07, Some(1)), "7.0");
+        assert_eq!(percentage_text(0.125, None), "12.50");
+        assert_eq!(percentage_text(0.5, Some(0)), "50");
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn check_rows() {
+        let sorted = keys(&["c", "a", "b"]);
+        let mut checked = HashSet::new();
+        let mut all_pages = false;
+
+        toggle_checked(&mut checked, &mut all_pages, &sorted, "b".to_string());
+        toggle_checked(&mut checked, &mut all_pages, &sorted, "c".to_string());
+        assert_eq!(
+            checked_in_order(&sorted, &checked, all_pages),
+            keys(&["c", "b"])
+        );
+
+        toggle_checked(&mut checked, &mut all_pages, &sorted, "c".to_string());
+        assert_eq!(checked_in_order(&sorted, &checked, all_pages), keys(&["b"]));
+    }
+
+    #[test]
+    fn check_all_pages() {
+        let sorted = keys(&["c", "a", "b"]);
+        let mut checked = HashSet::new();
+        let mut all_pages = true;
+        assert_eq!(checked_in_order(&sorted, &checked, all_pages), sorted);
+
+        // unchecking a row keeps the rows of the other pages checked
+        toggle_checked(&mut checked, &mut all_pages, &sorted, "a".to_string());
+        assert!(!all_pages);
+        assert_eq!(
+            checked_in_order(&sorted, &checked, all_pages),
+            keys(&["c", "b"])
+        );
+    }
+
+    #[test]
+    fn expand_rows() {
+        let mut expanded = HashSet::new();
+        assert!(toggle_expanded(&mut expanded, "a"));
+        assert!(toggle_expanded(&mut expanded, "b"));
+        // several rows stay expanded at once
+        assert_eq!(expanded.len(), 2);
+
+        assert!(!toggle_expanded(&mut expanded, "a"));
+        assert_eq!(expanded, HashSet::from(["b".to_string()]));
+    }
+
+    #[test]
+    fn uncheck_deleted_rows() {
+        let sorted = keys(&["c", "a", "b"]);
+        let mut checked = HashSet::from(["a".to_string(), "b".to_string()]);
+        let mut all_pages = false;
+
+        // deleting a row from its menu keeps the other rows checked
+        let taken = take_checked(&mut checked, &mut all_pages, &sorted, &keys(&["c"]));
+        assert!(taken.is_empty());
+        assert_eq!(
+            checked_in_order(&sorted, &checked, all_pages),
+            keys(&["a", "b"])
+        );
+
+        let taken = take_checked(&mut checked, &mut all_pages, &sorted, &keys(&["b"]));
+        assert_eq!(taken, HashSet::from(["b".to_string()]));
+        assert_eq!(checked_in_order(&sorted, &checked, all_pages), keys(&["a"]));
+    }
+
+    #[test]
+    fn uncheck_deleted_rows_of_all_pages() {
+        let sorted = keys(&["c", "a", "b"]);
+        let mut checked = HashSet::new();
+        let mut all_pages = true;
+
+        let taken = take_checked(&mut checked, &mut all_pages, &sorted, &keys(&["a"]));
+        assert_eq!(taken, HashSet::from(["a".to_string()]));
+        assert!(!all_pages);
+        assert_eq!(
+            checked_in_order(&sorted, &checked, all_pages),
+            keys(&["c", "b"])
+        );
+    }
+    #[test]
+    fn reorder_with_pending_delete() {
+        let previous = keys(&["a", "x", "b", "c"]);
+        let x = "x".to_string();
+        let pending = HashSet::from([&x]);
+
+        // "c" moved to the top while "x" waits to be deleted
+        let order = merge_order(&previous, &keys(&["c", "a", "b"]), &pending);
+        assert_eq!(order, keys(&["c", "x", "a", "b"]));
+
+        // rows out of the previous order follow, and committed ones are gone
+        let order = merge_order(&previous, &keys(&["b", "d", "a", "c"]), &HashSet::new());
+        assert_eq!(order, keys(&["b", "d", "a", "c"]));
+        let order = merge_order(&keys(&["a", "b"]), &keys(&["b", "a", "d"]), &HashSet::new());
+        assert_eq!(order, keys(&["b", "a", "d"]));
+    }
+    #[test]
+    fn delete_second_level_rows() {
+        let deleted = Rc::new(RefCell::new(Vec::new()));
+        let on_delete_second = {
+            let deleted = Rc::clone(&deleted);
+            Callback::from(move |keys| deleted.borrow_mut().push(keys))
+        };
+        let on_delete = on_delete_of_second(Some(&on_delete_second), "customer")
+            .expect("the callback of the second level");
+        on_delete.emit(keys(&["network-1", "network-2"]));
+        assert_eq!(
+            *deleted.borrow(),
+            [("customer".to_string(), keys(&["network-1", "network-2"]))]
+        );
+        assert!(on_delete_of_second(None, "customer").is_none());
+    }
+}
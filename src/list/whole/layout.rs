@@ -0,0 +1,143 @@
+//! The layout of the columns of `WholeList` changed by users.
+//!
+//! Users can show, hide, reorder and resize the columns of the first line. The
+//! layout is saved to the browser's local storage per list `id` so that it
+//! survives reloads.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+/// Local storage key prefix for persisting the layout of a list
+const STORAGE_KEY_PREFIX: &str = "aice.list.layout.";
+
+/// The minimum width in pixels a column can be resized to
+const MIN_COLUMN_WIDTH: u32 = 40;
+
+/// The order, visibility and widths of the columns in the first line
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub(super) struct ColumnLayout {
+    /// The indices of the columns in the order shown
+    pub(super) order: Vec<usize>,
+    /// The indices of the hidden columns
+    pub(super) hidden: BTreeSet<usize>,
+    /// The widths in pixels set by users, by the index of a column
+    pub(super) widths: BTreeMap<usize, u32>,
+}
+
+impl ColumnLayout {
+    /// Creates the default layout of `num` columns.
+    pub(super) fn new(num: usize) -> Self {
+        Self {
+            order: (0..num).collect(),
+            hidden: BTreeSet::new(),
+            widths: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the layout of the list `id` from browser storage. Returns the
+    /// default layout if none is stored or the stored one doesn't fit `num`
+    /// columns.
+    pub(super) fn load(id: &str, num: usize) -> Self {
+        LocalStorage::get::<Self>(storage_key(id))
+            .ok()
+            .filter(|layout| layout.fits(num))
+            .unwrap_or_else(|| Self::new(num))
+    }
+
+    pub(super) fn save(&self, id: &str) {
+        let _ = LocalStorage::set(storage_key(id), self);
+    }
+
+    /// Removes the stored layout of the list `id`.
+    pub(super) fn remove(id: &str) {
+        LocalStorage::delete(storage_key(id));
+    }
+
+    fn fits(&self, num: usize) -> bool {
+        let mut order = self.order.clone();
+        order.sort_unstable();
+        order == (0..num).collect::<Vec<_>>()
+            && self.hidden.iter().all(|&c| c < num)
+            && self.hidden.len() < num
+            && self.widths.keys().all(|&c| c < num)
+    }
+
+    /// Returns the indices of the visible columns in the order shown.
+    pub(super) fn visible(&self) -> Vec<usize> {
+        self.order
+            .iter()
+            .filter(|c| !self.hidden.contains(c))
+            .copied()
+            .collect()
+    }
+
+    /// Shows or hides the column. The last visible column cannot be hidden.
+    ///
+    /// Returns `false` if nothing changes.
+    pub(super) fn toggle(&mut self, column: usize) -> bool {
+        if self.hidden.remove(&column) {
+            return true;
+        }
+        if !self.order.contains(&column) || self.visible().len() <= 1 {
+            return false;
+        }
+        self.hidden.insert(column)
+    }
+
+    /// Moves the column `from` to the place of the column `to`.
+    ///
+    /// Returns `false` if nothing changes.
+    pub(super) fn move_column(&mut self, from: usize, to: usize) -> bool {
+        let (Some(from), Some(to)) = (
+            self.order.iter().position(|&c| c == from),
+            self.order.iter().position(|&c| c == to),
+        ) else {
+            return false;
+        };
+        if from == to {
+            return false;
+        }
+        let column = self.order.remove(from);
+        self.order.insert(to, column);
+        true
+    }
+
+    pub(super) fn resize(&mut self, column: usize, width: u32) {
+        self.widths.insert(column, width.max(MIN_COLUMN_WIDTH));
+    }
+}
+
+fn storage_key(id: &str) -> String {
+    format!("{STORAGE_KEY_PREFIX}{id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnLayout, MIN_COLUMN_WIDTH};
+
+    #[test]
+    fn layout() {
+        let mut layout = ColumnLayout::new(4);
+        assert!(layout.move_column(3, 1));
+        assert_eq!(layout.order, vec![0, 3, 1, 2]);
+        assert!(layout.move_column(0, 2));
+        assert_eq!(layout.order, vec![3, 1, 2, 0]);
+        assert!(!layout.move_column(1, 1));
+
+        assert!(layout.toggle(1));
+        assert!(layout.toggle(2));
+        assert!(layout.toggle(3));
+        assert!(!layout.toggle(0));
+        assert_eq!(layout.visible(), vec![0]);
+        assert!(layout.toggle(2));
+        assert_eq!(layout.visible(), vec![2, 0]);
+
+        layout.resize(2, 10);
+        assert_eq!(layout.widths.get(&2), Some(&MIN_COLUMN_WIDTH));
+        assert!(layout.fits(4));
+        assert!(!layout.fits(3));
+        assert!(!layout.fits(5));
+    }
+}
@@ -4,17 +4,28 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 use json_gettext::get_text;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    DragEvent, Event, HtmlElement, HtmlInputElement, HtmlSelectElement, InputEvent, KeyboardEvent,
+    MouseEvent,
+};
 use yew::classes;
-use yew::{Component, Context, Html, html, virtual_dom::AttrValue};
+use yew::{Callback, Component, Context, Html, NodeRef, html, virtual_dom::AttrValue};
 
 use super::{
-    DEFAULT_NUM_PAGES,
-    component::{Message, Model},
+    CHECK_COLUMN_WIDTH, DEFAULT_NUM_PAGES, EXPAND_COLUMN_WIDTH,
+    aggregation::{aggregate, scoped_keys},
+    component::{CellEdit, Message, Model},
+    function::{column_conf, on_delete_of_second},
 };
 use crate::{
-    CheckStatus, Checkbox, InputConfig, MoreAction, Pages, SelectMini, SelectMiniKind, Sort,
-    SortStatus, Theme, ViewString, WholeList,
+    CheckStatus, Checkbox, InputConfig, Modal, ModalAlign, ModalTextStyle, ModalType, MoreAction,
+    Notification, Pages, SelectMini, SelectMiniKind, Sort, SortStatus, Theme, ViewString,
+    WholeList,
+    input::view_tag_color,
+    language::translate,
     list::{ColWidths, Column, DataType, Kind, ListItem, ModalDisplay},
+    notification::UNDO_ACTION,
     text,
 };
 
@@ -30,8 +41,12 @@ where
         let mut colspan = 0;
         let rowspan = ctx.props().display_info.widths.len().to_string();
         let theme = ctx.props().theme;
+        let sticky = ctx.props().sticky_header.is_some() && ctx.props().kind != Kind::LayeredSecond;
+        let class_pin_right = Self::pin_right(ctx);
+        let (class_pin_expand, style_pin_expand) = Self::pin_lead(ctx, false);
+        let (class_pin_check, style_pin_check) = Self::pin_lead(ctx, Self::has_row_detail(ctx));
 
-        html! {
+        let head = html! {
             <>
                 <tr class="list-whole-head">
                     {
@@ -58,19 +73,32 @@ where
                             }
                             Kind::Flat => {
                                 html! {
-                                    <td class="list-whole-head-check" rowspan={rowspan.clone()}>
-                                        <div onclick={onclick_all}>
-                                            <Checkbox status={check_status} {theme} />
-                                        </div>
-                                    </td>
+                                    <>
+                                        if Self::has_row_detail(ctx) {
+                                            <td
+                                                class={classes!("list-whole-head-check", "list-whole-head-expand", class_pin_expand)}
+                                                style={style_pin_expand}
+                                                rowspan={rowspan.clone()}
+                                            ></td>
+                                        }
+                                        <td
+                                            class={classes!("list-whole-head-check", class_pin_check)}
+                                            style={style_pin_check}
+                                            rowspan={rowspan.clone()}
+                                        >
+                                            <div onclick={onclick_all}>
+                                                <Checkbox status={check_status} {theme} />
+                                            </div>
+                                        </td>
+                                    </>
                                 }
                             }
                         }
                     }
                     {
                         if let Some(widths) = ctx.props().display_info.widths.first() {
-                            if let ColWidths::Pixel(ws) = widths {
-                                colspan += ws.len();
+                            if let ColWidths::Pixel(_) = widths {
+                                colspan += self.line_columns(ctx, 0).0.len();
                             }
 
                             if !self.expand_list.is_empty() {
@@ -79,10 +107,10 @@ where
 
                             html! {
                                 if self.expand_list.is_empty() {
-                                    { self.view_head_row(ctx, 0, widths) }
+                                    { self.view_head_row(ctx, 0) }
                                 } else {
                                     <td colspan={colspan.to_string()} class="list-whole-head">
-                                        { self.view_head_row(ctx, 0, widths) }
+                                        { self.view_head_row(ctx, 0) }
                                     </td>
                                 }
                             }
@@ -90,17 +118,14 @@ where
                             html! {}
                         }
                     }
-                    <td class="list-whole-head-last-column" rowspan={rowspan}>
+                    <td class={classes!("list-whole-head-last-column", class_pin_right)} rowspan={rowspan}>
+                        { self.view_column_chooser(ctx) }
                     </td>
                 </tr>
                 {
                     if ctx.props().display_info.widths.len() > 1 {
-                        let mut sum_cols: usize = 0;
-
                         html! {
                             for ctx.props().display_info.widths.iter().enumerate().map(|(row, cols)| {
-                                let first = sum_cols;
-                                sum_cols += cols.len();
                                 let style = if let ColWidths::Pixel(cols) = cols {
                                     format!("width: {}px;", cols.iter().filter_map(|x| *x).sum::<u32>())
                                 } else {
@@ -121,7 +146,7 @@ where
                                                 <div class="list-whole-head-next-lines">
                                                     <table style={style}>
                                                         <tr class="list-whole-head-next-lines">
-                                                            { self.view_head_row(ctx, first, cols) }
+                                                            { self.view_head_row(ctx, row) }
                                                         </tr>
                                                     </table>
                                                 </div>
@@ -136,31 +161,32 @@ where
                     }
                 }
             </>
+        };
+        if sticky {
+            // All the lines of the head stay at the top together.
+            html! { <thead class="list-whole-head-sticky">{ head }</thead> }
+        } else {
+            head
         }
     }
 
-    pub(super) fn view_head_row(
-        &self,
-        ctx: &Context<Self>,
-        start: usize,
-        widths: &ColWidths,
-    ) -> Html {
+    pub(super) fn view_head_row(&self, ctx: &Context<Self>, line: usize) -> Html {
         let txt = ctx.props().txt.txt.clone();
-        let varied_width = Self::varied_width(ctx, widths);
+        let (columns, widths) = self.line_columns(ctx, line);
+        let varied_width = Self::varied_width(ctx, &widths);
+        let settings = line == 0 && Self::has_column_settings(ctx);
+        let resizable = settings && matches!(widths, ColWidths::Pixel(_));
         html! {
-            for (0..widths.len()).map(|i| {
-                let index = start + i;
+            for columns.iter().enumerate().map(|(i, &index)| {
                 if let Some(title) = ctx.props().display_info.titles.get(index) {
                     let class_border = if i > 0 {
                         "list-whole-head-title-border"
                     } else {
                         ""
                     };
-                    let style = if cfg!(feature = "pumpkin") {
-                        format!("width: {}px;", ctx.props().display_info.width_full)
-                    } else {
-                        Self::style_width_height(ctx, widths, i, varied_width)
-                    };
+                    let class_dragging = (self.dragging == Some(index)).then_some("list-whole-head-title-dragging");
+                    let (class_pin, style_pin) = self.pin_column(ctx, line, i);
+                    let style = self.cell_style(ctx, &widths, i, index, varied_width) + &style_pin;
                     let style_inner = format!("width: 100%; height: {}px", ctx.props().display_info.height);
                     let onclick_sort = |index: usize| ctx.link().callback(move |_| Message::ClickSort(index));
                     let sort_status = self.sort.map_or(SortStatus::Unsorted, |s| if s.index == index {
@@ -168,9 +194,25 @@ where
                     } else {
                         SortStatus::Unsorted
                     });
+                    let ondragstart = settings.then(|| ctx.link().callback(move |_: DragEvent| Message::DragColumn(Some(index))));
+                    let ondragend = settings.then(|| ctx.link().callback(|_: DragEvent| Message::DragColumn(None)));
+                    let ondragover = settings.then(|| Callback::from(|e: DragEvent| e.prevent_default()));
+                    let ondrop = settings.then(|| ctx.link().callback(move |e: DragEvent| {
+                        e.prevent_default();
+                        Message::DropColumn(index)
+                    }));
 
                     html! {
-                        <td class={classes!("list-whole-head-title", class_border)} style={style} onclick={onclick_sort(index)}>
+                        <td
+                            class={classes!("list-whole-head-title", class_border, class_dragging, class_pin)}
+                            style={style}
+                            draggable={settings.then_some("true")}
+                            onclick={onclick_sort(index)}
+                            {ondragstart}
+                            {ondragend}
+                            {ondragover}
+                            {ondrop}
+                        >
                             <div style={style_inner} class="list-head-inner">
                                 <span class="list-whole-head-title-inner-text">
                                     { text!(txt, ctx.props().language, *title) }
@@ -179,6 +221,9 @@ where
                                     <Sort status={sort_status} />
                                 </div>
                             </div>
+                            if resizable {
+                                { Self::view_resize_handle(ctx, index) }
+                            }
                         </td>
                     }
                 } else {
@@ -188,6 +233,91 @@ where
         }
     }
 
+    /// The handle on the right border of a column title to resize the column
+    fn view_resize_handle(ctx: &Context<Self>, index: usize) -> Html {
+        let onmousedown = ctx.link().batch_callback(move |e: MouseEvent| {
+            e.prevent_default();
+            e.stop_propagation();
+            let width = e
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlElement>().ok())
+                .and_then(|handle| handle.parent_element())
+                .and_then(|td| td.dyn_into::<HtmlElement>().ok())
+                .map(|td| u32::try_from(td.offset_width()).unwrap_or_default());
+            width.map(|width| Message::StartResize(index, e.client_x(), width))
+        });
+        let onclick = Callback::from(|e: MouseEvent| e.stop_propagation());
+        html! {
+            <div class="list-whole-head-resize" draggable="false" {onmousedown} {onclick}></div>
+        }
+    }
+
+    /// Covers the page while a column is resized to follow the mouse
+    /// anywhere.
+    pub(super) fn view_resize_overlay(&self, ctx: &Context<Self>) -> Html {
+        if self.resizing.is_none() {
+            return html! {};
+        }
+        let onmousemove = ctx
+            .link()
+            .callback(|e: MouseEvent| Message::Resize(e.client_x()));
+        let onmouseup = ctx.link().callback(|_| Message::EndResize);
+        let onmouseleave = ctx.link().callback(|_| Message::EndResize);
+        html! {
+            <div class="list-whole-resize-overlay" {onmousemove} {onmouseup} {onmouseleave}></div>
+        }
+    }
+
+    /// The menu to show or hide columns and to reset the layout
+    fn view_column_chooser(&self, ctx: &Context<Self>) -> Html {
+        if !Self::has_column_settings(ctx) {
+            return html! {};
+        }
+        let txt = ctx.props().txt.txt.clone();
+        let theme = ctx.props().theme;
+        let onclick_toggle = ctx.link().callback(|_| Message::ToggleColumnChooser);
+        let onclick_reset = ctx.link().callback(|_| Message::ResetLayout);
+        let label = translate(&txt, ctx.props().language, "Columns");
+        html! {
+            <div class="list-whole-column-chooser">
+                <button
+                    type="button"
+                    class="list-whole-column-chooser"
+                    title={label.clone()}
+                    aria-label={label}
+                    aria-expanded={self.column_chooser.to_string()}
+                    onclick={onclick_toggle}
+                >
+                    { "⋮" }
+                </button>
+                if self.column_chooser {
+                    <div class="list-whole-column-chooser-list">
+                    {
+                        for self.layout.order.iter().filter_map(|&index| {
+                            let title = ctx.props().display_info.titles.get(index)?;
+                            let status = if self.layout.hidden.contains(&index) {
+                                CheckStatus::Unchecked
+                            } else {
+                                CheckStatus::Checked
+                            };
+                            let onclick = ctx.link().callback(move |_| Message::ToggleColumn(index));
+                            Some(html! {
+                                <div class="list-whole-column-chooser-item" {onclick}>
+                                    <Checkbox {status} {theme} />
+                                    { text!(txt, ctx.props().language, *title) }
+                                </div>
+                            })
+                        })
+                    }
+                        <button type="button" class="list-whole-column-chooser-reset" onclick={onclick_reset}>
+                            { translate(&txt, ctx.props().language, "Reset to default") }
+                        </button>
+                    </div>
+                }
+            </div>
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     pub(super) fn view_list(&self, ctx: &Context<Self>) -> Html {
         let (start, end) = self.item_range(ctx);
@@ -198,22 +328,19 @@ where
                 if let Some(key) = self.sorted_keys.get(index - 1) {
                     if let Some(item) = ctx.props().data.get(key) {
                         let onclick_item = |key: String| ctx.link().callback(move |_| Message::CheckItem(key.clone()));
-                        let check_status = if self.checked.contains(key) {
+                        let check_status = if self.is_checked(key) {
                             CheckStatus::Checked
                         } else {
                             CheckStatus::Unchecked
                         };
-                        let more_action_list = Rc::new(vec![
-                            ViewString::Key("Edit".to_string()),
-                            ViewString::Key("Delete".to_string()),
-                        ]);
-                        let value_candidates = Rc::new(vec![
-                            MoreAction::Edit,
-                            MoreAction::Delete,
-                        ]);
+                        let row_actions = Self::row_actions(ctx, key);
+                        let more_action_list = Rc::new(row_actions.iter().map(|a| a.label.clone()).collect::<Vec<_>>());
+                        let value_candidates = Rc::new(row_actions.iter().map(|a| a.action).collect::<Vec<_>>());
+                        let more_action_icons = Rc::new(row_actions.iter().map(|a| a.icon.clone()).collect::<Vec<_>>());
+                        let more_action_disabled = Rc::new(row_actions.iter().map(|a| a.disabled).collect::<Vec<_>>());
 
                         html! {
-                            <>
+                            <key={key.clone()}>
                             {
                                 match ctx.props().kind {
                                     Kind::LayeredFirst => {
@@ -239,17 +366,28 @@ where
                                             let expand_collapse_img = Theme::path(&theme, &format!("{file_name}.{ext}"));
                                             let style = format!("background-image: url('{expand_collapse_img}');");
                                             let onclick_expandible = |key: String| ctx.link().callback(move |_| Message::ClickExpandible(key.clone()));
+                                            let expand_label = translate(&ctx.props().txt.txt, ctx.props().language, "Expand");
                                             let list_top = if cfg!(feature = "pumpkin") {
                                                 34
                                             } else {
                                                 28
                                             };
-                                            if let Some(display_info_first) = ctx.props().display_info.widths.first() {
+                                            if ctx.props().display_info.widths.is_empty() {
+                                                html! {}
+                                            } else {
                                                 html! {
                                                     <tr class="list-whole-first-layer">
                                                         <td class={classes!("list-whole-list-first-expand", highlight_class)}>
-                                                            <div class="list-whole-list-first-expand" style={style} onclick={onclick_expandible(key.clone())}>
-                                                            </div>
+                                                            <button
+                                                                type="button"
+                                                                class="list-whole-list-first-expand"
+                                                                title={expand_label.clone()}
+                                                                aria-label={expand_label}
+                                                                aria-expanded={is_expanded.to_string()}
+                                                                {style}
+                                                                onclick={onclick_expandible(key.clone())}
+                                                            >
+                                                            </button>
                                                         </td>
                                                         <td class="list-whole-list-first-check">
                                                             <div onclick={onclick_item(key.clone())}>
@@ -260,7 +398,7 @@ where
                                                             </div>
                                                         </td>
                                                         <td colspan={cols.to_string()} class="list-whole-list-first-layer-wrapper">
-                                                            {self.view_column_row(ctx, item.columns.as_ref(), 0, display_info_first)}
+                                                            {self.view_column_row(ctx, key, item.columns.as_ref(), 0)}
                                                         </td>
                                                         <td class="list-whole-list-first-layer-more-action">
                                                             <div class="list-whole-list-flat-more-action">
@@ -272,6 +410,8 @@ where
                                                                     active={true}
                                                                     list={Rc::clone(&more_action_list)}
                                                                     candidate_values={Rc::clone(&value_candidates)}
+                                                                    list_icons={Some(Rc::clone(&more_action_icons))}
+                                                                    list_disabled={Some(Rc::clone(&more_action_disabled))}
                                                                     selected_value={Rc::clone(&self.more_action)}
                                                                     selected_value_cache={self.more_action.try_borrow().ok().and_then(|x| *x)}
                                                                     align_left={false}
@@ -283,8 +423,6 @@ where
                                                         </td>
                                                     </tr>
                                                 }
-                                            } else {
-                                                html! {}
                                             }
                                         } else {
                                             html! {}
@@ -307,14 +445,30 @@ where
                                             28
                                         };
 
+                                        let manual = self.is_manual_order(ctx);
+                                        let (class_pin_check, style_pin_check) =
+                                            Self::pin_lead(ctx, Self::has_row_detail(ctx));
+                                        let class_drop = (manual && self.dragging_row.as_ref().is_some_and(|k| k != key))
+                                            .then_some("list-whole-list-drop-target");
+                                        let ondragover = manual.then(|| Callback::from(|e: DragEvent| e.prevent_default()));
+                                        let ondrop = manual.then(|| {
+                                            let key = key.clone();
+                                            ctx.link().callback(move |e: DragEvent| {
+                                                e.prevent_default();
+                                                Message::DropRow(key.clone())
+                                            })
+                                        });
+
                                         html! {
                                             <>
-                                                <tr class={class}>
+                                                <tr class={classes!(class, class_drop)} {ondragover} {ondrop}>
                                                     {
                                                         if ctx.props().kind == Kind::LayeredSecond {
                                                             html! {
                                                                 <td class="list-whole-list-layered-second list-whole-highlight-left"></td>
                                                             }
+                                                        } else if Self::has_row_detail(ctx) {
+                                                            self.view_detail_toggle(ctx, key, &rowspan)
                                                         } else {
                                                             html! {}
                                                         }
@@ -326,7 +480,14 @@ where
                                                             }
                                                         } else {
                                                             html! {
-                                                                <td class="list-whole-list-flat-check" rowspan={rowspan.clone()}>
+                                                                <td
+                                                                    class={classes!("list-whole-list-flat-check", class_pin_check)}
+                                                                    style={style_pin_check}
+                                                                    rowspan={rowspan.clone()}
+                                                                >
+                                                                    if manual {
+                                                                        { self.view_row_handle(ctx, key) }
+                                                                    }
                                                                     <div onclick={onclick_item(key.clone())}>
                                                                         <Checkbox
                                                                             status={check_status}
@@ -339,18 +500,18 @@ where
                                                     }
                                                     {
                                                         if let Some(widths) = ctx.props().display_info.widths.first() {
-                                                            if let ColWidths::Pixel(ws) = widths {
-                                                                colspan += ws.len();
+                                                            if let ColWidths::Pixel(_) = widths {
+                                                                colspan += self.line_columns(ctx, 0).0.len();
                                                             }
 
                                                             html! {
-                                                                { self.view_column_row(ctx, item.columns.as_ref(), 0, widths) }
+                                                                { self.view_column_row(ctx, key, item.columns.as_ref(), 0) }
                                                             }
                                                         } else {
                                                             html! {}
                                                         }
                                                     }
-                                                    <td class="list-whole-list-flat-more-action" rowspan={rowspan}>
+                                                    <td class={classes!("list-whole-list-flat-more-action", Self::pin_right(ctx))} rowspan={rowspan}>
                                                         <div class="list-whole-list-flat-more-action">
                                                             <SelectMini::<MoreAction, Self>
                                                                 txt={ctx.props().txt.clone()}
@@ -360,6 +521,8 @@ where
                                                                 active={true}
                                                                 list={Rc::clone(&more_action_list)}
                                                                 candidate_values={Rc::clone(&value_candidates)}
+                                                                list_icons={Some(Rc::clone(&more_action_icons))}
+                                                                list_disabled={Some(Rc::clone(&more_action_disabled))}
                                                                 selected_value={Rc::clone(&self.more_action)}
                                                                 selected_value_cache={self.more_action.try_borrow().ok().and_then(|x| *x)}
                                                                 align_left={false}
@@ -372,12 +535,8 @@ where
                                                 </tr>
                                                 {
                                                     if ctx.props().display_info.widths.len() > 1 {
-                                                        let mut sum_cols: usize = 0;
-
                                                         html! {
                                                             for ctx.props().display_info.widths.iter().enumerate().map(|(row, cols)| {
-                                                                let first = sum_cols;
-                                                                sum_cols += cols.len();
                                                                 let style = if let ColWidths::Pixel(cols) = cols {
                                                                     format!("width: {}px;", cols.iter().filter_map(|x| *x).sum::<u32>())
                                                                 } else {
@@ -395,7 +554,7 @@ where
                                                                                 <div class="list-whole-column-next-lines">
                                                                                     <table style={style}>
                                                                                         <tr style={height}>
-                                                                                            { self.view_column_row(ctx, item.columns.as_ref(), first, cols) }
+                                                                                            { self.view_column_row(ctx, key, item.columns.as_ref(), row) }
                                                                                         </tr>
                                                                                     </table>
                                                                                 </div>
@@ -409,6 +568,7 @@ where
                                                         html! {}
                                                     }
                                                 }
+                                                { self.view_detail(ctx, key) }
                                             </>
                                         }
                                     }
@@ -477,6 +637,8 @@ where
                                                     input_conf={input_conf.clone()}
                                                     input_second_data={Some(second_data.clone())}
                                                     messages={ctx.props().messages.clone()}
+                                                    undo_delete={ctx.props().undo_delete}
+                                                    on_delete={on_delete_of_second(ctx.props().on_delete_second.as_ref(), key)}
                                                     {theme}
                                                 />
                                             }
@@ -502,6 +664,146 @@ where
         }
     }
 
+    /// The handle to drag the row or to move it with the arrow keys in
+    /// `SortListKind::Manual`
+    fn view_row_handle(&self, ctx: &Context<Self>, key: &str) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let ondragstart = {
+            let key = key.to_string();
+            ctx.link().callback(move |e: DragEvent| {
+                // Firefox starts dragging only with data set.
+                if let Some(data) = e.data_transfer() {
+                    let _ = data.set_data("text/plain", &key);
+                }
+                Message::DragRow(Some(key.clone()))
+            })
+        };
+        let ondragend = ctx.link().callback(|_: DragEvent| Message::DragRow(None));
+        let onkeydown = {
+            let key = key.to_string();
+            ctx.link().batch_callback(move |e: KeyboardEvent| {
+                let down = match e.key().as_str() {
+                    "ArrowUp" => false,
+                    "ArrowDown" => true,
+                    _ => return None,
+                };
+                e.prevent_default();
+                Some(Message::MoveRow(key.clone(), down))
+            })
+        };
+        let node_ref = if self.focused_row.as_deref() == Some(key) {
+            self.row_handle.clone()
+        } else {
+            NodeRef::default()
+        };
+        html! {
+            <div
+                class="list-whole-row-handle"
+                ref={node_ref}
+                draggable="true"
+                tabindex="0"
+                title={translate(&txt, ctx.props().language, "Drag or press the arrow keys to move")}
+                {ondragstart}
+                {ondragend}
+                {onkeydown}
+            >
+                { "⠿" }
+            </div>
+        }
+    }
+
+    /// The area to drop a row being dragged to move it to the previous or the
+    /// next page
+    pub(super) fn view_page_drop(&self, ctx: &Context<Self>, next: bool) -> Html {
+        if self.dragging_row.is_none() {
+            return html! {};
+        }
+        let (start, end) = self.item_range(ctx);
+        if (next && end >= self.sorted_keys.len()) || (!next && start <= 1) {
+            return html! {};
+        }
+        let txt = ctx.props().txt.txt.clone();
+        let label = if next {
+            "Move to the next page"
+        } else {
+            "Move to the previous page"
+        };
+        let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+        let ondrop = ctx.link().callback(move |e: DragEvent| {
+            e.prevent_default();
+            Message::DropRowToPage(next)
+        });
+        html! {
+            <tr>
+                <td colspan={(ctx.props().display_info.titles.len() + 3).to_string()}>
+                    <div class="list-whole-list-page-drop" {ondragover} {ondrop}>
+                        { translate(&txt, ctx.props().language, label) }
+                    </div>
+                </td>
+            </tr>
+        }
+    }
+
+    /// The button to expand or collapse the row to show `row_detail`
+    fn view_detail_toggle(&self, ctx: &Context<Self>, key: &str, rowspan: &str) -> Html {
+        let expanded = self.expanded_details.contains(key);
+        let file_name = if expanded {
+            "collapse-list"
+        } else {
+            "expand-list"
+        };
+        let ext = if cfg!(feature = "pumpkin") {
+            "svg"
+        } else {
+            "png"
+        };
+        let img = Theme::path(&ctx.props().theme, &format!("{file_name}.{ext}"));
+        let style = format!("background-image: url('{img}');");
+        let key = key.to_string();
+        let onclick = ctx
+            .link()
+            .callback(move |_| Message::ToggleDetail(key.clone()));
+        let (class_pin, style_pin) = Self::pin_lead(ctx, false);
+        let label = translate(&ctx.props().txt.txt, ctx.props().language, "Details");
+        html! {
+            <td
+                class={classes!("list-whole-list-first-expand", class_pin)}
+                style={style_pin}
+                rowspan={rowspan.to_string()}
+            >
+                <button
+                    type="button"
+                    class="list-whole-list-first-expand"
+                    title={label.clone()}
+                    aria-label={label}
+                    aria-expanded={expanded.to_string()}
+                    {style}
+                    {onclick}
+                >
+                </button>
+            </td>
+        }
+    }
+
+    /// The row below an expanded row showing `row_detail`
+    fn view_detail(&self, ctx: &Context<Self>, key: &str) -> Html {
+        let Some(row_detail) = ctx.props().row_detail.as_ref() else {
+            return html! {};
+        };
+        if !Self::has_row_detail(ctx) || !self.expanded_details.contains(key) {
+            return html! {};
+        }
+        // the toggle, the check, the columns of the first line and the more action
+        let colspan = self.line_columns(ctx, 0).0.len() + 3;
+        html! {
+            <tr class="list-whole-list-detail">
+                <td class="list-whole-list-detail" colspan={colspan.to_string()}>
+                    { row_detail.emit(key.to_string()) }
+                </td>
+            </tr>
+        }
+    }
+
     #[must_use]
     fn varied_width(ctx: &Context<Self>, widths: &ColWidths) -> Option<u32> {
         match widths {
@@ -543,25 +845,121 @@ where
         format!("{width} {height}")
     }
 
+    /// Returns the style of the cell at the position `i` in a line, showing
+    /// the column `index`.
+    fn cell_style(
+        &self,
+        ctx: &Context<Self>,
+        widths: &ColWidths,
+        i: usize,
+        index: usize,
+        varied_width: Option<u32>,
+    ) -> String {
+        if cfg!(feature = "pumpkin") {
+            self.pixel_width(ctx, widths, i, index, varied_width)
+                .map_or_else(String::new, |width| format!("width: {width}px;"))
+        } else {
+            Self::style_width_height(ctx, widths, i, varied_width)
+        }
+    }
+
+    /// Returns the width in pixels of the cell at the position `i` in a line,
+    /// showing the column `index`, or `None` if it is not known in pixels.
+    fn pixel_width(
+        &self,
+        ctx: &Context<Self>,
+        widths: &ColWidths,
+        i: usize,
+        index: usize,
+        varied_width: Option<u32>,
+    ) -> Option<u32> {
+        if cfg!(feature = "pumpkin") {
+            return Some(
+                self.layout
+                    .widths
+                    .get(&index)
+                    .copied()
+                    .unwrap_or(ctx.props().display_info.width_full),
+            );
+        }
+        match widths {
+            ColWidths::Pixel(ws) => ws.get(i).and_then(|w| w.or(varied_width)),
+            ColWidths::Ratio(_) => None,
+        }
+    }
+
+    /// Returns the class and the style pinning the column at the position `i`
+    /// of a line on the left, if it is pinned. Only the first line is pinned,
+    /// and only as far as the widths of the columns on its left are known in
+    /// pixels. Under `pumpkin`, the table resolves the widths of the cells, so
+    /// only the first column, whose offset is the width of the lead columns,
+    /// is pinned.
+    fn pin_column(
+        &self,
+        ctx: &Context<Self>,
+        line: usize,
+        i: usize,
+    ) -> (Option<&'static str>, String) {
+        let Some(pinned) = Self::pinned_columns(ctx) else {
+            return (None, String::new());
+        };
+        if line > 0 || i >= pinned || (cfg!(feature = "pumpkin") && i > 0) {
+            return (None, String::new());
+        }
+        let (columns, widths) = self.line_columns(ctx, 0);
+        let varied_width = Self::varied_width(ctx, &widths);
+        let lead = u32::from(Self::has_row_detail(ctx)) * EXPAND_COLUMN_WIDTH + CHECK_COLUMN_WIDTH;
+        let left = columns
+            .iter()
+            .enumerate()
+            .take(i)
+            .try_fold(lead, |left, (i, &index)| {
+                self.pixel_width(ctx, &widths, i, index, varied_width)
+                    .map(|width| left + width)
+            });
+        left.map_or((None, String::new()), |left| {
+            (Some("list-whole-pinned"), format!(" left: {left}px;"))
+        })
+    }
+
+    /// Returns the class and the style pinning a column before the first
+    /// column, the column to expand rows or the check column after it, on the
+    /// left.
+    fn pin_lead(ctx: &Context<Self>, after_expand: bool) -> (Option<&'static str>, Option<String>) {
+        if Self::pinned_columns(ctx).is_none() {
+            return (None, None);
+        }
+        let left = u32::from(after_expand) * EXPAND_COLUMN_WIDTH;
+        (
+            Some("list-whole-pinned list-whole-pinned-lead"),
+            Some(format!("left: {left}px;")),
+        )
+    }
+
+    /// Returns the class pinning the more-action column on the right.
+    fn pin_right(ctx: &Context<Self>) -> Option<&'static str> {
+        Self::pinned_columns(ctx).map(|_| "list-whole-pinned-right")
+    }
+
     pub(super) fn view_column_row(
         &self,
         ctx: &Context<Self>,
+        key: &str,
         columns: &[Column],
-        start: usize,
-        widths: &ColWidths,
+        line: usize,
     ) -> Html {
-        let varied_width = Self::varied_width(ctx, widths);
+        let (indices, widths) = self.line_columns(ctx, line);
+        let varied_width = Self::varied_width(ctx, &widths);
         let base_class = if ctx.props().kind == Kind::LayeredFirst {
             "list-whole-list-first-layer"
         } else {
             "list-whole-list-flat"
         };
         html! {
-            for (0..widths.len()).map(|i| {
-                let index = start + i;
+            for indices.iter().enumerate().map(|(i, &index)| {
                 if let Some(col) = columns.get(index) {
                     let class = if ctx.props().kind == Kind::LayeredSecond || ctx.props().kind == Kind::Flat {
-                        if start == 0 {
+                        if line == 0 {
                             "list-whole-column-next-lines-first"
                         } else if i > 0 {
                             "list-whole-column-next-lines"
@@ -571,17 +969,14 @@ where
                     } else {
                         ""
                     };
-                    let style = if cfg!(feature = "pumpkin") {
-                        format!("width: {}px;", ctx.props().display_info.width_full)
-                    } else {
-                        Self::style_width_height(ctx, widths, i, varied_width)
-                    };
+                    let (class_pin, style_pin) = self.pin_column(ctx, line, i);
+                    let style = self.cell_style(ctx, &widths, i, index, varied_width) + &style_pin;
                     let onclick_close = {
                         ctx.link().callback(move |_| Message::CloseModal)
                     };
                     html! {
-                        <td class={classes!(base_class, class)} style={style}>
-                            { Self::view_column(ctx, index, col) }
+                        <td class={classes!(base_class, class, class_pin)} style={style}>
+                            { self.view_cell(ctx, key, index, col) }
                             {
                                 if let Some(modal) = &self.modal {
                                     let modal_content = Some(Html::from_html_unchecked(AttrValue::from_str(&modal.1).expect("AttrValue never returns Err.")));
@@ -784,16 +1179,17 @@ where
         }
 
         let txt = ctx.props().txt.txt.clone();
+        let count = self.checked_keys().len();
         if cfg!(feature = "pumpkin") {
             Some(format!(
                 "{} {}",
-                self.checked.len(),
+                count,
                 text!(txt, ctx.props().language, "selected")
             ))
         } else {
             Some(format!(
                 "{}{} {} {}{}",
-                self.checked.len(),
+                count,
                 text!(txt, ctx.props().language, "(items of)"),
                 text!(txt, ctx.props().language, "chosen"),
                 text!(txt, ctx.props().language, &ctx.props().title)
@@ -804,20 +1200,280 @@ where
         }
     }
 
+    pub(super) fn view_confirm_action(&self, ctx: &Context<Self>) -> Html {
+        let Some(pending) = &self.confirm_action else {
+            return html! {};
+        };
+        let Some(confirm) = &pending.action().confirm else {
+            return html! {};
+        };
+        let message = match confirm {
+            ViewString::Key(key) => (key.clone(), ModalTextStyle::Key),
+            ViewString::Raw(raw) => (raw.clone(), ModalTextStyle::RawNormal),
+        };
+        html! {
+            <Modal<Self>
+                txt={ctx.props().txt.clone()}
+                language={ctx.props().language}
+                kind={ModalType::Info}
+                align_button={ModalAlign::Row}
+                title_messages={Rc::new(vec![vec![message]])}
+                option_messages={Rc::new(vec!["OK".to_string(), "Cancel".to_string()])}
+                parent_messages={vec![Message::ConfirmAction, Message::CancelAction]}
+                parent_cancel_message={Message::CancelAction}
+                theme={ctx.props().theme}
+            />
+        }
+    }
+
     pub(super) fn view_action_bar(&self, ctx: &Context<Self>) -> Html {
-        if !cfg!(feature = "pumpkin")
-            || !matches!(ctx.props().kind, Kind::Flat | Kind::LayeredFirst)
-        {
+        if !matches!(ctx.props().kind, Kind::Flat | Kind::LayeredFirst) {
             return html! {};
         }
 
         let Some(msg) = self.selection_message(ctx) else {
             return html! {};
         };
-
         html! {
             <div class="list-whole-action-bar">
-                { self.view_delete_checked(ctx, msg) }
+                // The classic design shows `view_delete_checked` with the pages.
+                if cfg!(feature = "pumpkin") {
+                    { self.view_delete_checked(ctx, msg) }
+                }
+                { Self::view_bulk_actions(ctx) }
+                { self.view_check_all_pages(ctx) }
+            </div>
+        }
+    }
+
+    /// The footer showing `aggregations` of the rows in `aggregation_scope`
+    pub(super) fn view_aggregation(&self, ctx: &Context<Self>) -> Html {
+        if ctx.props().kind != Kind::Flat || ctx.props().aggregations.is_empty() {
+            return html! {};
+        }
+        let txt = ctx.props().txt.txt.clone();
+        let keys = scoped_keys(
+            &ctx.props().aggregation_scope,
+            &self.sorted_keys,
+            &self.checked,
+            self.all_pages_checked,
+        );
+        let rows = keys
+            .into_iter()
+            .filter_map(|key| ctx.props().data.get(key))
+            .collect::<Vec<_>>();
+        let (columns, widths) = self.line_columns(ctx, 0);
+        let varied_width = Self::varied_width(ctx, &widths);
+        let (class_pin_expand, style_pin_expand) = Self::pin_lead(ctx, false);
+        let (class_pin_check, style_pin_check) = Self::pin_lead(ctx, Self::has_row_detail(ctx));
+
+        html! {
+            <tr class="list-whole-aggregation">
+                if Self::has_row_detail(ctx) {
+                    <td class={classes!("list-whole-aggregation-lead", class_pin_expand)} style={style_pin_expand}></td>
+                }
+                <td class={classes!("list-whole-aggregation-lead", class_pin_check)} style={style_pin_check}></td>
+                {
+                    for columns.iter().enumerate().map(|(i, &index)| {
+                        let (class_pin, style_pin) = self.pin_column(ctx, 0, i);
+                        let style = self.cell_style(ctx, &widths, i, index, varied_width) + &style_pin;
+                        let mut aggregations = ctx.props().aggregations.get(&index).cloned().unwrap_or_default();
+                        aggregations.sort_unstable();
+                        aggregations.dedup();
+                        let values = aggregations.into_iter().filter_map(|aggregation| {
+                            aggregate(aggregation, rows.iter().filter_map(|row| row.columns.get(index)))
+                                .map(|value| (aggregation, value))
+                        });
+                        html! {
+                            <td class={classes!("list-whole-aggregation", class_pin)} {style}>
+                            {
+                                for values.map(|(aggregation, value)| html! {
+                                    <div class="list-whole-aggregation-value">
+                                        <span class="list-whole-aggregation-label">
+                                            { translate(&txt, ctx.props().language, aggregation.label()) }
+                                        </span>
+                                        { value }
+                                    </div>
+                                })
+                            }
+                            </td>
+                        }
+                    })
+                }
+                <td class={classes!("list-whole-aggregation-lead", Self::pin_right(ctx))}></td>
+            </tr>
+        }
+    }
+
+    /// The notification of the deleted rows with the action to undo the
+    /// deletion
+    pub(super) fn view_undo_notices(&self, ctx: &Context<Self>) -> Html {
+        if !Self::can_undo_delete(ctx) {
+            return html! {};
+        }
+        let on_action = ctx.link().batch_callback(|(serial, id): (usize, String)| {
+            (id == UNDO_ACTION).then_some(Message::UndoDelete(serial))
+        });
+        html! {
+            <Notification
+                txt={ctx.props().txt.clone()}
+                language={ctx.props().language}
+                list={Rc::clone(&self.undo_notices)}
+                serial={self.undo_notices_serial}
+                {on_action}
+                theme={ctx.props().theme}
+            />
+        }
+    }
+
+    fn view_bulk_actions(ctx: &Context<Self>) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        html! {
+            <div class="list-whole-bulk-actions">
+            {
+                for ctx.props().bulk_actions.iter().enumerate().map(|(index, action)| {
+                    let label = match &action.label {
+                        ViewString::Key(key) => text!(txt, ctx.props().language, key).to_string(),
+                        ViewString::Raw(raw) => raw.clone(),
+                    };
+                    let onclick = ctx.link().callback(move |_| Message::DoBulkAction(index));
+                    html! {
+                        <button
+                            type="button"
+                            class="list-whole-bulk-action"
+                            disabled={action.disabled}
+                            {onclick}
+                        >
+                            if let Some(icon) = &action.icon {
+                                <img src={icon.clone()} class="list-whole-bulk-action" />
+                            }
+                            { label }
+                        </button>
+                    }
+                })
+            }
+            </div>
+        }
+    }
+
+    /// Offers to check all rows across pages once all rows of the page are
+    /// checked.
+    fn view_check_all_pages(&self, ctx: &Context<Self>) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let total = self.sorted_keys.len();
+        if self.all_pages_checked {
+            let onclick = ctx.link().callback(|_| Message::ClearChecked);
+            html! {
+                <div class="list-whole-check-all-pages">
+                    { format!("{} {total}", translate(&txt, ctx.props().language, "All rows selected:")) }
+                    <button type="button" class="list-whole-check-all-pages" {onclick}>
+                        { translate(&txt, ctx.props().language, "Clear selection") }
+                    </button>
+                </div>
+            }
+        } else if self.check_status(ctx) == CheckStatus::Checked
+            && self.checked_keys().len() < total
+        {
+            let onclick = ctx.link().callback(|_| Message::CheckAllPages);
+            html! {
+                <div class="list-whole-check-all-pages">
+                    <button type="button" class="list-whole-check-all-pages" {onclick}>
+                        { format!("{} ({total})", translate(&txt, ctx.props().language, "Select all rows")) }
+                    </button>
+                </div>
+            }
+        } else {
+            html! {}
+        }
+    }
+
+    /// Shows the cell, or its editor if it is being edited in place.
+    fn view_cell(&self, ctx: &Context<Self>, key: &str, index: usize, col: &Column) -> Html {
+        if let Some(edit) = self
+            .editing
+            .as_ref()
+            .filter(|edit| edit.key == key && edit.column == index)
+        {
+            return Self::view_cell_editor(ctx, edit);
+        }
+        if !Self::is_inline_editable(ctx, index) {
+            return Self::view_column(ctx, index, col);
+        }
+        let key = key.to_string();
+        let onclick = if matches!(col, Column::Checkbox(_)) {
+            ctx.link()
+                .callback(move |_| Message::ToggleCell(key.clone(), index))
+        } else {
+            ctx.link()
+                .callback(move |_| Message::EditCell(key.clone(), index))
+        };
+        html! {
+            <div class="list-whole-cell-editable" {onclick}>
+                { Self::view_column(ctx, index, col) }
+            </div>
+        }
+    }
+
+    fn view_cell_editor(ctx: &Context<Self>, edit: &CellEdit) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let onkeydown = ctx
+            .link()
+            .batch_callback(|e: KeyboardEvent| match e.key().as_str() {
+                "Enter" => Some(Message::SaveCell),
+                "Escape" => Some(Message::CancelCell),
+                _ => None,
+            });
+        let conf = column_conf(&ctx.props().input_conf, edit.column).map(|conf| &**conf);
+        let editor = if let Some(InputConfig::SelectSingle(config)) = conf {
+            // a selection is saved at once
+            let onchange = ctx.link().batch_callback(|e: Event| {
+                e.target()
+                    .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                    .map_or_else(Vec::new, |select| {
+                        vec![Message::InputCell(select.value()), Message::SaveCell]
+                    })
+            });
+            html! {
+                <select class="list-whole-cell-editor" ref={edit.editor.clone()} {onchange} {onkeydown}>
+                    if !config.ess.required {
+                        <option value="" selected={edit.value.is_empty()}></option>
+                    }
+                    { for config.options.iter().map(|(key, value)| html! {
+                        <option value={key.clone()} selected={*key == edit.value}>
+                            { value.to_string_txt(&txt, ctx.props().language) }
+                        </option>
+                    }) }
+                </select>
+            }
+        } else {
+            let oninput = ctx.link().batch_callback(|e: InputEvent| {
+                e.target()
+                    .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+                    .map(|input| Message::InputCell(input.value()))
+            });
+            let maxlength = if let Some(InputConfig::Text(config)) = conf {
+                config.length.map(|l| l.to_string())
+            } else {
+                None
+            };
+            let class = if edit.invalid.is_some() {
+                "list-whole-cell-editor list-whole-cell-editor-alert"
+            } else {
+                "list-whole-cell-editor"
+            };
+            html! {
+                <input type="text" {class} ref={edit.editor.clone()} value={edit.value.clone()}
+                    {maxlength} {oninput} {onkeydown} />
+            }
+        };
+        html! {
+            <div class="list-whole-cell-edit">
+                { editor }
+                if let Some(msg) = &edit.invalid {
+                    <div class="list-whole-cell-edit-alert">
+                        { translate(&txt, ctx.props().language, msg) }
+                    </div>
+                }
             </div>
         }
     }
@@ -887,20 +1543,21 @@ where
                     .filter_map(|t| {
                         ctx.props().input_conf.get(index).and_then(|x| {
                             if let InputConfig::Tag(config) = &**x {
-                                config.name_map.get(t).cloned()
+                                config.tags.get(t)
                             } else {
                                 None
                             }
                         })
                     })
-                    .collect::<Vec<String>>();
-                list.sort_unstable();
+                    .collect::<Vec<_>>();
+                list.sort_unstable_by(|a, b| a.name.cmp(&b.name));
                 html! {
                     <div class="list-whole-tag">
                     {
                         for list.iter().map(|item| html! {
-                            <div class="list-whole-tag-item">
-                                { item.clone() }
+                            <div class="list-whole-tag-item" title={item.description.clone()}>
+                                { view_tag_color(item) }
+                                { item.name.clone() }
                             </div>
                         })
                     }
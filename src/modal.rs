@@ -1,16 +1,17 @@
 //! Modal dialog components for displaying overlaid content.
 //!
 //! This module provides modal dialog functionality with customizable styling,
-//! button layouts, and content types.
+//! button layouts, and content types. Keyboard focus is kept inside the modal
+//! while it is open, and Escape cancels it.
 
 use std::{marker::PhantomData, rc::Rc, str::FromStr};
 
 use json_gettext::get_text;
 use wasm_bindgen::JsCast;
-use web_sys::{Event, HtmlElement};
-use yew::{AttrValue, Callback, Component, Context, Html, Properties, classes, html};
+use web_sys::{Event, HtmlElement, KeyboardEvent};
+use yew::{AttrValue, Callback, Component, Context, Html, NodeRef, Properties, classes, html};
 
-use crate::{Texts, Theme, define_u32_consts, language::Language, text};
+use crate::{Texts, Theme, define_u32_consts, language::Language, sanitize_html, text};
 
 const MAX_HEIGHT: u32 = 700;
 const DEFAULT_MIN_HEIGHT: u32 = 306;
@@ -19,6 +20,8 @@ const DEFAULT_MAX_OPTION_WIDTH: u32 = 440;
 const INITIAL_BATCH_SIZE: usize = 50;
 const LOAD_BATCH_SIZE: usize = 50;
 const SCROLL_LOAD_THRESHOLD: i32 = 40;
+const FOCUSABLE_SELECTOR: &str =
+    "input, select, textarea, button, a[href], [tabindex]:not([tabindex='-1'])";
 
 fn initial_visible_count(total: usize) -> usize {
     total.min(INITIAL_BATCH_SIZE)
@@ -62,8 +65,10 @@ pub enum TextStyle {
     RawNormal,
     /// Raw text with bold styling
     RawBold,
-    /// Raw HTML content
+    /// Raw HTML content, sanitized with [`sanitize_html`] before rendering
     RawHtml,
+    /// Raw HTML content rendered as-is; only for trusted content
+    UnsafeRawHtml,
 }
 
 #[derive(PartialEq, Eq)]
@@ -101,11 +106,17 @@ where
     pub parent_cancel_message: T::Message,
     #[prop_or(None)]
     pub theme: Option<Theme>,
+    /// Extra content, such as an input field, shown below the messages
+    #[prop_or_default]
+    pub children: Html,
 }
 
 pub struct Model<T> {
     phantom: PhantomData<T>,
     visible_count: usize,
+    contents: NodeRef,
+    /// The element focused before the modal opened, refocused when it closes
+    opener: Option<HtmlElement>,
 }
 
 impl<T> Component for Model<T>
@@ -121,6 +132,35 @@ where
         Self {
             phantom: PhantomData,
             visible_count: initial_visible_count(total),
+            contents: NodeRef::default(),
+            opener: gloo_utils::document()
+                .active_element()
+                .and_then(|element| element.dyn_into::<HtmlElement>().ok()),
+        }
+    }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+        // Focus moves into the modal when it opens, and back into it if a
+        // re-render removed the focused element.
+        let inside = gloo_utils::document()
+            .active_element()
+            .is_some_and(|active| {
+                self.contents
+                    .cast::<web_sys::Node>()
+                    .is_some_and(|contents| contents.contains(Some(&active)))
+            });
+        if (first_render || !inside)
+            && let Some(first) = focusable_elements(&self.contents).first()
+        {
+            let _ = first.focus();
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        if let Some(opener) = self.opener.take()
+            && opener.is_connected()
+        {
+            let _ = opener.focus();
         }
     }
 
@@ -249,12 +289,12 @@ where
         let messages_class = classes!(
             "modal-messages",
             (!cfg!(feature = "pumpkin")
-                && ctx
-                    .props()
-                    .title_messages
-                    .iter()
-                    .flatten()
-                    .any(|(_, t)| { matches!(t, TextStyle::RawNormal | TextStyle::RawHtml) }))
+                && ctx.props().title_messages.iter().flatten().any(|(_, t)| {
+                    matches!(
+                        t,
+                        TextStyle::RawNormal | TextStyle::RawHtml | TextStyle::UnsafeRawHtml
+                    )
+                }))
             .then_some("modal-messages-raw")
         );
         let total_items = ctx.props().title_messages.len();
@@ -275,9 +315,28 @@ where
         } else {
             Callback::default()
         };
+        let onkeydown = {
+            let link = ctx.link().clone();
+            let contents = self.contents.clone();
+            Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+                "Escape" => {
+                    e.prevent_default();
+                    link.send_message(Message::Close);
+                }
+                "Tab" => trap_focus(&contents, &e),
+                _ => (),
+            })
+        };
         html! {
             <div class="modal-outer">
-                <div class={modal_contents} style={style}>
+                <div
+                    class={modal_contents}
+                    style={style}
+                    role="dialog"
+                    aria-modal="true"
+                    ref={self.contents.clone()}
+                    {onkeydown}
+                >
                     if cfg!(feature="pumpkin") {
                         <div class="modal-icon-close">
                             { title_header }
@@ -310,9 +369,6 @@ where
                                     <div key={index} class="modal-message-item">
                                     {
                                         for ms.iter().map(|(m, t)| {
-                                            let v_node = Html::from_html_unchecked(
-                                                AttrValue::from_str(m).expect("AttrValue never returns Err.")
-                                            );
                                             match t {
                                                 TextStyle::Key => html! {
                                                     { text!(txt, ctx.props().language, m) }
@@ -326,7 +382,12 @@ where
                                                     </div>
                                                 },
                                                 TextStyle::RawHtml => html! {
-                                                    { v_node }
+                                                    { Html::from_html_unchecked(AttrValue::from(sanitize_html(m))) }
+                                                },
+                                                TextStyle::UnsafeRawHtml => html! {
+                                                    { Html::from_html_unchecked(
+                                                        AttrValue::from_str(m).expect("AttrValue never returns Err.")
+                                                    ) }
                                                 }
                                             }
                                         })
@@ -336,6 +397,7 @@ where
                             })
                     }
                     </div>
+                    { ctx.props().children.clone() }
                     if cfg!(feature="pumpkin") {
                         if ctx.props().height.is_none() {
                             <img src={ divider_img } class="modal-divider" />
@@ -350,12 +412,21 @@ where
                                 "modal-button-item-option"
                             };
                             let onclick_button = ctx.link().callback(move |_| Message::ClickButton(index));
+                            let onkeydown_button = ctx.link().batch_callback(move |e: KeyboardEvent| {
+                                matches!(e.key().as_str(), "Enter" | " ").then(|| {
+                                    e.prevent_default();
+                                    Message::ClickButton(index)
+                                })
+                            });
 
                             html! {
                                 <div
                                     class={classes!(button_class, button_color_class)}
                                     style={button_style.clone()}
+                                    role="button"
+                                    tabindex="0"
                                     onclick={onclick_button}
+                                    onkeydown={onkeydown_button}
                                 >
                                     { text!(txt, ctx.props().language, m) }
                                 </div>
@@ -368,3 +439,42 @@ where
         }
     }
 }
+
+fn focusable_elements(contents: &NodeRef) -> Vec<HtmlElement> {
+    let Some(nodes) = contents
+        .cast::<HtmlElement>()
+        .and_then(|c| c.query_selector_all(FOCUSABLE_SELECTOR).ok())
+    else {
+        return Vec::new();
+    };
+    (0..nodes.length())
+        .filter_map(|i| nodes.item(i))
+        .filter_map(|node| node.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Keeps keyboard focus inside the modal by wrapping Tab and Shift+Tab
+/// around its first and last focusable elements.
+fn trap_focus(contents: &NodeRef, e: &KeyboardEvent) {
+    let elements = focusable_elements(contents);
+    let (Some(first), Some(last)) = (elements.first(), elements.last()) else {
+        e.prevent_default();
+        return;
+    };
+    let active = gloo_utils::document().active_element();
+    let position = active.and_then(|active| {
+        elements
+            .iter()
+            .position(|element| AsRef::<web_sys::Element>::as_ref(element) == &active)
+    });
+    let target = match position {
+        None => Some(first),
+        Some(0) if e.shift_key() => Some(last),
+        Some(i) if !e.shift_key() && i + 1 == elements.len() => Some(first),
+        Some(_) => None,
+    };
+    if let Some(target) = target {
+        e.prevent_default();
+        let _ = target.focus();
+    }
+}
@@ -1,20 +1,36 @@
 //! Notification system for displaying success and error messages.
 //!
 //! This module provides a notification component that can display timed messages
-//! with different categories (success/failure) and automatic dismissal.
+//! with different categories (success, failure, information and warning),
+//! optional action buttons and automatic dismissal. Repeated identical messages
+//! can be merged and the number of visible notifications can be capped. Shown
+//! notifications can be recorded in a [`History`] for later review.
+
+mod error;
+mod history;
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::rc::Rc;
 use std::time::Duration;
 
+pub use error::{
+    DefaultErrorMapper, ErrorMapper, GraphQLError, GraphQLErrorExtensions, HttpStatusClass,
+    PathSegment, ResponseError, gen_error_notification,
+};
 use gloo_timers::callback::Timeout;
+pub use history::{
+    DEFAULT_HISTORY_CAPACITY, History, HistoryEntry, HistoryFilter, Model as HistoryPanel,
+};
+use jiff::Timestamp;
 use json_gettext::get_text;
 use num_traits::ToPrimitive;
-use yew::{Component, Context, Html, Properties, html};
+use serde::{Deserialize, Serialize};
+use yew::{Callback, Component, Context, Html, Properties, classes, html};
 
-use crate::{Texts, Theme, define_u32_consts, language::Language, text, window_inner_height};
+use crate::language::{Language, translate};
+use crate::{Texts, Theme, define_u32_consts, text, window_inner_height};
 
 #[cfg(feature = "pumpkin")]
 define_u32_consts! {
@@ -26,22 +42,74 @@ define_u32_consts! {
 }
 
 /// Category of notification determining visual styling.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Category {
     /// Error or failure notification (red styling)
     Fail,
     /// Success notification (green styling)
     Success,
+    /// Informational notification (blue styling)
+    Info,
+    /// Warning notification (amber styling)
+    Warning,
 }
 
 const SUCCESS_COLOR: &str = "#83CA29";
 const FAIL_COLOR: &str = "#B5131A";
+const INFO_COLOR: &str = "#2F80ED";
+const WARNING_COLOR: &str = "#F2A516";
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+impl Category {
+    /// Returns the label color used for this category.
+    #[must_use]
+    pub fn color(self) -> &'static str {
+        match self {
+            Self::Fail => FAIL_COLOR,
+            Self::Success => SUCCESS_COLOR,
+            Self::Info => INFO_COLOR,
+            Self::Warning => WARNING_COLOR,
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Self::Fail => "notification-fail",
+            Self::Success => "notification-success",
+            Self::Info => "notification-info",
+            Self::Warning => "notification-warning",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub enum Message {
     Timeout(usize),
     Close(usize),
     CloseAll,
+    Action(usize, String),
+    MouseEnter(usize),
+    MouseLeave(usize),
+    ToggleMore,
+    ToggleDetails(usize),
+}
+
+/// An action button shown in a notification, such as "Undo" or "View details".
+#[derive(Clone, PartialEq, Eq)]
+pub struct Action {
+    /// Identifier passed to `Props::on_action` when the button is clicked
+    pub id: String,
+    /// Button label, looked up as a translation key and shown as-is otherwise
+    pub label: String,
+}
+
+impl Action {
+    #[must_use]
+    pub fn new(id: &str, label: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+        }
+    }
 }
 
 /// A single notification item with message content and context.
@@ -54,23 +122,58 @@ pub struct NotificationItem {
     pub sub_message: String,
     /// HTTP status code if this notification is related to a network request
     pub status_code: Option<u16>,
-    /// Optional timestamp for when the notification occurred
+    /// Time until automatic dismissal; `None` keeps the notification until
+    /// the user closes it
     pub time: Option<Duration>,
     /// Notification category affecting visual styling
     pub category: Category,
+    /// Action buttons displayed below the message
+    pub actions: Vec<Action>,
+    /// Whether the dismissal timer pauses while the pointer is over the item
+    pub pause_on_hover: bool,
+    /// Additional lines, such as individual field errors, shown when the
+    /// notification is expanded
+    pub details: Vec<String>,
+}
+
+impl NotificationItem {
+    /// Returns `true` if both items would render the same message with the
+    /// same actions.
+    #[must_use]
+    pub fn is_same_message(&self, other: &Self) -> bool {
+        self.message == other.message
+            && self.sub_message == other.sub_message
+            && self.category == other.category
+            && self.details == other.details
+            && self.actions == other.actions
+    }
 }
 
 /// Default timeout duration for notifications before auto-dismissal
 pub const TIMEOUT_SECS: Duration = Duration::from_secs(10);
 
+struct Timer {
+    handle: Option<Timeout>,
+    started_at: f64,
+    remaining: u32,
+}
+
 /// Notification component model managing active notifications and their timers.
 pub struct Model {
     /// Active timeouts for automatic notification dismissal
-    timeouts: HashMap<usize, Timeout>,
+    timeouts: HashMap<usize, Timer>,
+    /// The last time each notification, or a duplicate of it, arrived
+    arrivals: HashMap<usize, f64>,
+    /// The number of times each merged notification has been repeated
+    repeats: HashMap<usize, usize>,
+    /// Whether notifications beyond `max_visible` are shown
+    expanded: bool,
+    /// Notifications whose details are shown
+    expanded_details: HashSet<usize>,
 }
 
 /// Properties for the notification component.
-#[derive(Clone, Eq, PartialEq, Properties)]
+#[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     /// Translation context for localized text
     pub txt: Texts,
@@ -85,6 +188,20 @@ pub struct Props {
     pub width: u32,
     #[prop_or(None)]
     pub theme: Option<Theme>,
+    /// Called with the serial and action ID when an action button is clicked
+    #[prop_or(None)]
+    pub on_action: Option<Callback<(usize, String)>>,
+    /// Maximum number of notifications shown before the rest collapse into
+    /// a "+N more" row
+    #[prop_or(None)]
+    pub max_visible: Option<usize>,
+    /// Identical notifications arriving within this window are merged into
+    /// the earlier one with a repeat count
+    #[prop_or(None)]
+    pub dedup_window: Option<Duration>,
+    /// History in which every newly shown notification is recorded
+    #[prop_or(None)]
+    pub history: Option<Rc<RefCell<History>>>,
 }
 
 impl Component for Model {
@@ -94,13 +211,18 @@ impl Component for Model {
     fn create(ctx: &Context<Self>) -> Self {
         let mut s = Self {
             timeouts: HashMap::new(),
+            arrivals: HashMap::new(),
+            repeats: HashMap::new(),
+            expanded: false,
+            expanded_details: HashSet::new(),
         };
         s.add_timer(ctx);
         s
     }
 
-    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
-        self.add_timer(ctx)
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        // A new `serial` without new items means that the parent removed some.
+        self.add_timer(ctx) || ctx.props().serial != old_props.serial
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -111,16 +233,58 @@ impl Component for Model {
             }
             Message::Close(serial) => {
                 self.timeouts.remove(&serial);
+                self.arrivals.remove(&serial);
+                self.repeats.remove(&serial);
+                self.expanded_details.remove(&serial);
                 if let Ok(mut list) = ctx.props().list.try_borrow_mut() {
                     list.retain(|l| l.0 != serial);
                 }
             }
             Message::CloseAll => {
                 self.timeouts.clear();
+                self.arrivals.clear();
+                self.repeats.clear();
+                self.expanded = false;
+                self.expanded_details.clear();
                 if let Ok(mut list) = ctx.props().list.try_borrow_mut() {
                     list.clear();
                 }
             }
+            Message::Action(serial, id) => {
+                if let Some(on_action) = ctx.props().on_action.as_ref() {
+                    on_action.emit((serial, id));
+                }
+                ctx.link().send_message(Message::Close(serial));
+                return false;
+            }
+            Message::MouseEnter(serial) => {
+                if let Some(timer) = self.timeouts.get_mut(&serial)
+                    && let Some(handle) = timer.handle.take()
+                {
+                    handle.cancel();
+                    let elapsed = (js_sys::Date::now() - timer.started_at)
+                        .to_u32()
+                        .unwrap_or(u32::MAX);
+                    timer.remaining = timer.remaining.saturating_sub(elapsed);
+                }
+                return false;
+            }
+            Message::MouseLeave(serial) => {
+                if let Some(remaining) = self
+                    .timeouts
+                    .get(&serial)
+                    .and_then(|timer| timer.handle.is_none().then_some(timer.remaining))
+                {
+                    self.start_timer(ctx, serial, remaining);
+                }
+                return false;
+            }
+            Message::ToggleMore => self.expanded = !self.expanded,
+            Message::ToggleDetails(serial) => {
+                if !self.expanded_details.remove(&serial) {
+                    self.expanded_details.insert(serial);
+                }
+            }
         }
         true
     }
@@ -139,11 +303,18 @@ impl Component for Model {
             )
         };
         let theme = ctx.props().theme;
+        let max_visible = ctx.props().max_visible.unwrap_or(usize::MAX);
+        let visible = visible_count(list.len(), ctx.props().max_visible, self.expanded);
         html! {
             <>
                 <div id="notification" class="notification" style={style}>
                     { if list.len() > 1 { Self::view_close_all_button(ctx) } else { html! {} } }
-                    { for list.iter().rev().map(|l| Self::view_item(ctx, l.0, &l.1, theme)) }
+                    { if list.len() > max_visible { self.view_more_button(ctx, list.len() - visible) } else { html! {} } }
+                    {
+                        for list.iter().rev().take(visible).map(|l| {
+                            self.view_item(ctx, l.0, &l.1, theme)
+                        })
+                    }
                 </div>
             </>
         }
@@ -152,34 +323,62 @@ impl Component for Model {
 
 impl Model {
     fn add_timer(&mut self, ctx: &Context<Self>) -> bool {
-        let Ok(list) = ctx.props().list.try_borrow() else {
-            return false;
-        };
-        let (serial, time) = if let Some((serial, item)) = list.last() {
-            (*serial, item.time)
-        } else {
+        let Ok(mut list) = ctx.props().list.try_borrow_mut() else {
             return false;
         };
-        if self.timeouts.contains_key(&serial) {
-            return false;
+        let now = js_sys::Date::now();
+        let window = ctx
+            .props()
+            .dedup_window
+            .and_then(|w| w.as_millis().to_f64());
+        let arrival = merge_arrivals(&list, &mut self.arrivals, &mut self.repeats, now, window);
+        for (serial, item) in list.iter() {
+            if arrival.shown.contains(serial) {
+                if let Some(mut history) = ctx
+                    .props()
+                    .history
+                    .as_ref()
+                    .and_then(|h| h.try_borrow_mut().ok())
+                {
+                    history.record(item, timestamp_from_millis(now));
+                }
+                if let Some(time) = item.time {
+                    self.start_timer(ctx, *serial, timer_delay(time));
+                }
+            } else if arrival.repeated.contains(serial)
+                && let Some(time) = item.time
+            {
+                self.restart_timer(ctx, *serial, timer_delay(time));
+            }
         }
+        if !arrival.merged.is_empty() {
+            list.retain(|(serial, _)| !arrival.merged.contains(serial));
+        }
+        !arrival.shown.is_empty() || !arrival.merged.is_empty()
+    }
 
-        if let Some(time) = time {
-            let handle = {
-                let link = ctx.link().clone();
-                Timeout::new(
-                    time.as_millis().to_u32().unwrap_or_else(|| {
-                        TIMEOUT_SECS
-                            .as_millis()
-                            .to_u32()
-                            .expect("Default timeout is within u32 range")
-                    }),
-                    move || link.send_message(Message::Timeout(serial)),
-                )
-            };
-            self.timeouts.insert(serial, handle);
+    fn start_timer(&mut self, ctx: &Context<Self>, serial: usize, millis: u32) {
+        let handle = {
+            let link = ctx.link().clone();
+            Timeout::new(millis, move || link.send_message(Message::Timeout(serial)))
+        };
+        self.timeouts.insert(
+            serial,
+            Timer {
+                handle: Some(handle),
+                started_at: js_sys::Date::now(),
+                remaining: millis,
+            },
+        );
+    }
+
+    /// Restarts the countdown of a repeated notification. If the pointer is
+    /// over it, the countdown stays paused with its full time remaining.
+    fn restart_timer(&mut self, ctx: &Context<Self>, serial: usize, millis: u32) {
+        match self.timeouts.get_mut(&serial) {
+            Some(timer) if timer.handle.is_none() => timer.remaining = millis,
+            _ => self.start_timer(ctx, serial, millis),
         }
-        true
     }
 
     fn view_close_all_button(ctx: &Context<Self>) -> Html {
@@ -195,32 +394,106 @@ impl Model {
         }
     }
 
+    fn view_more_button(&self, ctx: &Context<Self>, hidden: usize) -> Html {
+        let onclick_more = ctx.link().callback(|_| Message::ToggleMore);
+        let txt = ctx.props().txt.txt.clone();
+        let label = if self.expanded {
+            translate(&txt, ctx.props().language, "Show less")
+        } else {
+            format!(
+                "+{hidden} {}",
+                translate(&txt, ctx.props().language, "more")
+            )
+        };
+
+        html! {
+            <div class="notification-more">
+                <div class="notification-more-button" onclick={onclick_more}>
+                    { label }
+                </div>
+            </div>
+        }
+    }
+
+    fn view_details(&self, ctx: &Context<Self>, serial: usize, noti: &NotificationItem) -> Html {
+        if noti.details.is_empty() {
+            return html! {};
+        }
+        let txt = ctx.props().txt.txt.clone();
+        let expanded = self.expanded_details.contains(&serial);
+        let onclick = ctx.link().callback(move |_| Message::ToggleDetails(serial));
+
+        html! {
+            <div class="notification-contents-details">
+                <div class="notification-contents-details-toggle" {onclick}>
+                    { format!(
+                        "{} ({}) {}",
+                        translate(&txt, ctx.props().language, "Details"),
+                        noti.details.len(),
+                        if expanded { "▴" } else { "▾" },
+                    ) }
+                </div>
+                if expanded {
+                    <ul class="notification-contents-details-list">
+                    {
+                        for noti.details.iter().map(|detail| html! {
+                            <li>{ translate(&txt, ctx.props().language, detail) }</li>
+                        })
+                    }
+                    </ul>
+                }
+            </div>
+        }
+    }
+
+    fn view_actions(ctx: &Context<Self>, serial: usize, noti: &NotificationItem) -> Html {
+        if noti.actions.is_empty() {
+            return html! {};
+        }
+        let txt = ctx.props().txt.txt.clone();
+
+        html! {
+            <div class="notification-contents-actions">
+            {
+                for noti.actions.iter().map(|action| {
+                    let id = action.id.clone();
+                    let onclick = ctx.link().callback(move |_| Message::Action(serial, id.clone()));
+                    html! {
+                        <div class="notification-contents-action-button" {onclick}>
+                            { translate(&txt, ctx.props().language, &action.label) }
+                        </div>
+                    }
+                })
+            }
+            </div>
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     fn view_item(
+        &self,
         ctx: &Context<Self>,
         serial: usize,
         noti: &NotificationItem,
         theme: Option<Theme>,
     ) -> Html {
-        let color = match noti.category {
-            Category::Fail => FAIL_COLOR,
-            Category::Success => SUCCESS_COLOR,
-        };
         let style_contents = format!("width: {}px;", ctx.props().width - 4);
-        let style_label = format!("background-color: {color};");
+        let style_label = format!("background-color: {};", noti.category.color());
         let txt = ctx.props().txt.txt.clone();
-        let msg = get_text!(txt, ctx.props().language.tag(), &noti.message)
-            .map_or(noti.message.clone(), |text| text.to_string());
+        let msg = translate(&txt, ctx.props().language, &noti.message);
         let msg = if noti.sub_message.is_empty() {
             msg
         } else {
             format!(
                 "{}: {}",
                 msg,
-                get_text!(txt, ctx.props().language.tag(), &noti.sub_message)
-                    .map_or(noti.sub_message.clone(), |text| text.to_string())
+                translate(&txt, ctx.props().language, &noti.sub_message)
             )
         };
+        let repeat = self
+            .repeats
+            .get(&serial)
+            .map_or_else(String::new, |count| format!("×{count}"));
 
         let onclick_close = ctx.link().callback(move |_| Message::Close(serial));
         let onclick_done = ctx.link().callback(move |_| Message::Close(serial));
@@ -231,18 +504,33 @@ impl Model {
         };
         let close_img = Theme::path(&theme, &format!("notification-close.{ext}"));
         let error_img = Theme::path(&theme, "notification-error.svg");
+        let header = if cfg!(feature = "pumpkin") && noti.time.is_none() {
+            match noti.category {
+                Category::Fail => Some(text!(txt, ctx.props().language, "Error")),
+                Category::Warning => Some(translate(&txt, ctx.props().language, "Warning")),
+                Category::Success | Category::Info => None,
+            }
+        } else {
+            None
+        };
+        let onmouseenter = ctx.link().callback(move |_| Message::MouseEnter(serial));
+        let onmouseleave = ctx.link().callback(move |_| Message::MouseLeave(serial));
 
         html! {
-            <table class="notification">
+            <table
+                class={classes!("notification", noti.category.class())}
+                onmouseenter={noti.pause_on_hover.then_some(onmouseenter)}
+                onmouseleave={noti.pause_on_hover.then_some(onmouseleave)}
+            >
                 <tr>
                     <td class="notification-contents" style={style_contents}>
                         if cfg!(feature = "pumpkin") {
                             {
-                                if noti.time.is_none() {
+                                if let Some(header) = header.as_ref() {
                                     html! {
                                         <div class="clumit-notification-error">
                                             <img src={error_img} class="clumit-notification-error"/>
-                                            { text!(txt, ctx.props().language, "Error") }
+                                            { header.clone() }
                                             if cfg!(feature = "pumpkin") {
                                                 <td class="notification-contents-text-close">
                                                     <img src={close_img.clone()}
@@ -264,8 +552,13 @@ impl Model {
                                     <td class="notification-contents-text-text">
                                         { msg }
                                     </td>
+                                    if !repeat.is_empty() {
+                                        <td class="notification-contents-text-repeat">
+                                            { repeat }
+                                        </td>
+                                    }
                                     if cfg!(feature = "pumpkin") {
-                                        if noti.time.is_some() {
+                                        if header.is_none() {
                                             <td class="notification-contents-text-close">
                                                 <img src={ close_img.clone() }
                                                 class="notification-close"
@@ -284,6 +577,8 @@ impl Model {
                                 </tr>
                             </table>
                         </div>
+                        { self.view_details(ctx, serial, noti) }
+                        { Self::view_actions(ctx, serial, noti) }
                         {
                             if noti.time.is_none() {
                                 html! {
@@ -308,6 +603,101 @@ impl Model {
     }
 }
 
+/// The notifications newly found in the list
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Arrival {
+    /// Notifications shown for the first time
+    shown: Vec<usize>,
+    /// Earlier notifications into which identical new ones were merged
+    repeated: Vec<usize>,
+    /// New notifications merged into earlier ones, to be removed from the list
+    merged: Vec<usize>,
+}
+
+/// Records the arrival of the notifications not seen before. A notification
+/// identical to one that arrived within `window` is merged into that one,
+/// whose repeat count goes up.
+fn merge_arrivals(
+    list: &[(usize, NotificationItem)],
+    arrivals: &mut HashMap<usize, f64>,
+    repeats: &mut HashMap<usize, usize>,
+    now: f64,
+    window: Option<f64>,
+) -> Arrival {
+    let mut arrival = Arrival::default();
+    for (index, (serial, item)) in list.iter().enumerate() {
+        if arrivals.contains_key(serial) {
+            continue;
+        }
+        let original = window.and_then(|window| {
+            list[..index]
+                .iter()
+                .filter(|(s, i)| !arrival.merged.contains(s) && i.is_same_message(item))
+                .find(|(s, _)| arrivals.get(s).is_some_and(|t| now - t <= window))
+                .map(|(s, _)| *s)
+        });
+        if let Some(original) = original {
+            arrival.merged.push(*serial);
+            *repeats.entry(original).or_insert(1) += 1;
+            arrivals.insert(original, now);
+            if !arrival.repeated.contains(&original) {
+                arrival.repeated.push(original);
+            }
+        } else {
+            arrivals.insert(*serial, now);
+            arrival.shown.push(*serial);
+        }
+    }
+    arrival
+}
+
+/// Returns how many of `len` notifications are shown. The rest are collapsed
+/// into a "+N more" row unless expanded.
+fn visible_count(len: usize, max_visible: Option<usize>, expanded: bool) -> usize {
+    if expanded {
+        len
+    } else {
+        len.min(max_visible.unwrap_or(usize::MAX))
+    }
+}
+
+fn timestamp_from_millis(millis: f64) -> Timestamp {
+    millis
+        .to_i64()
+        .and_then(|millis| Timestamp::from_millisecond(millis).ok())
+        .unwrap_or(Timestamp::UNIX_EPOCH)
+}
+
+/// The id of the action of `gen_undo_notification`
+pub(crate) const UNDO_ACTION: &str = "undo";
+
+/// The longest delay of a timer in milliseconds. Browsers fire a timer with a
+/// longer delay at once.
+const MAX_DELAY_MILLIS: u32 = i32::MAX.unsigned_abs();
+
+/// Returns the delay in milliseconds of a timer for `time`, at most
+/// `MAX_DELAY_MILLIS`.
+pub(crate) fn timer_delay(time: Duration) -> u32 {
+    time.as_millis()
+        .to_u32()
+        .map_or(MAX_DELAY_MILLIS, |millis| millis.min(MAX_DELAY_MILLIS))
+}
+
+/// Returns the notification of a deletion with the "Undo" action, shown as
+/// long as users can undo the deletion.
+pub(crate) fn gen_undo_notification(message: String, time: Duration) -> NotificationItem {
+    NotificationItem {
+        message,
+        sub_message: String::new(),
+        status_code: None,
+        time: Some(Duration::from_millis(timer_delay(time).into())),
+        category: Category::Info,
+        actions: vec![Action::new(UNDO_ACTION, "Undo")],
+        pause_on_hover: false,
+        details: Vec::new(),
+    }
+}
+
 /// Common error types that can be converted to notifications.
 #[derive(Clone, PartialEq, Eq)]
 pub enum CommonError {
@@ -331,6 +721,8 @@ pub enum NotificationType {
     CommonError(CommonError),
     /// A list of errors with a primary message
     ErrorList(String, Vec<String>),
+    /// A structured response error mapped by `DefaultErrorMapper`
+    Response(ResponseError),
 }
 
 /// Generates a notification item from a notification type.
@@ -366,6 +758,9 @@ pub fn gen_notifications(noti: NotificationType) -> NotificationItem {
                 status_code: None,
                 time: None,
                 category: Category::Fail,
+                actions: Vec::new(),
+                pause_on_hover: false,
+                details: Vec::new(),
             },
             CommonError::SendGraphQLQueryError => NotificationItem {
                 message: "Invalid GraphQL query".to_string(),
@@ -373,6 +768,9 @@ pub fn gen_notifications(noti: NotificationType) -> NotificationItem {
                 status_code: None,
                 time: None,
                 category: Category::Fail,
+                actions: Vec::new(),
+                pause_on_hover: false,
+                details: Vec::new(),
             },
             CommonError::HttpStatusNoSuccess(status) => NotificationItem {
                 message: "No success HTTPS status code".to_string(),
@@ -380,6 +778,9 @@ pub fn gen_notifications(noti: NotificationType) -> NotificationItem {
                 status_code: Some(status),
                 time: None,
                 category: Category::Fail,
+                actions: Vec::new(),
+                pause_on_hover: false,
+                details: Vec::new(),
             },
             CommonError::UnknownError => NotificationItem {
                 message: "Unknown error".to_string(),
@@ -387,6 +788,9 @@ pub fn gen_notifications(noti: NotificationType) -> NotificationItem {
                 status_code: None,
                 time: None,
                 category: Category::Fail,
+                actions: Vec::new(),
+                pause_on_hover: false,
+                details: Vec::new(),
             },
             CommonError::GraphQLParseError => NotificationItem {
                 message: "GraphQL parse error".to_string(),
@@ -394,6 +798,9 @@ pub fn gen_notifications(noti: NotificationType) -> NotificationItem {
                 status_code: None,
                 time: None,
                 category: Category::Fail,
+                actions: Vec::new(),
+                pause_on_hover: false,
+                details: Vec::new(),
             },
         },
         NotificationType::ErrorList(message, errors) => {
@@ -418,7 +825,93 @@ pub fn gen_notifications(noti: NotificationType) -> NotificationItem {
                 status_code: None,
                 time: None,
                 category: Category::Fail,
+                actions: Vec::new(),
+                pause_on_hover: false,
+                details: Vec::new(),
             }
         }
+        NotificationType::Response(error) => DefaultErrorMapper.map(&error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::{
+        Action, Category, MAX_DELAY_MILLIS, NotificationItem, merge_arrivals, timer_delay,
+        visible_count,
+    };
+
+    fn item(message: &str) -> NotificationItem {
+        NotificationItem {
+            message: message.to_string(),
+            sub_message: String::new(),
+            status_code: None,
+            time: None,
+            category: Category::Info,
+            actions: Vec::new(),
+            pause_on_hover: false,
+            details: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dedup() {
+        let mut arrivals = HashMap::new();
+        let mut repeats = HashMap::new();
+        let list = vec![(1, item("a")), (2, item("b")), (3, item("a"))];
+        let arrival = merge_arrivals(&list, &mut arrivals, &mut repeats, 0.0, Some(1000.0));
+        assert_eq!(arrival.shown, vec![1, 2]);
+        assert_eq!(arrival.repeated, vec![1]);
+        assert_eq!(arrival.merged, vec![3]);
+        assert_eq!(repeats.get(&1), Some(&2));
+
+        // Each repeat within the window counts, measured from the last one.
+        let list = vec![(1, item("a")), (2, item("b")), (4, item("a"))];
+        let arrival = merge_arrivals(&list, &mut arrivals, &mut repeats, 900.0, Some(1000.0));
+        assert_eq!(arrival.merged, vec![4]);
+        assert_eq!(repeats.get(&1), Some(&3));
+        let list = vec![(1, item("a")), (2, item("b")), (5, item("a"))];
+        let arrival = merge_arrivals(&list, &mut arrivals, &mut repeats, 1800.0, Some(1000.0));
+        assert_eq!(arrival.merged, vec![5]);
+        assert_eq!(repeats.get(&1), Some(&4));
+
+        // Outside the window, or without one, a repeat is shown separately.
+        let list = vec![(1, item("a")), (2, item("b")), (6, item("a"))];
+        let arrival = merge_arrivals(&list, &mut arrivals, &mut repeats, 5000.0, Some(1000.0));
+        assert_eq!(arrival.shown, vec![6]);
+        assert!(arrival.merged.is_empty());
+        let list = vec![(1, item("a")), (7, item("a"))];
+        let arrival = merge_arrivals(&list, &mut arrivals, &mut repeats, 5000.0, None);
+        assert_eq!(arrival.shown, vec![7]);
+        assert_eq!(repeats.get(&1), Some(&4));
+
+        // Items with different actions, such as two undo handles, are kept.
+        let undo = |id: &str| NotificationItem {
+            actions: vec![Action::new(id, "Undo")],
+            ..item("c")
+        };
+        let list = vec![(8, undo("1")), (9, undo("2"))];
+        let arrival = merge_arrivals(&list, &mut arrivals, &mut repeats, 6000.0, Some(1000.0));
+        assert_eq!(arrival.shown, vec![8, 9]);
+        assert!(arrival.merged.is_empty());
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(visible_count(5, Some(3), false), 3);
+        assert_eq!(visible_count(5, Some(3), true), 5);
+        assert_eq!(visible_count(2, Some(3), false), 2);
+        assert_eq!(visible_count(5, None, false), 5);
+    }
+
+    #[test]
+    fn delay() {
+        assert_eq!(timer_delay(Duration::from_secs(5)), 5000);
+        // browsers fire longer delays at once
+        assert_eq!(timer_delay(Duration::from_hours(30 * 24)), MAX_DELAY_MILLIS);
+        assert_eq!(timer_delay(Duration::MAX), MAX_DELAY_MILLIS);
     }
 }
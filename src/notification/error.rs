@@ -0,0 +1,345 @@
+//! Structured errors from GraphQL and HTTP responses and their mapping to
+//! notifications.
+
+use std::fmt::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Category, NotificationItem};
+use crate::Texts;
+use crate::language::{Language, translate};
+
+/// Class of an HTTP status code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpStatusClass {
+    /// 1xx
+    Informational,
+    /// 2xx
+    Success,
+    /// 3xx
+    Redirection,
+    /// 4xx
+    ClientError,
+    /// 5xx
+    ServerError,
+    /// Any code outside 100-599
+    Unknown,
+}
+
+impl From<u16> for HttpStatusClass {
+    fn from(status: u16) -> Self {
+        match status {
+            100..=199 => Self::Informational,
+            200..=299 => Self::Success,
+            300..=399 => Self::Redirection,
+            400..=499 => Self::ClientError,
+            500..=599 => Self::ServerError,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A segment of the `path` of a GraphQL error.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    /// A field name
+    Key(String),
+    /// An index into a list
+    Index(u64),
+}
+
+/// The `extensions` of a GraphQL error.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GraphQLErrorExtensions {
+    /// Machine-readable error code such as `FORBIDDEN`
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+/// An error in the `errors` array of a GraphQL response.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GraphQLError {
+    pub message: String,
+    /// Path of the field that caused the error, if any
+    #[serde(default)]
+    pub path: Option<Vec<PathSegment>>,
+    #[serde(default)]
+    pub extensions: Option<GraphQLErrorExtensions>,
+}
+
+impl GraphQLError {
+    /// Returns `extensions.code`, if present.
+    #[must_use]
+    pub fn code(&self) -> Option<&str> {
+        self.extensions.as_ref().and_then(|e| e.code.as_deref())
+    }
+
+    /// Returns the path in dotted notation, e.g. `customer.networks[0].name`.
+    #[must_use]
+    pub fn path_string(&self) -> Option<String> {
+        let path = self.path.as_ref().filter(|p| !p.is_empty())?;
+        let mut joined = String::new();
+        for segment in path {
+            match segment {
+                PathSegment::Key(key) => {
+                    if !joined.is_empty() {
+                        joined.push('.');
+                    }
+                    joined.push_str(key);
+                }
+                PathSegment::Index(index) => {
+                    write!(joined, "[{index}]").expect("in-memory operation");
+                }
+            }
+        }
+        Some(joined)
+    }
+
+    fn is_forbidden(&self) -> bool {
+        matches!(self.code(), Some("FORBIDDEN" | "UNAUTHENTICATED"))
+            || self.message.ends_with("Forbidden")
+    }
+}
+
+impl fmt::Display for GraphQLError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(path) = self.path_string() {
+            write!(f, "{path}: {}", self.message)
+        } else {
+            write!(f, "{}", self.message)
+        }
+    }
+}
+
+/// An error that occurred while sending a request or handling its response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResponseError {
+    /// The browser is offline
+    Offline,
+    /// The request did not complete in time
+    Timeout,
+    /// The request could not be sent
+    Send,
+    /// The server returned a non-success HTTP status code
+    HttpStatus(u16),
+    /// The response body could not be parsed
+    Parse,
+    /// The response contained GraphQL errors
+    GraphQL(Vec<GraphQLError>),
+    /// Unspecified error
+    Unknown,
+}
+
+/// Converts a [`ResponseError`] into a notification.
+///
+/// Messages, sub-messages and details are translation keys or raw text,
+/// localized by `Notification` when shown. Implement this trait to
+/// customize the wording or category of particular errors, falling back to
+/// [`DefaultErrorMapper`] for the rest.
+pub trait ErrorMapper {
+    fn map(&self, error: &ResponseError) -> NotificationItem;
+}
+
+/// The mapping used when no custom [`ErrorMapper`] is given.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultErrorMapper;
+
+impl ErrorMapper for DefaultErrorMapper {
+    fn map(&self, error: &ResponseError) -> NotificationItem {
+        let (message, sub_message, status_code, category) = match error {
+            ResponseError::Offline => {
+                ("Network is offline", String::new(), None, Category::Warning)
+            }
+            ResponseError::Timeout => ("Request timed out", String::new(), None, Category::Warning),
+            ResponseError::Send => ("Invalid GraphQL query", String::new(), None, Category::Fail),
+            ResponseError::HttpStatus(status) => {
+                let message = match (*status, HttpStatusClass::from(*status)) {
+                    (401 | 403, _) => "Unauthorized",
+                    (404, _) => "Not found",
+                    (408 | 504, _) => "Request timed out",
+                    (_, HttpStatusClass::ClientError) => "Invalid request",
+                    (_, HttpStatusClass::ServerError) => "Server error",
+                    _ => "No success HTTPS status code",
+                };
+                (message, status.to_string(), Some(*status), Category::Fail)
+            }
+            ResponseError::Parse => ("GraphQL parse error", String::new(), None, Category::Fail),
+            ResponseError::GraphQL(errors) => return map_graphql_errors(errors),
+            ResponseError::Unknown => ("Unknown error", String::new(), None, Category::Fail),
+        };
+        NotificationItem {
+            message: message.to_string(),
+            sub_message,
+            status_code,
+            time: None,
+            category,
+            actions: Vec::new(),
+            pause_on_hover: false,
+            details: Vec::new(),
+        }
+    }
+}
+
+/// Maps GraphQL errors to one notification. Errors on fields are grouped
+/// into the details of the notification.
+fn map_graphql_errors(errors: &[GraphQLError]) -> NotificationItem {
+    let (message, sub_message, details) = match errors {
+        [] => (
+            "Invalid GraphQL response".to_string(),
+            String::new(),
+            Vec::new(),
+        ),
+        [error] if error.is_forbidden() => (
+            "Unauthorized".to_string(),
+            error.path_string().unwrap_or_default(),
+            Vec::new(),
+        ),
+        [error] => (error.to_string(), String::new(), Vec::new()),
+        errors if errors.iter().any(GraphQLError::is_forbidden) => (
+            "Unauthorized".to_string(),
+            String::new(),
+            errors.iter().map(ToString::to_string).collect(),
+        ),
+        errors => (
+            "Request failed".to_string(),
+            String::new(),
+            errors.iter().map(ToString::to_string).collect(),
+        ),
+    };
+    NotificationItem {
+        message,
+        sub_message,
+        status_code: None,
+        time: None,
+        category: Category::Fail,
+        actions: Vec::new(),
+        pause_on_hover: false,
+        details,
+    }
+}
+
+/// Generates a notification for `error` using `mapper`. If the notification
+/// has several details and no sub-message, the number of details is added as
+/// a localized sub-message such as "3 errors".
+///
+/// # Examples
+///
+/// ```rust
+/// use frontary::{DefaultErrorMapper, ErrorMapper, ResponseError};
+///
+/// let item = DefaultErrorMapper.map(&ResponseError::HttpStatus(503));
+/// assert_eq!(item.message, "Server error");
+/// assert_eq!(item.status_code, Some(503));
+/// ```
+#[must_use]
+pub fn gen_error_notification(
+    error: &ResponseError,
+    mapper: &dyn ErrorMapper,
+    txt: &Texts,
+    language: Language,
+) -> NotificationItem {
+    let mut item = mapper.map(error);
+    if item.details.len() > 1 && item.sub_message.is_empty() {
+        item.sub_message = format!(
+            "{} {}",
+            item.details.len(),
+            translate(&txt.txt, language, "errors")
+        );
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use json_gettext::JSONGetText;
+
+    use super::{
+        DefaultErrorMapper, ErrorMapper, GraphQLError, GraphQLErrorExtensions, PathSegment,
+        ResponseError, gen_error_notification,
+    };
+    use crate::Texts;
+    use crate::language::Language;
+    use crate::notification::Category;
+
+    fn graphql_error(message: &str, path: &[&str], code: Option<&str>) -> GraphQLError {
+        GraphQLError {
+            message: message.to_string(),
+            path: Some(
+                path.iter()
+                    .map(|p| {
+                        p.parse::<u64>()
+                            .map_or_else(|_| PathSegment::Key((*p).to_string()), PathSegment::Index)
+                    })
+                    .collect(),
+            ),
+            extensions: code.map(|code| GraphQLErrorExtensions {
+                code: Some(code.to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn http_status() {
+        let item = DefaultErrorMapper.map(&ResponseError::HttpStatus(403));
+        assert_eq!(item.message, "Unauthorized");
+        assert_eq!(item.sub_message, "403");
+        let item = DefaultErrorMapper.map(&ResponseError::HttpStatus(302));
+        assert_eq!(item.message, "No success HTTPS status code");
+        let item = DefaultErrorMapper.map(&ResponseError::Offline);
+        assert!(item.category == Category::Warning);
+    }
+
+    #[test]
+    fn graphql_field_errors_are_grouped() {
+        let errors = vec![
+            graphql_error("invalid name", &["customer", "networks", "0", "name"], None),
+            graphql_error("too long", &["customer", "description"], None),
+        ];
+        let error = ResponseError::GraphQL(errors);
+        let item = DefaultErrorMapper.map(&error);
+        assert_eq!(item.message, "Request failed");
+        assert!(item.sub_message.is_empty());
+        assert_eq!(
+            item.details,
+            vec![
+                "customer.networks[0].name: invalid name".to_string(),
+                "customer.description: too long".to_string(),
+            ]
+        );
+        let mut builder = JSONGetText::build("en-US");
+        builder
+            .add_json("en-US", r#"{"errors": "errors"}"#)
+            .expect("valid JSON");
+        let txt = Texts {
+            txt: Rc::new(builder.build().expect("valid texts")),
+        };
+        let item = gen_error_notification(&error, &DefaultErrorMapper, &txt, Language::English);
+        assert_eq!(item.sub_message, "2 errors");
+    }
+
+    #[test]
+    fn graphql_single_error_keeps_path() {
+        let errors = vec![graphql_error(
+            "too long",
+            &["customer", "description"],
+            None,
+        )];
+        let item = DefaultErrorMapper.map(&ResponseError::GraphQL(errors));
+        assert_eq!(item.message, "customer.description: too long");
+        let errors = vec![graphql_error("denied", &["customer"], Some("FORBIDDEN"))];
+        let item = DefaultErrorMapper.map(&ResponseError::GraphQL(errors));
+        assert_eq!(item.message, "Unauthorized");
+        assert_eq!(item.sub_message, "customer");
+    }
+
+    #[test]
+    fn graphql_forbidden() {
+        let errors = vec![graphql_error("denied", &[], Some("FORBIDDEN"))];
+        let item = DefaultErrorMapper.map(&ResponseError::GraphQL(errors));
+        assert_eq!(item.message, "Unauthorized");
+        assert!(item.details.is_empty());
+    }
+}
@@ -0,0 +1,432 @@
+//! Notification history store and review panel.
+//!
+//! The store keeps recently shown notifications with their timestamps and
+//! read state. A persistent store is saved to the browser's local storage so
+//! the history survives reloads.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo_storage::{LocalStorage, Storage};
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use yew::{Component, Context, Html, Properties, classes, html};
+
+use super::{Category, NotificationItem};
+use crate::Texts;
+use crate::language::{Language, translate};
+
+/// Local storage key for persisting the notification history
+const STORAGE_KEY: &str = "aice.notification.history";
+
+/// Default maximum number of entries kept in the history
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+/// A notification recorded in the history.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    /// Identifier unique within the history
+    pub id: u64,
+    /// Primary notification message
+    pub message: String,
+    /// Secondary message with additional details
+    pub sub_message: String,
+    /// HTTP status code if the notification was related to a network request
+    pub status_code: Option<u16>,
+    /// Notification category
+    pub category: Category,
+    /// When the notification was shown
+    pub time: Timestamp,
+    /// Whether the user has reviewed the entry
+    pub read: bool,
+}
+
+/// Criteria for selecting history entries.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// Only entries of this category, or all categories if `None`
+    pub category: Option<Category>,
+    /// Only entries that have not been read
+    pub unread_only: bool,
+}
+
+impl HistoryFilter {
+    fn accepts(self, entry: &HistoryEntry) -> bool {
+        self.category.is_none_or(|c| c == entry.category) && !(self.unread_only && entry.read)
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct Stored {
+    next_id: u64,
+    entries: Vec<HistoryEntry>,
+}
+
+/// A bounded record of shown notifications, oldest first.
+#[derive(Clone, PartialEq, Eq)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    next_id: u64,
+    capacity: usize,
+    persistent: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl History {
+    /// Creates an in-memory history keeping at most `capacity` entries.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            next_id: 0,
+            capacity,
+            persistent: false,
+        }
+    }
+
+    /// Loads the history from browser storage. Every later change is saved
+    /// back to the storage.
+    #[must_use]
+    pub fn load_from_browser(capacity: usize) -> Self {
+        let stored: Stored = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
+        let mut history = Self {
+            entries: stored.entries,
+            next_id: stored.next_id,
+            capacity,
+            persistent: true,
+        };
+        history.truncate();
+        history
+    }
+
+    fn save(&self) {
+        if self.persistent {
+            let stored = Stored {
+                next_id: self.next_id,
+                entries: self.entries.clone(),
+            };
+            let _ = LocalStorage::set(STORAGE_KEY, stored);
+        }
+    }
+
+    fn truncate(&mut self) {
+        if self.entries.len() > self.capacity {
+            let excess = self.entries.len() - self.capacity;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Records a notification shown at `time` as an unread entry.
+    pub fn record(&mut self, item: &NotificationItem, time: Timestamp) {
+        self.entries.push(HistoryEntry {
+            id: self.next_id,
+            message: item.message.clone(),
+            sub_message: item.sub_message.clone(),
+            status_code: item.status_code,
+            category: item.category,
+            time,
+            read: false,
+        });
+        self.next_id = self.next_id.wrapping_add(1);
+        self.truncate();
+        self.save();
+    }
+
+    /// Returns all entries, oldest first.
+    #[must_use]
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Returns the entries accepted by `filter`, newest first.
+    #[must_use]
+    pub fn filtered(&self, filter: HistoryFilter) -> Vec<&HistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|e| filter.accepts(e))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn unread_count(&self) -> usize {
+        self.entries.iter().filter(|e| !e.read).count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Marks the entry with `id` as read.
+    pub fn mark_read(&mut self, id: u64) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.id == id)
+            && !entry.read
+        {
+            entry.read = true;
+            self.save();
+        }
+    }
+
+    /// Marks every entry accepted by `filter` as read.
+    pub fn mark_all_read(&mut self, filter: HistoryFilter) {
+        for entry in &mut self.entries {
+            if filter.accepts(entry) {
+                entry.read = true;
+            }
+        }
+        self.save();
+    }
+
+    /// Removes every entry accepted by `filter`.
+    pub fn clear(&mut self, filter: HistoryFilter) {
+        self.entries.retain(|e| !filter.accepts(e));
+        self.save();
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Message {
+    SelectCategory(Option<Category>),
+    ToggleUnreadOnly,
+    Read(u64),
+    MarkAllRead,
+    Clear,
+}
+
+/// Panel for reviewing, filtering and clearing past notifications.
+pub struct Model {
+    filter: HistoryFilter,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub txt: Texts,
+    pub language: Language,
+    /// The history shared with `Notification`
+    pub history: Rc<RefCell<History>>,
+    /// Serial number for tracking updates
+    pub serial: usize,
+}
+
+const CATEGORY_FILTERS: [(Option<Category>, &str); 5] = [
+    (None, "All"),
+    (Some(Category::Fail), "Error"),
+    (Some(Category::Warning), "Warning"),
+    (Some(Category::Info), "Information"),
+    (Some(Category::Success), "Success"),
+];
+
+impl Component for Model {
+    type Message = Message;
+    type Properties = Props;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            filter: HistoryFilter::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::SelectCategory(category) => self.filter.category = category,
+            Message::ToggleUnreadOnly => self.filter.unread_only = !self.filter.unread_only,
+            Message::Read(id) => {
+                if let Ok(mut history) = ctx.props().history.try_borrow_mut() {
+                    history.mark_read(id);
+                }
+            }
+            Message::MarkAllRead => {
+                if let Ok(mut history) = ctx.props().history.try_borrow_mut() {
+                    history.mark_all_read(self.filter);
+                }
+            }
+            Message::Clear => {
+                if let Ok(mut history) = ctx.props().history.try_borrow_mut() {
+                    history.clear(self.filter);
+                }
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let Ok(history) = ctx.props().history.try_borrow() else {
+            return html! {};
+        };
+        let txt = ctx.props().txt.txt.clone();
+        let language = ctx.props().language;
+        let onclick_unread = ctx.link().callback(|_| Message::ToggleUnreadOnly);
+        let onclick_mark_all = ctx.link().callback(|_| Message::MarkAllRead);
+        let onclick_clear = ctx.link().callback(|_| Message::Clear);
+        let entries = history.filtered(self.filter);
+
+        html! {
+            <div class="notification-history">
+                <div class="notification-history-header">
+                    <div class="notification-history-filters">
+                    {
+                        for CATEGORY_FILTERS.iter().map(|&(category, label)| {
+                            let onclick = ctx.link().callback(move |_| Message::SelectCategory(category));
+                            let class = classes!(
+                                "notification-history-filter",
+                                (self.filter.category == category).then_some("selected")
+                            );
+                            html! {
+                                <div {class} {onclick}>
+                                    { translate(&txt, language, label) }
+                                </div>
+                            }
+                        })
+                    }
+                        <div
+                            class={classes!(
+                                "notification-history-filter",
+                                self.filter.unread_only.then_some("selected")
+                            )}
+                            onclick={onclick_unread}
+                        >
+                            { format!("{} ({})", translate(&txt, language, "Unread"), history.unread_count()) }
+                        </div>
+                    </div>
+                    <div class="notification-history-commands">
+                        <div class="notification-history-command" onclick={onclick_mark_all}>
+                            { translate(&txt, language, "Mark all as read") }
+                        </div>
+                        <div class="notification-history-command" onclick={onclick_clear}>
+                            { translate(&txt, language, "Clear") }
+                        </div>
+                    </div>
+                </div>
+                <div class="notification-history-list">
+                    if entries.is_empty() {
+                        <div class="notification-history-empty">
+                            { translate(&txt, language, "No notifications") }
+                        </div>
+                    } else {
+                        { for entries.into_iter().map(|entry| Self::view_entry(ctx, entry)) }
+                    }
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Model {
+    fn view_entry(ctx: &Context<Self>, entry: &HistoryEntry) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let language = ctx.props().language;
+        let id = entry.id;
+        let onclick = ctx.link().callback(move |_| Message::Read(id));
+        let msg = translate(&txt, language, &entry.message);
+        let msg = if entry.sub_message.is_empty() {
+            msg
+        } else {
+            format!("{msg}: {}", translate(&txt, language, &entry.sub_message))
+        };
+        let style_label = format!("background-color: {};", entry.category.color());
+        let class = classes!(
+            "notification-history-item",
+            entry.category.class(),
+            (!entry.read).then_some("unread")
+        );
+
+        html! {
+            <div key={id.to_string()} {class} {onclick}>
+                <div class="notification-history-item-label" style={style_label}></div>
+                <div class="notification-history-item-contents">
+                    <div class="notification-history-item-message">{ msg }</div>
+                    <div class="notification-history-item-time">
+                        { local_time(entry.time, language) }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)] // milliseconds since epoch fit in f64
+fn local_time(time: Timestamp, language: Language) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(time.as_millisecond() as f64));
+    date.to_locale_string(language.tag(), &JsValue::UNDEFINED)
+        .as_string()
+        .unwrap_or_else(|| time.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use jiff::Timestamp;
+
+    use super::{History, HistoryFilter};
+    use crate::notification::{Category, NotificationItem};
+
+    fn item(message: &str, category: Category) -> NotificationItem {
+        NotificationItem {
+            message: message.to_string(),
+            sub_message: String::new(),
+            status_code: None,
+            time: Some(Duration::from_secs(1)),
+            category,
+            actions: Vec::new(),
+            pause_on_hover: false,
+            details: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn record_keeps_capacity() {
+        let mut history = History::new(2);
+        for message in ["a", "b", "c"] {
+            history.record(&item(message, Category::Info), Timestamp::UNIX_EPOCH);
+        }
+        let messages = history
+            .entries()
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["b", "c"]);
+        assert_eq!(history.entries()[1].id, 2);
+    }
+
+    #[test]
+    fn filter_and_read_state() {
+        let mut history = History::default();
+        history.record(&item("a", Category::Fail), Timestamp::UNIX_EPOCH);
+        history.record(&item("b", Category::Success), Timestamp::UNIX_EPOCH);
+        history.record(&item("c", Category::Fail), Timestamp::UNIX_EPOCH);
+        assert_eq!(history.unread_count(), 3);
+
+        let fail = HistoryFilter {
+            category: Some(Category::Fail),
+            unread_only: false,
+        };
+        let messages = history
+            .filtered(fail)
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["c", "a"]);
+
+        history.mark_read(0);
+        let unread = HistoryFilter {
+            category: None,
+            unread_only: true,
+        };
+        assert_eq!(history.filtered(unread).len(), 2);
+
+        history.mark_all_read(fail);
+        assert_eq!(history.unread_count(), 1);
+
+        history.clear(fail);
+        assert_eq!(history.entries().len(), 1);
+        assert_eq!(history.entries()[0].message, "b");
+    }
+}
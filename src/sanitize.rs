@@ -0,0 +1,393 @@
+//! Allow-list based HTML sanitizer.
+//!
+//! Used to render server-provided HTML, such as `ModalTextStyle::RawHtml`
+//! messages, without allowing script injection. Tags and attributes outside
+//! the allow-list are removed, text is escaped, and the output is always well
+//! formed so that it cannot affect markup around it.
+
+/// Tags kept in the output, with the attributes allowed on each.
+const ALLOWED_TAGS: [(&str, &[&str]); 15] = [
+    ("a", &["href", "title"]),
+    ("b", &[]),
+    ("br", &[]),
+    ("code", &[]),
+    ("div", &[]),
+    ("em", &[]),
+    ("i", &[]),
+    ("li", &[]),
+    ("ol", &[]),
+    ("p", &[]),
+    ("pre", &[]),
+    ("span", &[]),
+    ("strong", &[]),
+    ("u", &[]),
+    ("ul", &[]),
+];
+
+/// Tags that never have content or a closing tag.
+const VOID_TAGS: [&str; 1] = ["br"];
+
+/// Tags that are removed together with everything inside them.
+const DROPPED_WITH_CONTENT: [&str; 11] = [
+    "iframe", "noembed", "noframes", "noscript", "object", "script", "style", "svg", "template",
+    "textarea", "title",
+];
+
+/// URL schemes allowed in `href`.
+const ALLOWED_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Sanitizes `input` so that it only contains allow-listed markup.
+///
+/// Allowed tags are `a`, `b`, `br`, `code`, `div`, `em`, `i`, `li`, `ol`, `p`,
+/// `pre`, `span`, `strong`, `u` and `ul`. The only attributes kept are `href`
+/// and `title` on `a`, and `href` must be relative or use the `http`,
+/// `https` or `mailto` scheme. Other tags are dropped while their text is
+/// kept, except for tags such as `script` and `style` whose content is
+/// dropped as well.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontary::sanitize_html;
+///
+/// assert_eq!(
+///     sanitize_html(r#"<b onclick="x()">Hi</b><script>alert(1)</script>"#),
+///     "<b>Hi</b>"
+/// );
+/// ```
+#[must_use]
+pub fn sanitize_html(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut open: Vec<&'static str> = Vec::new();
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('<') {
+        escape_text(&rest[..pos], &mut output);
+        rest = &rest[pos..];
+        let Some(tag) = parse_tag(rest) else {
+            output.push_str("&lt;");
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.len..];
+        let Some(name) = tag.name.as_deref() else {
+            continue; // a comment, doctype or processing instruction
+        };
+        if !tag.closing && DROPPED_WITH_CONTENT.contains(&name) {
+            rest = skip_until_closing(rest, name);
+            continue;
+        }
+        let Some(&(allowed, attrs)) = ALLOWED_TAGS.iter().find(|(t, _)| *t == name) else {
+            continue;
+        };
+        if tag.closing {
+            if let Some(index) = open.iter().rposition(|t| *t == allowed) {
+                for t in open.drain(index..).rev() {
+                    output.push_str("</");
+                    output.push_str(t);
+                    output.push('>');
+                }
+            }
+            continue;
+        }
+        output.push('<');
+        output.push_str(allowed);
+        for (attr, value) in &tag.attrs {
+            if !attrs.contains(&attr.as_str()) || (attr == "href" && !is_safe_url(value)) {
+                continue;
+            }
+            output.push(' ');
+            output.push_str(attr);
+            output.push_str("=\"");
+            escape_attr(value, &mut output);
+            output.push('"');
+        }
+        if allowed == "a" {
+            output.push_str(" rel=\"noopener noreferrer\"");
+        }
+        output.push('>');
+        if !VOID_TAGS.contains(&allowed) {
+            open.push(allowed);
+        }
+    }
+    escape_text(rest, &mut output);
+    for t in open.into_iter().rev() {
+        output.push_str("</");
+        output.push_str(t);
+        output.push('>');
+    }
+    output
+}
+
+struct Tag {
+    /// Lowercase tag name, or `None` for comments and declarations
+    name: Option<String>,
+    closing: bool,
+    attrs: Vec<(String, String)>,
+    /// Number of bytes of the input the tag spans
+    len: usize,
+}
+
+/// Parses the tag at the start of `input`, which begins with `<`.
+fn parse_tag(input: &str) -> Option<Tag> {
+    let body = &input[1..];
+    if let Some(comment) = body.strip_prefix("!--") {
+        let len = comment.find("-->").map_or(input.len(), |end| 4 + end + 3);
+        return Some(Tag {
+            name: None,
+            closing: false,
+            attrs: Vec::new(),
+            len,
+        });
+    }
+    if body.starts_with('!') || body.starts_with('?') {
+        let len = body.find('>').map_or(input.len(), |end| 1 + end + 1);
+        return Some(Tag {
+            name: None,
+            closing: false,
+            attrs: Vec::new(),
+            len,
+        });
+    }
+
+    let (closing, body) = body
+        .strip_prefix('/')
+        .map_or((false, body), |body| (true, body));
+    let name_len = body
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(body.len());
+    if name_len == 0 || !body.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = body[..name_len].to_ascii_lowercase();
+    let mut pos = input.len() - body.len() + name_len;
+    let mut attrs = Vec::new();
+
+    loop {
+        let rest = &input[pos..];
+        let trimmed = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        pos += rest.len() - trimmed.len();
+        let Some(c) = trimmed.chars().next() else {
+            return None; // unterminated tag
+        };
+        if c == '>' {
+            pos += 1;
+            break;
+        }
+        let attr_len = trimmed
+            .find(|c: char| c.is_ascii_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(trimmed.len())
+            .max(c.len_utf8());
+        let attr = trimmed[..attr_len].to_ascii_lowercase();
+        pos += attr_len;
+
+        let rest = &input[pos..];
+        let trimmed = rest.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        let Some(value) = trimmed.strip_prefix('=') else {
+            attrs.push((attr, String::new()));
+            continue;
+        };
+        let value_start = value.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        pos += rest.len() - value_start.len();
+        let (value, len) = if let Some(quote @ ('"' | '\'')) = value_start.chars().next() {
+            let inner = &value_start[1..];
+            let end = inner.find(quote)?;
+            (&inner[..end], end + 2)
+        } else {
+            let end = value_start
+                .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                .unwrap_or(value_start.len());
+            (&value_start[..end], end)
+        };
+        attrs.push((attr, decode_entities(value)));
+        pos += len;
+    }
+
+    Some(Tag {
+        name: Some(name),
+        closing,
+        attrs,
+        len: pos,
+    })
+}
+
+/// Returns the input after the closing tag of `name`, or an empty string if
+/// there is none.
+fn skip_until_closing<'a>(input: &'a str, name: &str) -> &'a str {
+    let lower = input.to_ascii_lowercase();
+    let closing = format!("</{name}");
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(&closing) {
+        let start = from + found;
+        let after = &lower[start + closing.len()..];
+        if after.starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/') {
+            return input[start + closing.len()..]
+                .find('>')
+                .map_or("", |end| &input[start + closing.len() + end + 1..]);
+        }
+        from = start + closing.len();
+    }
+    ""
+}
+
+/// Decodes numeric character references and the basic named entities, so
+/// that encoded URL schemes such as `javascript&#58;` are recognized.
+fn decode_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(pos) = rest.find('&') {
+        decoded.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let end = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+            .map_or(rest.len(), |end| end + 1);
+        let entity = &rest[1..end];
+        let c = if let Some(num) = entity.strip_prefix('#') {
+            if let Some(hex) = num.strip_prefix(['x', 'X']) {
+                u32::from_str_radix(hex, 16).ok()
+            } else {
+                num.parse::<u32>().ok()
+            }
+            .and_then(char::from_u32)
+        } else {
+            match entity.to_ascii_lowercase().as_str() {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "colon" => Some(':'),
+                "tab" => Some('\t'),
+                "newline" => Some('\n'),
+                _ => None,
+            }
+        };
+        if let Some(c) = c {
+            decoded.push(c);
+            rest = rest[end..].strip_prefix(';').unwrap_or(&rest[end..]);
+        } else {
+            decoded.push('&');
+            rest = &rest[1..];
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let compact = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match compact.find([':', '/', '?', '#']) {
+        Some(pos) if compact[pos..].starts_with(':') => ALLOWED_SCHEMES.contains(&&compact[..pos]),
+        _ => true,
+    }
+}
+
+fn escape_text(text: &str, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            _ => output.push(c),
+        }
+    }
+}
+
+fn escape_attr(value: &str, output: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            _ => output.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize_html;
+
+    #[test]
+    fn keeps_allowed_markup() {
+        assert_eq!(
+            sanitize_html("<b>bold</b> <i>it</i><br/><code>x &amp; y</code>"),
+            "<b>bold</b> <i>it</i><br><code>x &amp; y</code>"
+        );
+        assert_eq!(
+            sanitize_html("<UL><li>one<LI>two</ul>"),
+            "<ul><li>one<li>two</li></li></ul>"
+        );
+        assert_eq!(
+            sanitize_html(r#"<a href="https://example.com/?a=1&b=2" target="_top">x</a>"#),
+            r#"<a href="https://example.com/?a=1&amp;b=2" rel="noopener noreferrer">x</a>"#
+        );
+        assert_eq!(
+            sanitize_html("<a href=/docs>docs</a>"),
+            r#"<a href="/docs" rel="noopener noreferrer">docs</a>"#
+        );
+    }
+
+    #[test]
+    fn removes_scripts() {
+        assert_eq!(sanitize_html("<script>alert(1)</script>ok"), "ok");
+        assert_eq!(sanitize_html("<SCRIPT SRC=//x.js></SCRIPT>ok"), "ok");
+        assert_eq!(sanitize_html("<script>alert(1)"), "");
+        assert_eq!(sanitize_html("<style>*{}</style ><b>ok</b>"), "<b>ok</b>");
+        assert_eq!(sanitize_html("<svg><script>alert(1)</script></svg>"), "");
+        assert_eq!(sanitize_html("<!--<script>x</script>-->ok"), "ok");
+    }
+
+    #[test]
+    fn removes_event_handlers_and_unknown_tags() {
+        assert_eq!(sanitize_html("<img src=x onerror=alert(1)>"), "");
+        assert_eq!(
+            sanitize_html(r#"<b onmouseover="alert(1)">x</b>"#),
+            "<b>x</b>"
+        );
+        assert_eq!(
+            sanitize_html(r#"<div style="background:url(javascript:x)">x</div>"#),
+            "<div>x</div>"
+        );
+        assert_eq!(
+            sanitize_html("<iframe src=javascript:alert(1)></iframe>"),
+            ""
+        );
+        assert_eq!(sanitize_html("<body onload=alert(1)>hi"), "hi");
+    }
+
+    #[test]
+    fn removes_dangerous_urls() {
+        for payload in [
+            r#"<a href="javascript:alert(1)">x</a>"#,
+            r#"<a href="JaVaScRiPt:alert(1)">x</a>"#,
+            r#"<a href=" java&#x09;script:alert(1)">x</a>"#,
+            r#"<a href="javascript&#58;alert(1)">x</a>"#,
+            r#"<a href="javascript&colon;alert(1)">x</a>"#,
+            r#"<a href="data:text/html;base64,PHNjcmlwdD4=">x</a>"#,
+            r#"<a href="vbscript:msgbox(1)">x</a>"#,
+        ] {
+            assert_eq!(
+                sanitize_html(payload),
+                r#"<a rel="noopener noreferrer">x</a>"#,
+                "{payload}"
+            );
+        }
+    }
+
+    #[test]
+    fn escapes_stray_markup() {
+        assert_eq!(sanitize_html("1 < 2 > 0"), "1 &lt; 2 &gt; 0");
+        assert_eq!(sanitize_html("<b>x</i></b>"), "<b>x</b>");
+        assert_eq!(sanitize_html("</div><b>open"), "<b>open</b>");
+        assert_eq!(
+            sanitize_html(r#"<a href="x" title='"><script>'>y</a>"#),
+            r#"<a href="x" title="&quot;&gt;&lt;script&gt;" rel="noopener noreferrer">y</a>"#
+        );
+        assert_eq!(sanitize_html("<b unterminated"), "&lt;b unterminated");
+    }
+}
@@ -1,4 +1,7 @@
 pub mod complex;
+mod hierarchy;
 pub mod mini;
+mod navigation;
+mod search;
 pub mod searchable;
 pub mod vec_searchable;
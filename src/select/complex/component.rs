@@ -3,17 +3,21 @@ use std::collections::{
     HashMap, HashSet,
     hash_map::Entry::{Occupied, Vacant},
 };
+use std::ops::Range;
 use std::rc::Rc;
 
 use json_gettext::get_text;
+use web_sys::{HtmlElement, KeyboardEvent};
 use yew::virtual_dom::AttrValue;
-use yew::{Component, Context, Html, Properties, classes, html};
+use yew::{Callback, Component, Context, Html, NodeRef, Properties, classes, html};
 
 use super::DEFAULT_POP_WIDTH;
-use crate::click_outside::toggle_visibility_complex;
+use crate::click_outside::{is_visible, toggle_visibility_complex};
+use crate::select::navigation::{NavKey, TypeAhead, move_active, now, option_id, scroll_into_view};
+use crate::select::search::{SearchIndex, match_network};
 use crate::{
-    CheckStatus, ComplexSelection, EndpointKind, NetworkGroup, NetworkItem, SelectionExtraInfo,
-    Texts, Theme, language::Language, text, validate_host_network,
+    CheckStatus, ComplexSelection, EndpointKind, NetworkItem, SelectionExtraInfo, Texts, Theme,
+    language::Language, text, validate_host_network,
 };
 
 #[cfg(feature = "pumpkin")]
@@ -40,10 +44,13 @@ pub struct Directions {
 }
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq)]
 pub struct Model {
     pub(super) search_result: Option<Vec<usize>>,
     pub(super) search_text: String,
+    pub(super) search_index: SearchIndex,
+    // matched characters of the name of each item in `search_result`
+    pub(super) highlights: HashMap<usize, Vec<Range<usize>>>,
     pub(super) input_text: String,
     pub(super) input_wrong_msg: Option<&'static str>,
     pub(super) directions: Directions,
@@ -51,6 +58,15 @@ pub struct Model {
 
     pub(super) view_list: bool,
     pub(super) view_input: bool,
+
+    // position of the highlighted option among the visible registered items
+    pub(super) active: Option<usize>,
+    pub(super) expanded: bool,
+    pub(super) type_ahead: TypeAhead,
+    pub(super) scroll_to_active: bool,
+    pub(super) pending_focus: Option<NodeRef>,
+    pub(super) top: NodeRef,
+    pub(super) search: NodeRef,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -67,6 +83,7 @@ pub enum Message {
     ClickAddInput,
     DeleteInputItem(String),
     Render,
+    Key(String),
     SetDirection,
     SetDirectionItem(ItemKind),
     InputError,
@@ -106,10 +123,19 @@ impl Component for Model {
         let mut s = Self {
             search_result: None,
             search_text: String::new(),
+            search_index: Self::build_search_index(ctx),
+            highlights: HashMap::new(),
             input_text: String::new(),
             input_wrong_msg: None,
             view_list: false,
             view_input: false,
+            active: None,
+            expanded: false,
+            type_ahead: TypeAhead::default(),
+            scroll_to_active: false,
+            pending_focus: None,
+            top: NodeRef::default(),
+            search: NodeRef::default(),
             directions: Directions {
                 registered: Rc::new(RefCell::new(None)),
                 custom: Rc::new(RefCell::new(None)),
@@ -137,15 +163,36 @@ impl Component for Model {
             }
         }
         self.buffer_direction_items(ctx);
+        self.search_index = Self::build_search_index(ctx);
+        self.search(ctx);
 
         true
     }
 
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        if let Some(element) = self
+            .pending_focus
+            .take()
+            .and_then(|node| node.cast::<HtmlElement>())
+        {
+            let _ = element.focus();
+        }
+        if std::mem::take(&mut self.scroll_to_active)
+            && let Some(index) = self.active_index(ctx)
+        {
+            scroll_into_view(&option_id(&ctx.props().id, index));
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Message::Click | Message::Close => {
                 let _ = toggle_visibility_complex(&ctx.props().id);
+                self.expanded = is_visible(&ctx.props().id);
+            }
+            Message::Key(key) => {
+                return NavKey::from_key(&key).is_some_and(|key| self.handle_key(ctx, key));
             }
             Message::ToggleList => {
                 self.view_list = !self.view_list;
@@ -160,32 +207,18 @@ impl Component for Model {
                 }
             }
             Message::InputSearch(text) => {
-                self.search_text.clone_from(&text);
-                if text.is_empty() {
-                    self.search_result = None;
-                } else if let Ok(list) = ctx.props().list.try_borrow() {
-                    let text = text.to_lowercase();
-                    self.search_result = Some(
-                        list.iter()
-                            .enumerate()
-                            .filter_map(|(i, item)| {
-                                if let Some(networks) = item.networks() {
-                                    if search_network_ip_item(
-                                        &item.value.to_string(),
-                                        networks,
-                                        &text,
-                                    ) {
-                                        Some(i)
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect(),
-                    );
+                self.search_text = text;
+                // `list` can be altered in place without changing `Properties`.
+                if ctx
+                    .props()
+                    .list
+                    .try_borrow()
+                    .is_ok_and(|list| list.len() != self.search_index.len())
+                {
+                    self.search_index = Self::build_search_index(ctx);
                 }
+                self.search(ctx);
+                self.active = (!self.visible_indices(ctx).is_empty()).then_some(0);
             }
             Message::InputInput(text) => {
                 self.input_wrong_msg = None;
@@ -767,7 +800,15 @@ impl Component for Model {
         html! {
             <div class="complex-select">
                 <div onclick={onclick} class="complex-select-top">
-                    <input type="text" class={classes!("complex-select-top-input", class_input)} readonly={true} value={value} style={style} />
+                    <input type="text" class={classes!("complex-select-top-input", class_input)} readonly={true} value={value} style={style}
+                        ref={self.top.clone()}
+                        role="combobox"
+                        aria-haspopup="listbox"
+                        aria-expanded={self.expanded.to_string()}
+                        aria-controls={format!("{}-listbox", ctx.props().id)}
+                        aria-activedescendant={self.active_descendant(ctx)}
+                        onkeydown={Self::onkeydown(ctx, false)}
+                    />
                 </div>
                 { self.view_pop(ctx) }
             </div>
@@ -776,6 +817,172 @@ impl Component for Model {
 }
 
 impl Model {
+    pub(super) fn onkeydown(ctx: &Context<Self>, in_search: bool) -> Callback<KeyboardEvent> {
+        ctx.link().batch_callback(move |e: KeyboardEvent| {
+            let key = NavKey::from_event(&e)?;
+            // keys editing the search text are left to the input, and Escape
+            // is handled by the pop-up
+            if in_search
+                && matches!(
+                    key,
+                    NavKey::Char(_)
+                        | NavKey::Space
+                        | NavKey::Left
+                        | NavKey::Right
+                        | NavKey::Home
+                        | NavKey::End
+                        | NavKey::Escape
+                )
+            {
+                return None;
+            }
+            if key.prevents_default() {
+                e.prevent_default();
+            }
+            Some(Message::Key(e.key()))
+        })
+    }
+
+    pub(super) fn active_descendant(&self, ctx: &Context<Self>) -> Option<String> {
+        if self.expanded && self.view_list {
+            self.active_index(ctx)
+                .map(|index| option_id(&ctx.props().id, index))
+        } else {
+            None
+        }
+    }
+
+    fn build_search_index(ctx: &Context<Self>) -> SearchIndex {
+        ctx.props()
+            .list
+            .try_borrow()
+            .map(|list| SearchIndex::new(list.iter().map(NetworkItem::value)))
+            .unwrap_or_default()
+    }
+
+    /// Updates `search_result` for `search_text`, best matches first. An item
+    /// matches by its name or by the addresses of its networks.
+    fn search(&mut self, ctx: &Context<Self>) {
+        self.highlights.clear();
+        if self.search_text.trim().is_empty() {
+            self.search_result = None;
+            return;
+        }
+        let Ok(list) = ctx.props().list.try_borrow() else {
+            return;
+        };
+        let mut scores = self
+            .search_index
+            .search(&self.search_text)
+            .into_iter()
+            .map(|m| (m.index, (m.score, m.ranges)))
+            .collect::<HashMap<_, _>>();
+        for (index, item) in list.iter().enumerate() {
+            if let Some(score) = item
+                .networks()
+                .and_then(|networks| match_network(&self.search_text, networks))
+            {
+                scores
+                    .entry(index)
+                    .and_modify(|(s, _)| *s = (*s).max(score))
+                    .or_insert((score, Vec::new()));
+            }
+        }
+        let mut matches = scores
+            .into_iter()
+            .filter(|(index, _)| {
+                list.get(*index)
+                    .is_some_and(|item| item.networks().is_some())
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, (a_score, _)), (b, (b_score, _))| {
+            b_score.cmp(a_score).then_with(|| a.cmp(b))
+        });
+        self.search_result = Some(matches.iter().map(|(index, _)| *index).collect());
+        self.highlights = matches
+            .into_iter()
+            .map(|(index, (_, ranges))| (index, ranges))
+            .collect();
+    }
+
+    /// Returns the indices in `list` of the registered items currently shown.
+    fn visible_indices(&self, ctx: &Context<Self>) -> Vec<usize> {
+        self.search_result.clone().unwrap_or_else(|| {
+            (0..ctx.props().list.try_borrow().map_or(0, |list| list.len())).collect()
+        })
+    }
+
+    /// Returns the index in `list` of the highlighted item.
+    pub(super) fn active_index(&self, ctx: &Context<Self>) -> Option<usize> {
+        self.active
+            .and_then(|active| self.visible_indices(ctx).get(active).copied())
+    }
+
+    fn handle_key(&mut self, ctx: &Context<Self>, key: NavKey) -> bool {
+        let id = ctx.props().id.clone();
+        self.expanded = is_visible(&id);
+        let visible = self.visible_indices(ctx);
+        if !self.expanded {
+            if !matches!(
+                key,
+                NavKey::Down | NavKey::Up | NavKey::Enter | NavKey::Space
+            ) {
+                return false;
+            }
+            let _ = toggle_visibility_complex(&id);
+            self.expanded = true;
+            self.view_list = true;
+            self.view_input = false;
+            self.active = (!visible.is_empty()).then_some(0);
+            self.pending_focus = Some(self.search.clone());
+            return true;
+        }
+        match key {
+            NavKey::Down | NavKey::Up | NavKey::Home | NavKey::End => {
+                if !self.view_list {
+                    self.view_list = true;
+                    self.view_input = false;
+                }
+                self.active = move_active(self.active, visible.len(), key);
+                self.scroll_to_active = true;
+            }
+            NavKey::Enter | NavKey::Space => {
+                let item_id = self.active_index(ctx).and_then(|index| {
+                    ctx.props().list.try_borrow().ok().and_then(|list| {
+                        list.get(index)
+                            .filter(|item| item.networks().is_some())
+                            .map(|item| item.id().clone())
+                    })
+                });
+                let Some(item_id) = item_id else {
+                    return false;
+                };
+                return self.update(ctx, Message::ClickItem(item_id, ItemKind::Registered));
+            }
+            NavKey::Escape => {
+                let _ = toggle_visibility_complex(&id);
+                self.expanded = false;
+                self.pending_focus = Some(self.top.clone());
+            }
+            NavKey::Tab | NavKey::Left | NavKey::Right => return false,
+            NavKey::Char(c) => {
+                let Ok(list) = ctx.props().list.try_borrow() else {
+                    return false;
+                };
+                let labels = visible
+                    .iter()
+                    .filter_map(|&index| list.get(index))
+                    .map(|item| item.value().clone())
+                    .collect::<Vec<_>>();
+                if let Some(position) = self.type_ahead.search(c, now(), &labels, self.active) {
+                    self.active = Some(position);
+                    self.scroll_to_active = true;
+                }
+            }
+        }
+        true
+    }
+
     pub(super) fn check_status(&self, ctx: &Context<Self>, search: bool) -> CheckStatus {
         if let (Ok(predefined), Ok(list)) = (
             ctx.props().selected.predefined.try_borrow(),
@@ -1167,29 +1374,3 @@ pub(super) fn check_network(
             }
         })
 }
-
-#[inline]
-fn search_network_ip_item(name: &str, networks: &NetworkGroup, text: &str) -> bool {
-    if name.to_lowercase().contains(text) {
-        return true;
-    }
-    for host in &networks.hosts {
-        if host.contains(text) {
-            return true;
-        }
-    }
-    for n in &networks.networks {
-        if n.contains(text) {
-            return true;
-        }
-    }
-    for r in &networks.ranges {
-        if r.start.contains(text) {
-            return true;
-        }
-        if r.end.contains(text) {
-            return true;
-        }
-    }
-    false
-}
@@ -4,10 +4,12 @@ use htmlescape::decode_html;
 use json_gettext::get_text;
 use wasm_bindgen::JsCast;
 use web_sys::{HtmlInputElement, KeyboardEvent};
-use yew::{Context, Html, events::InputEvent, html};
+use yew::{Context, Html, classes, events::InputEvent, html};
 
 use super::{MIN_POP_HEIGHT, Message, Model};
 use crate::select::complex::component::check_network;
+use crate::select::navigation::option_id;
+use crate::select::search::{highlight, substring_ranges};
 use crate::{
     CheckStatus, Checkbox, EndpointKind, NBSP, NetworkItem, SelectComplexKind, SelectMini,
     SelectMiniKind, SelectionExtraInfo, Theme, ViewString, select::complex::ItemKind, text,
@@ -78,9 +80,16 @@ impl Model {
         let onclick_list = ctx.link().callback(|_| Message::ToggleList);
         let onclick_all = ctx.link().callback(|_| Message::ClickAll);
         let onclick_close = ctx.link().callback(|_| Message::Close);
+        let onkeydown_pop = ctx.link().batch_callback(|e: KeyboardEvent| {
+            (e.key() == "Escape").then(|| Message::Key(e.key()))
+        });
 
         html! {
-            <div id={ctx.props().id.clone()} class="complex-select-pop" style={style_pop}>
+            <div id={ctx.props().id.clone()} class="complex-select-pop" style={style_pop}
+                role="dialog"
+                aria-label={text!(txt, ctx.props().language, &ctx.props().title).to_string()}
+                onkeydown={onkeydown_pop}
+            >
                 <div class="complex-select-pop-head">
                     {
                         if cfg!(feature = "pumpkin") {
@@ -384,6 +393,11 @@ impl Model {
                         style={style_width_search}
                         oninput={oninput_search}
                         value={self.search_text.clone()}
+                        ref={self.search.clone()}
+                        aria-label={text!(txt, ctx.props().language, "Search").to_string()}
+                        aria-controls={format!("{}-listbox", ctx.props().id)}
+                        aria-activedescendant={self.active_descendant(ctx)}
+                        onkeydown={Self::onkeydown(ctx, true)}
                     />
                 </div>
                 {
@@ -410,7 +424,11 @@ impl Model {
                                                 </div>
                                                 <div class="complex-select-pop-list-divider">
                                                 </div>
-                                                <div class="complex-select-pop-list-container">
+                                                <div class="complex-select-pop-list-container"
+                                                    id={format!("{}-listbox", ctx.props().id)}
+                                                    role="listbox"
+                                                    aria-multiselectable="true"
+                                                >
                                                     { self.view_registered_list_items(ctx) }
                                                 </div>
                                             </>
@@ -454,7 +472,11 @@ impl Model {
                                         SelectComplexKind::Basic => html! {},
                                     }
                                 }
-                                <div class="complex-select-pop-list-list-items" style={style_pop_list_list_items}>
+                                <div class="complex-select-pop-list-list-items" style={style_pop_list_list_items}
+                                    id={format!("{}-listbox", ctx.props().id)}
+                                    role="listbox"
+                                    aria-multiselectable="true"
+                                >
                                     {
                                         if cfg!(feature = "pumpkin") {
                                             html! {
@@ -479,11 +501,11 @@ impl Model {
         if let Ok(list) = ctx.props().list.try_borrow() {
             if let Some(search) = self.search_result.as_ref() {
                 html! {
-                    for search.iter().filter_map(|&index| list.get(index)).map(|item| self.view_list_item(ctx, item))
+                    for search.iter().filter_map(|&index| list.get(index).map(|item| (index, item))).map(|(index, item)| self.view_list_item(ctx, index, item))
                 }
             } else {
                 html! {
-                    for list.iter().map(|item| self.view_list_item(ctx, item))
+                    for list.iter().enumerate().map(|(index, item)| self.view_list_item(ctx, index, item))
                 }
             }
         } else {
@@ -556,7 +578,7 @@ impl Model {
     }
 
     #[allow(clippy::too_many_lines)]
-    fn view_list_item(&self, ctx: &Context<Self>, item: &NetworkItem) -> Html {
+    fn view_list_item(&self, ctx: &Context<Self>, index: usize, item: &NetworkItem) -> Html {
         let (key, checked) = if item.networks().is_some() {
             (
                 item.id.clone(),
@@ -583,9 +605,16 @@ impl Model {
             (SelectComplexKind::Basic, _) => "width: 279px;",
         };
         let theme = ctx.props().theme;
+        let option_id = option_id(&ctx.props().id, index);
+        let class_active = (self.active_index(ctx) == Some(index))
+            .then_some("complex-select-pop-list-item-active");
+        let aria_selected = (checked == CheckStatus::Checked).to_string();
+        let name_ranges = self.highlights.get(&index).map_or(&[][..], Vec::as_slice);
         if cfg!(feature = "pumpkin") {
             html! {
-                <div class="complex-select-pop-list-item">
+                <div class={classes!("complex-select-pop-list-item", class_active)}
+                    id={option_id} role="option" aria-selected={aria_selected}
+                >
                     <div class="complex-select-pop-list-item-checkbox">
                         <div onclick={onclick_item(key)}>
                             <Checkbox status={checked} {theme} />
@@ -595,26 +624,26 @@ impl Model {
                                 if let Some(networks)=item.networks() {
                                         html! {
                                             <>
-                                                { item.value().clone() } <br/>
+                                                { highlight(&item.value(), name_ranges, usize::MAX) } <br/>
                                                 <div class="complex-select-pop-list-networks">
                                                 {
                                                     for networks.hosts.iter().map(|host| html! {
                                                         <>
-                                                            { host } <br/>
+                                                            { self.highlight_address(host) } <br/>
                                                         </>
                                                     })
                                                 }
                                                 {
                                                     for networks.networks.iter().map(|nt| html! {
                                                         <>
-                                                            { nt } <br/>
+                                                            { self.highlight_address(nt) } <br/>
                                                         </>
                                                     })
                                                 }
                                                 {
                                                     for networks.ranges.iter().map(|r| html! {
                                                         <>
-                                                            { r.start.clone() } { " - " } { r.end.clone() } <br/>
+                                                            { self.highlight_address(&r.start) } { " - " } { self.highlight_address(&r.end) } <br/>
                                                         </>
                                                     })
                                                 }
@@ -633,7 +662,9 @@ impl Model {
             }
         } else {
             html! {
-                <table>
+                <table class={classes!(class_active)}
+                    id={option_id} role="option" aria-selected={aria_selected}
+                >
                     <tr>
                         <td class="complex-select-pop-list-list-items-checkbox">
                             <div onclick={onclick_item(key)}>
@@ -645,26 +676,26 @@ impl Model {
                             if let Some(networks)=item.networks() {
                                     html! {
                                         <>
-                                            { item.value().clone() } <br/>
+                                            { highlight(&item.value(), name_ranges, usize::MAX) } <br/>
                                             <div class="complex-select-pop-list-networks">
                                             {
                                                 for networks.hosts.iter().map(|host| html! {
                                                     <>
-                                                        { host } <br/>
+                                                        { self.highlight_address(host) } <br/>
                                                     </>
                                                 })
                                             }
                                             {
                                                 for networks.networks.iter().map(|nt| html! {
                                                     <>
-                                                        { nt } <br/>
+                                                        { self.highlight_address(nt) } <br/>
                                                     </>
                                                 })
                                             }
                                             {
                                                 for networks.ranges.iter().map(|r| html! {
                                                     <>
-                                                        { r.start.clone() } { " - " } { r.end.clone() } <br/>
+                                                        { self.highlight_address(&r.start) } { " - " } { self.highlight_address(&r.end) } <br/>
                                                     </>
                                                 })
                                             }
@@ -693,6 +724,18 @@ impl Model {
         }
     }
 
+    fn highlight_address(&self, address: &str) -> Html {
+        if self.search_result.is_some() {
+            highlight(
+                address,
+                &substring_ranges(address, &self.search_text),
+                usize::MAX,
+            )
+        } else {
+            html! { address }
+        }
+    }
+
     fn view_network_ip_item_direction(
         &self,
         ctx: &Context<Self>,
@@ -0,0 +1,352 @@
+//! Option groups and trees of options built from [`Item::parent`] and
+//! [`Item::group`].
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{CheckStatus, Item, ViewString};
+
+/// A row shown in a select list when it is not being searched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Row {
+    /// The header of an option group
+    Header(ViewString),
+    /// The option at `index` in the list, `depth` levels below the top
+    Option {
+        index: usize,
+        depth: usize,
+        has_children: bool,
+    },
+}
+
+/// The structure of a list of options.
+///
+/// An item whose parent is missing from the list, or which is its own
+/// ancestor, is treated as a top-level item.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Hierarchy {
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    // top-level items by group, in the order the groups first appear
+    groups: Vec<(Option<ViewString>, Vec<usize>)>,
+}
+
+impl Hierarchy {
+    pub(crate) fn new(list: &[Item]) -> Self {
+        let positions = list
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (item.id(), index))
+            .collect::<HashMap<_, _>>();
+        let mut parents = list
+            .iter()
+            .map(|item| {
+                item.parent()
+                    .and_then(|parent| positions.get(parent).copied())
+            })
+            .collect::<Vec<_>>();
+        for index in 0..parents.len() {
+            let mut ancestor = parents[index];
+            let mut steps = 0;
+            while let Some(current) = ancestor {
+                steps += 1;
+                if current == index || steps > parents.len() {
+                    parents[index] = None;
+                    break;
+                }
+                ancestor = parents[current];
+            }
+        }
+
+        let mut children = vec![Vec::new(); list.len()];
+        let mut groups: Vec<(Option<ViewString>, Vec<usize>)> = Vec::new();
+        for (index, item) in list.iter().enumerate() {
+            if let Some(parent) = parents[index] {
+                children[parent].push(index);
+            } else if let Some(roots) = groups
+                .iter_mut()
+                .find(|(group, _)| group.as_ref() == item.group())
+            {
+                roots.1.push(index);
+            } else {
+                groups.push((item.group().cloned(), vec![index]));
+            }
+        }
+        // ungrouped items come first
+        groups.sort_by_key(|(group, _)| group.is_some());
+        Self {
+            parents,
+            children,
+            groups,
+        }
+    }
+
+    pub(crate) fn parent(&self, index: usize) -> Option<usize> {
+        self.parents.get(index).copied().flatten()
+    }
+
+    pub(crate) fn has_children(&self, index: usize) -> bool {
+        self.children
+            .get(index)
+            .is_some_and(|children| !children.is_empty())
+    }
+
+    /// Returns `true` if any item has a child.
+    pub(crate) fn is_tree(&self) -> bool {
+        self.parents.iter().any(Option::is_some)
+    }
+
+    /// Returns all the descendants of the item at `index`.
+    pub(crate) fn descendants(&self, index: usize) -> Vec<usize> {
+        let mut descendants = Vec::new();
+        let mut stack = self.children.get(index).cloned().unwrap_or_default();
+        while let Some(child) = stack.pop() {
+            descendants.push(child);
+            stack.extend(self.children[child].iter().copied());
+        }
+        descendants
+    }
+
+    /// Returns the rows to show, skipping the descendants of items whose
+    /// identifiers are not in `expanded`.
+    pub(crate) fn rows(&self, list: &[Item], expanded: &HashSet<String>) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (group, roots) in &self.groups {
+            if let Some(group) = group {
+                rows.push(Row::Header(group.clone()));
+            }
+            let mut stack = roots
+                .iter()
+                .rev()
+                .map(|&root| (root, 0))
+                .collect::<Vec<_>>();
+            while let Some((index, depth)) = stack.pop() {
+                let has_children = self.has_children(index);
+                rows.push(Row::Option {
+                    index,
+                    depth,
+                    has_children,
+                });
+                if has_children
+                    && list
+                        .get(index)
+                        .is_some_and(|item| expanded.contains(item.id()))
+                {
+                    stack.extend(self.children[index].iter().rev().map(|&c| (c, depth + 1)));
+                }
+            }
+        }
+        rows
+    }
+
+    /// Returns the check status of the item at `index`, derived from its
+    /// descendants if it has any.
+    pub(crate) fn check_status(
+        &self,
+        list: &[Item],
+        selected: Option<&HashSet<String>>,
+        index: usize,
+    ) -> CheckStatus {
+        let Some(selected) = selected else {
+            return CheckStatus::Checked;
+        };
+        let is_selected = |index: usize| {
+            list.get(index)
+                .is_some_and(|item| selected.contains(item.id()))
+        };
+        if !self.has_children(index) {
+            return if is_selected(index) {
+                CheckStatus::Checked
+            } else {
+                CheckStatus::Unchecked
+            };
+        }
+        let descendants = self.descendants(index);
+        let count = descendants.iter().filter(|&&d| is_selected(d)).count();
+        if count == 0 {
+            CheckStatus::Unchecked
+        } else if count == descendants.len() {
+            CheckStatus::Checked
+        } else {
+            CheckStatus::Indeterminate
+        }
+    }
+
+    /// Returns the check status of every item as [`Self::check_status`]
+    /// does, in a single pass over the list.
+    pub(crate) fn check_statuses(
+        &self,
+        list: &[Item],
+        selected: Option<&HashSet<String>>,
+    ) -> Vec<CheckStatus> {
+        let Some(selected) = selected else {
+            return vec![CheckStatus::Checked; self.parents.len()];
+        };
+        let is_selected = |index: usize| {
+            list.get(index)
+                .is_some_and(|item| selected.contains(item.id()))
+        };
+        // items in an order where every item comes after its descendants
+        let mut order = Vec::with_capacity(self.parents.len());
+        let mut stack = (0..self.parents.len())
+            .filter(|&index| self.parents[index].is_none())
+            .collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            order.push(index);
+            stack.extend(self.children[index].iter().copied());
+        }
+        // (selected descendants, all descendants) of each item
+        let mut counts = vec![(0, 0); self.parents.len()];
+        for &index in order.iter().rev() {
+            if let Some(parent) = self.parents[index] {
+                let (count, total) = counts[index];
+                counts[parent].0 += count + usize::from(is_selected(index));
+                counts[parent].1 += total + 1;
+            }
+        }
+        counts
+            .iter()
+            .enumerate()
+            .map(|(index, &(count, total))| {
+                if total == 0 {
+                    if is_selected(index) {
+                        CheckStatus::Checked
+                    } else {
+                        CheckStatus::Unchecked
+                    }
+                } else if count == 0 {
+                    CheckStatus::Unchecked
+                } else if count == total {
+                    CheckStatus::Checked
+                } else {
+                    CheckStatus::Indeterminate
+                }
+            })
+            .collect()
+    }
+
+    /// Selects or deselects the item at `index` together with its
+    /// descendants, then updates its ancestors.
+    pub(crate) fn toggle(&self, list: &[Item], selected: &mut HashSet<String>, index: usize) {
+        let mut subtree = self.descendants(index);
+        subtree.push(index);
+        let check = self.check_status(list, Some(selected), index) != CheckStatus::Checked;
+        for item in subtree.iter().filter_map(|&i| list.get(i)) {
+            if check {
+                selected.insert(item.id().clone());
+            } else {
+                selected.remove(item.id());
+            }
+        }
+        self.sync_ancestors(list, selected);
+    }
+
+    /// Selects exactly the items with children whose descendants are all
+    /// selected.
+    pub(crate) fn sync_ancestors(&self, list: &[Item], selected: &mut HashSet<String>) {
+        for (index, item) in list.iter().enumerate() {
+            if !self.has_children(index) {
+                continue;
+            }
+            if self.check_status(list, Some(selected), index) == CheckStatus::Checked {
+                selected.insert(item.id().clone());
+            } else {
+                selected.remove(item.id());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{Hierarchy, Row};
+    use crate::{CheckStatus, Item, ViewString};
+
+    fn item(id: &str, parent: Option<&str>) -> Item {
+        let item = Item::new(id.to_string(), ViewString::Raw(id.to_string()));
+        match parent {
+            Some(parent) => item.with_parent(parent.to_string()),
+            None => item,
+        }
+    }
+
+    #[test]
+    fn rows() {
+        let list = vec![
+            item("b", None).with_group(ViewString::Raw("g".to_string())),
+            item("a", None),
+            item("a1", Some("a")),
+            item("a2", Some("a")),
+            item("x", Some("missing")),
+        ];
+        let hierarchy = Hierarchy::new(&list);
+        let option = |index, depth, has_children| Row::Option {
+            index,
+            depth,
+            has_children,
+        };
+        assert_eq!(
+            hierarchy.rows(&list, &HashSet::new()),
+            vec![
+                option(1, 0, true),
+                option(4, 0, false),
+                Row::Header(ViewString::Raw("g".to_string())),
+                option(0, 0, false),
+            ]
+        );
+        let expanded = HashSet::from(["a".to_string()]);
+        assert_eq!(
+            hierarchy.rows(&list, &expanded)[..3],
+            [option(1, 0, true), option(2, 1, false), option(3, 1, false)]
+        );
+    }
+
+    #[test]
+    fn toggle_selects_descendants() {
+        let list = vec![
+            item("a", None),
+            item("a1", Some("a")),
+            item("a2", Some("a")),
+            item("a21", Some("a2")),
+        ];
+        let hierarchy = Hierarchy::new(&list);
+        let mut selected = HashSet::new();
+        hierarchy.toggle(&list, &mut selected, 3);
+        assert_eq!(selected.len(), 2);
+        assert_eq!(
+            hierarchy.check_status(&list, Some(&selected), 0),
+            CheckStatus::Indeterminate
+        );
+
+        let statuses = hierarchy.check_statuses(&list, Some(&selected));
+        for (index, status) in statuses.iter().enumerate() {
+            assert_eq!(
+                *status,
+                hierarchy.check_status(&list, Some(&selected), index)
+            );
+        }
+        assert_eq!(
+            statuses,
+            vec![
+                CheckStatus::Indeterminate,
+                CheckStatus::Unchecked,
+                CheckStatus::Checked,
+                CheckStatus::Checked,
+            ]
+        );
+
+        hierarchy.toggle(&list, &mut selected, 0);
+        assert_eq!(selected.len(), 4);
+        hierarchy.toggle(&list, &mut selected, 0);
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn cycles_become_roots() {
+        let list = vec![item("a", Some("b")), item("b", Some("a"))];
+        let hierarchy = Hierarchy::new(&list);
+        assert_eq!(hierarchy.parent(0), None);
+        assert_eq!(hierarchy.parent(1), Some(0));
+    }
+}
@@ -3,16 +3,21 @@ use std::{cell::RefCell, marker::PhantomData};
 
 use gloo_events::EventListener;
 use json_gettext::get_text;
-use web_sys::{Event, HtmlElement};
+use web_sys::{Event, HtmlElement, KeyboardEvent};
 use yew::virtual_dom::AttrValue;
 use yew::{Component, Context, Html, NodeRef, Properties, classes, html};
 
-use crate::click_outside::toggle_visibility;
+use super::navigation::{NavKey, TypeAhead, move_active, now, option_id, scroll_into_view};
+use crate::click_outside::{is_visible, toggle_visibility};
 use crate::{Texts, Theme, ViewString, language::Language, text};
 
 pub struct Model<T, U> {
     click_listener: Option<EventListener>,
     click_count: usize,
+    active_option: Option<usize>,
+    expanded: bool,
+    type_ahead: TypeAhead,
+    scroll_to_active: bool,
     phantom: PhantomData<(T, U)>,
 }
 
@@ -32,6 +37,7 @@ pub enum Kind {
 pub enum Message {
     ClickTop,
     ClickItem(usize),
+    Key(String),
     ListenClick,
 }
 
@@ -66,6 +72,13 @@ where
     pub id: AttrValue,
     pub list: Rc<Vec<ViewString>>,
     pub candidate_values: Rc<Vec<T>>,
+    /// The icon paths of `Kind::MoreAction` items, overriding the built-in
+    /// icons of "Edit" and "Delete"
+    #[prop_or(None)]
+    pub list_icons: Option<Rc<Vec<Option<String>>>>,
+    /// Whether each item of `list` is disabled
+    #[prop_or(None)]
+    pub list_disabled: Option<Rc<Vec<bool>>>,
     #[prop_or(None)]
     pub default_value: Option<T>,
     // HIGHTLIGHT:
@@ -109,6 +122,10 @@ where
         let s = Self {
             click_listener: None,
             click_count: 0,
+            active_option: None,
+            expanded: false,
+            type_ahead: TypeAhead::default(),
+            scroll_to_active: false,
             phantom: PhantomData,
         };
         if ctx.props().active
@@ -122,6 +139,11 @@ where
     }
 
     fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if std::mem::take(&mut self.scroll_to_active)
+            && let Some(index) = self.active_option
+        {
+            scroll_into_view(&option_id(&ctx.props().id, index));
+        }
         if !first_render {
             return;
         }
@@ -139,25 +161,34 @@ where
             Message::ClickTop => {
                 if ctx.props().active {
                     let _ = toggle_visibility(&ctx.props().id);
+                    self.expanded = is_visible(&ctx.props().id);
+                    if self.expanded {
+                        self.active_option = Self::selected_index(ctx);
+                        self.scroll_to_active = true;
+                    }
                     ctx.props().parent_message.clone()
                 } else {
                     return false;
                 }
             }
             Message::ClickItem(index) => {
+                if Self::is_disabled(ctx, index) {
+                    return false;
+                }
                 if let Ok(mut selected) = ctx.props().selected_value.try_borrow_mut() {
                     *selected = ctx.props().candidate_values.get(index).copied();
                 }
                 let _ = toggle_visibility(&ctx.props().id);
+                self.expanded = is_visible(&ctx.props().id);
                 ctx.props().parent_message.clone()
             }
+            Message::Key(key) => {
+                return NavKey::from_key(&key).is_some_and(|key| self.handle_key(ctx, key));
+            }
             Message::ListenClick => {
                 self.click_count += 1;
                 if self.click_count > 1 {
-                    ctx.props()
-                        .parent_cancel_message
-                        .as_ref()
-                        .map_or(ctx.props().parent_message.clone(), Clone::clone)
+                    Self::cancel_message(ctx)
                 } else {
                     return false;
                 }
@@ -195,8 +226,31 @@ where
         } else {
             msg
         };
+        let listbox_id = format!("{}-listbox", ctx.props().id);
+        let active_descendant = if self.expanded {
+            self.active_option
+                .map(|index| option_id(&ctx.props().id, index))
+        } else {
+            None
+        };
+        let onkeydown = ctx.link().batch_callback(|e: KeyboardEvent| {
+            let key = NavKey::from_event(&e)?;
+            if key.prevents_default() {
+                e.prevent_default();
+            }
+            Some(Message::Key(e.key()))
+        });
         html! {
-            <div class="mini-select">
+            <div class="mini-select"
+                tabindex={if ctx.props().active { "0" } else { "-1" }}
+                role="combobox"
+                aria-haspopup="listbox"
+                aria-expanded={self.expanded.to_string()}
+                aria-controls={listbox_id}
+                aria-activedescendant={active_descendant}
+                aria-disabled={(!ctx.props().active).to_string()}
+                {onkeydown}
+            >
             {
                 match ctx.props().kind {
                     Kind::DirectionAll => Self::view_direction_all(ctx),
@@ -209,7 +263,7 @@ where
                     Kind::Round | Kind::Soft => Self::view_basic(ctx,&value),
                 }
             }
-            { Self::view_list(ctx,&value, theme) }
+            { self.view_list(ctx, &value, theme) }
             </div>
         }
     }
@@ -221,6 +275,105 @@ where
     U: Clone + Component + PartialEq,
     <U as Component>::Message: Clone + PartialEq,
 {
+    fn is_disabled(ctx: &Context<Self>, index: usize) -> bool {
+        ctx.props()
+            .list_disabled
+            .as_ref()
+            .is_some_and(|disabled| disabled.get(index).copied().unwrap_or(false))
+    }
+
+    /// The message sent to the parent when the list is closed without a
+    /// selection
+    fn cancel_message(ctx: &Context<Self>) -> U::Message {
+        ctx.props()
+            .parent_cancel_message
+            .as_ref()
+            .map_or(ctx.props().parent_message.clone(), Clone::clone)
+    }
+
+    /// Returns the index of the selected value in `list`.
+    fn selected_index(ctx: &Context<Self>) -> Option<usize> {
+        let selected = ctx
+            .props()
+            .selected_value
+            .try_borrow()
+            .ok()
+            .and_then(|v| *v);
+        selected
+            .and_then(|value| {
+                ctx.props()
+                    .candidate_values
+                    .iter()
+                    .position(|candidate| *candidate == value)
+            })
+            .or_else(|| (!ctx.props().list.is_empty()).then_some(0))
+    }
+
+    fn handle_key(&mut self, ctx: &Context<Self>, key: NavKey) -> bool {
+        if !ctx.props().active {
+            return false;
+        }
+        self.expanded = is_visible(&ctx.props().id);
+        if !self.expanded {
+            return match key {
+                NavKey::Down | NavKey::Up | NavKey::Enter | NavKey::Space => {
+                    self.update(ctx, Message::ClickTop)
+                }
+                _ => false,
+            };
+        }
+        let len = ctx.props().list.len();
+        match key {
+            NavKey::Down | NavKey::Up | NavKey::Home | NavKey::End => {
+                self.active_option = move_active(self.active_option, len, key);
+                self.scroll_to_active = true;
+            }
+            NavKey::Enter | NavKey::Space => {
+                return self
+                    .active_option
+                    .is_some_and(|index| self.update(ctx, Message::ClickItem(index)));
+            }
+            // The list of `Kind::MoreActionNoImage` is always shown, so the
+            // parent closes it.
+            NavKey::Escape if ctx.props().kind == Kind::MoreActionNoImage => {
+                if let Some(parent) = ctx.link().get_parent() {
+                    parent
+                        .clone()
+                        .downcast::<U>()
+                        .send_message(Self::cancel_message(ctx));
+                }
+                return false;
+            }
+            NavKey::Escape | NavKey::Tab => {
+                if ctx.props().kind != Kind::MoreActionNoImage {
+                    let _ = toggle_visibility(&ctx.props().id);
+                    self.expanded = false;
+                }
+            }
+            NavKey::Left | NavKey::Right => return false,
+            NavKey::Char(c) => {
+                let txt = &ctx.props().txt.txt;
+                let labels = ctx
+                    .props()
+                    .list
+                    .iter()
+                    .map(|item| match item {
+                        ViewString::Key(key) => text!(txt, ctx.props().language, key).to_string(),
+                        ViewString::Raw(raw) => raw.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(index) = self
+                    .type_ahead
+                    .search(c, now(), &labels, self.active_option)
+                {
+                    self.active_option = Some(index);
+                    self.scroll_to_active = true;
+                }
+            }
+        }
+        true
+    }
+
     fn value_to_text(ctx: &Context<Self>, value: &T) -> Option<String> {
         let txt = &ctx.props().txt.txt;
         ctx.props()
@@ -236,7 +389,7 @@ where
     }
 
     #[allow(clippy::too_many_lines)]
-    fn view_list(ctx: &Context<Self>, value: &str, theme: Option<Theme>) -> Html {
+    fn view_list(&self, ctx: &Context<Self>, value: &str, theme: Option<Theme>) -> Html {
         let list = ctx.props().list.clone();
         let onclick_item = |index: usize| ctx.link().callback(move |_| Message::ClickItem(index));
         let style_width = ctx
@@ -274,7 +427,7 @@ where
         let txt = ctx.props().txt.txt.clone();
         html! {
             <div id={ctx.props().id.clone()} class={classes!("mini-select-list-down", class)} style={style}>
-                <table class="mini-select-list-down-table">
+                <table class="mini-select-list-down-table" id={format!("{}-listbox", ctx.props().id)} role="listbox">
                     {
                         for list.iter().enumerate().map(|(index, item)|{
                         let item_text = match item {
@@ -286,35 +439,45 @@ where
                         } else {
                             "mini-select-list-down-item"
                         };
+                        let class_active = (self.active_option == Some(index))
+                            .then_some("mini-select-list-down-item-active");
+                        let disabled = Self::is_disabled(ctx, index);
+                        let class_disabled = disabled.then_some("mini-select-list-down-item-disabled");
                         html! {
-                            <tr>
+                            <tr id={option_id(&ctx.props().id, index)} role="option"
+                                class={classes!(class_active, class_disabled)}
+                                aria-selected={(item_text == value).to_string()}
+                                aria-disabled={disabled.then_some("true")}
+                            >
                                 {
                                     if ctx.props().kind == Kind::MoreAction {
-                                        match item {
-                                            ViewString::Key(key) => {
-                                                let ext = if cfg!(feature = "pumpkin") { "svg" } else { "png" };
-                                                let delete_icon_name = if cfg!(feature = "pumpkin") {
-                                                    "delete-trash-alt"
-                                                } else {
-                                                    "delete-trash"
-                                                };
-                                                let icon = match key.as_ref() {
-                                                    "Edit" => Theme::path(&theme, &format!("edit.{ext}")),
-                                                    "Delete" => Theme::path(&theme, &format!("{delete_icon_name}.{ext}")),
-                                                    _ => String::new(),
-                                                };
-                                                html! {
-                                                    <td class={classes!("mini-select-list-down-item", class_list_align)} onclick={onclick_item(index)} style={style_width.clone()}>
-                                                        <div class="mini-select-list-down-item-more-action">
-                                                            <img src={icon} class="mini-select-list-down-item-more-action" />
-                                                            <div class={classes!("mini-select-list-down-item-more-action-text", class_list_align_more_action)}>
-                                                            { text!(txt, ctx.props().language, key) }
-                                                            </div>
-                                                        </div>
-                                                    </td>
-                                                }
+                                        let custom_icon = ctx.props().list_icons.as_ref().and_then(|icons| icons.get(index).cloned().flatten());
+                                        let icon = custom_icon.unwrap_or_else(|| {
+                                            let ext = if cfg!(feature = "pumpkin") { "svg" } else { "png" };
+                                            let delete_icon_name = if cfg!(feature = "pumpkin") {
+                                                "delete-trash-alt"
+                                            } else {
+                                                "delete-trash"
+                                            };
+                                            match item {
+                                                ViewString::Key(key) if key == "Edit" => Theme::path(&theme, &format!("edit.{ext}")),
+                                                ViewString::Key(key) if key == "Delete" => Theme::path(&theme, &format!("{delete_icon_name}.{ext}")),
+                                                _ => String::new(),
                                             }
-                                            ViewString::Raw(_) => html! {}
+                                        });
+                                        html! {
+                                            <td class={classes!("mini-select-list-down-item", class_list_align)} onclick={onclick_item(index)} style={style_width.clone()}>
+                                                <div class="mini-select-list-down-item-more-action">
+                                                    if icon.is_empty() {
+                                                        <div class="mini-select-list-down-item-more-action-no-icon"></div>
+                                                    } else {
+                                                        <img src={icon} class="mini-select-list-down-item-more-action" />
+                                                    }
+                                                    <div class={classes!("mini-select-list-down-item-more-action-text", class_list_align_more_action)}>
+                                                    { item_text.clone() }
+                                                    </div>
+                                                </div>
+                                            </td>
                                         }
                                     } else if ctx.props().kind == Kind::OnOffAction {
                                         match item {
@@ -0,0 +1,191 @@
+//! Keyboard navigation shared by the select components.
+//!
+//! The selects follow the WAI-ARIA combobox pattern: the focused control
+//! keeps DOM focus while the highlighted option is announced through
+//! `aria-activedescendant`.
+
+use num_traits::ToPrimitive;
+use web_sys::{KeyboardEvent, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+/// Milliseconds after which a type-ahead buffer is reset.
+const TYPE_AHEAD_TIMEOUT: u64 = 500;
+
+/// A key handled by a select.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum NavKey {
+    Down,
+    Up,
+    Left,
+    Right,
+    Home,
+    End,
+    Enter,
+    Space,
+    Escape,
+    Tab,
+    Char(char),
+}
+
+impl NavKey {
+    /// Converts the `key` of a keyboard event. Returns `None` for keys a
+    /// select does not handle, including those pressed with Ctrl, Alt or
+    /// Meta.
+    pub(crate) fn from_event(e: &KeyboardEvent) -> Option<Self> {
+        if e.ctrl_key() || e.alt_key() || e.meta_key() {
+            return None;
+        }
+        Self::from_key(&e.key())
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "ArrowDown" => Some(Self::Down),
+            "ArrowUp" => Some(Self::Up),
+            "ArrowLeft" => Some(Self::Left),
+            "ArrowRight" => Some(Self::Right),
+            "Home" => Some(Self::Home),
+            "End" => Some(Self::End),
+            "Enter" => Some(Self::Enter),
+            " " => Some(Self::Space),
+            "Escape" => Some(Self::Escape),
+            "Tab" => Some(Self::Tab),
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if !c.is_control() => Some(Self::Char(c)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the browser's default action for the key should be
+    /// suppressed while a list is open.
+    pub(crate) fn prevents_default(self) -> bool {
+        matches!(
+            self,
+            Self::Down | Self::Up | Self::Home | Self::End | Self::Enter | Self::Space
+        )
+    }
+}
+
+/// Moves the active option of a list of `len` options for `key`.
+///
+/// The active option stops at either end of the list.
+pub(crate) fn move_active(active: Option<usize>, len: usize, key: NavKey) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let last = len - 1;
+    match (key, active) {
+        (NavKey::Down, None) | (NavKey::Home, _) => Some(0),
+        (NavKey::Down, Some(index)) => Some((index + 1).min(last)),
+        (NavKey::Up, None) | (NavKey::End, _) => Some(last),
+        (NavKey::Up, Some(index)) => Some(index.min(last).saturating_sub(1)),
+        _ => active.map(|index| index.min(last)),
+    }
+}
+
+/// Finds options by the characters typed in quick succession.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TypeAhead {
+    buffer: String,
+    last: u64,
+}
+
+impl TypeAhead {
+    /// Adds `c` typed at `now` (in milliseconds) to the buffer and returns
+    /// the first option after `active` whose label starts with the buffer.
+    ///
+    /// Typing the same character repeatedly cycles through the options
+    /// starting with it.
+    pub(crate) fn search<S: AsRef<str>>(
+        &mut self,
+        c: char,
+        now: u64,
+        labels: &[S],
+        active: Option<usize>,
+    ) -> Option<usize> {
+        if now.saturating_sub(self.last) > TYPE_AHEAD_TIMEOUT {
+            self.buffer.clear();
+        }
+        self.last = now;
+        self.buffer.extend(c.to_lowercase());
+        if labels.is_empty() {
+            return None;
+        }
+
+        let mut chars = self.buffer.chars();
+        let first = chars.next();
+        let repeated = chars.all(|other| Some(other) == first);
+        let (prefix, start) = if repeated {
+            let prefix = first.map(String::from).unwrap_or_default();
+            (prefix, active.map_or(0, |index| index + 1))
+        } else {
+            (self.buffer.clone(), active.unwrap_or(0))
+        };
+        (0..labels.len())
+            .map(|offset| (start + offset) % labels.len())
+            .find(|&index| labels[index].as_ref().to_lowercase().starts_with(&prefix))
+    }
+}
+
+/// Returns the current time in milliseconds for [`TypeAhead::search`].
+pub(crate) fn now() -> u64 {
+    js_sys::Date::now().to_u64().unwrap_or_default()
+}
+
+/// Returns the element ID of the option at `index` in the list `list_id`.
+pub(crate) fn option_id(list_id: &str, index: usize) -> String {
+    format!("{list_id}-option-{index}")
+}
+
+/// Scrolls the option with the element ID `id` into view if it is hidden
+/// in its scrollable list.
+pub(crate) fn scroll_into_view(id: &str) {
+    if let Some(element) = gloo_utils::document().get_element_by_id(id) {
+        let options = ScrollIntoViewOptions::new();
+        options.set_block(ScrollLogicalPosition::Nearest);
+        element.scroll_into_view_with_scroll_into_view_options(&options);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NavKey, TypeAhead, move_active};
+
+    #[test]
+    fn active_stops_at_both_ends() {
+        assert_eq!(move_active(None, 3, NavKey::Down), Some(0));
+        assert_eq!(move_active(Some(2), 3, NavKey::Down), Some(2));
+        assert_eq!(move_active(Some(0), 3, NavKey::Up), Some(0));
+        assert_eq!(move_active(None, 3, NavKey::Up), Some(2));
+        assert_eq!(move_active(Some(1), 3, NavKey::End), Some(2));
+        assert_eq!(move_active(Some(5), 3, NavKey::Enter), Some(2));
+        assert_eq!(move_active(Some(1), 0, NavKey::Down), None);
+    }
+
+    #[test]
+    fn type_ahead() {
+        let labels = ["Apple", "Banana", "Blueberry", "Cherry"];
+        let mut type_ahead = TypeAhead::default();
+        assert_eq!(type_ahead.search('b', 0, &labels, None), Some(1));
+        assert_eq!(type_ahead.search('l', 100, &labels, Some(1)), Some(2));
+
+        // repeated characters cycle through matching options
+        assert_eq!(type_ahead.search('b', 1000, &labels, Some(2)), Some(1));
+        assert_eq!(type_ahead.search('b', 1100, &labels, Some(1)), Some(2));
+
+        // the buffer is reset after a pause
+        assert_eq!(type_ahead.search('c', 2000, &labels, Some(2)), Some(3));
+        assert_eq!(type_ahead.search('x', 3000, &labels, Some(3)), None);
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(NavKey::from_key("ArrowDown"), Some(NavKey::Down));
+        assert_eq!(NavKey::from_key(" "), Some(NavKey::Space));
+        assert_eq!(NavKey::from_key("가"), Some(NavKey::Char('가')));
+        assert_eq!(NavKey::from_key("Shift"), None);
+    }
+}
@@ -0,0 +1,438 @@
+//! Ranked fuzzy search over the options of the select components.
+//!
+//! A query is split into whitespace-separated tokens, and an option matches
+//! if every token matches its label in any order. A token matches as a
+//! substring, as Korean initial consonants (e.g. `ㅅㅇ` for `서울`), as a
+//! sequence of Korean jamo (so a syllable still being composed matches), or
+//! as a subsequence of characters. Options are ranked by how well the
+//! tokens match.
+
+use std::net::IpAddr;
+use std::ops::Range;
+
+use ipnet::IpNet;
+use yew::{Html, html};
+
+use crate::NetworkGroup;
+
+const HANGUL_BASE: u32 = 0xAC00;
+const HANGUL_LAST: u32 = 0xD7A3;
+const JUNGSEONG_COUNT: u32 = 21;
+const JONGSEONG_COUNT: u32 = 28;
+
+const CHOSEONG: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ',
+    'ㅌ', 'ㅍ', 'ㅎ',
+];
+const JUNGSEONG: [&str; 21] = [
+    "ㅏ", "ㅐ", "ㅑ", "ㅒ", "ㅓ", "ㅔ", "ㅕ", "ㅖ", "ㅗ", "ㅗㅏ", "ㅗㅐ", "ㅗㅣ", "ㅛ", "ㅜ",
+    "ㅜㅓ", "ㅜㅔ", "ㅜㅣ", "ㅠ", "ㅡ", "ㅡㅣ", "ㅣ",
+];
+const JONGSEONG: [&str; 28] = [
+    "", "ㄱ", "ㄲ", "ㄱㅅ", "ㄴ", "ㄴㅈ", "ㄴㅎ", "ㄷ", "ㄹ", "ㄹㄱ", "ㄹㅁ", "ㄹㅂ", "ㄹㅅ",
+    "ㄹㅌ", "ㄹㅍ", "ㄹㅎ", "ㅁ", "ㅂ", "ㅂㅅ", "ㅅ", "ㅆ", "ㅇ", "ㅈ", "ㅊ", "ㅋ", "ㅌ", "ㅍ",
+    "ㅎ",
+];
+// Compound compatibility jamo typed as a single character
+const COMPOUND_JAMO: [(char, &str); 18] = [
+    ('ㄳ', "ㄱㅅ"),
+    ('ㄵ', "ㄴㅈ"),
+    ('ㄶ', "ㄴㅎ"),
+    ('ㄺ', "ㄹㄱ"),
+    ('ㄻ', "ㄹㅁ"),
+    ('ㄼ', "ㄹㅂ"),
+    ('ㄽ', "ㄹㅅ"),
+    ('ㄾ', "ㄹㅌ"),
+    ('ㄿ', "ㄹㅍ"),
+    ('ㅀ', "ㄹㅎ"),
+    ('ㅄ', "ㅂㅅ"),
+    ('ㅘ', "ㅗㅏ"),
+    ('ㅙ', "ㅗㅐ"),
+    ('ㅚ', "ㅗㅣ"),
+    ('ㅝ', "ㅜㅓ"),
+    ('ㅞ', "ㅜㅔ"),
+    ('ㅟ', "ㅜㅣ"),
+    ('ㅢ', "ㅡㅣ"),
+];
+
+const SCORE_EXACT: u32 = 1000;
+const SCORE_PREFIX: u32 = 900;
+const SCORE_WORD: u32 = 800;
+const SCORE_SUBSTRING: u32 = 700;
+const SCORE_CHOSEONG: u32 = 600;
+const SCORE_JAMO: u32 = 500;
+const SCORE_SUBSEQUENCE: u32 = 300;
+const BONUS_AT_START: u32 = 50;
+const BONUS_IN_ORDER: u32 = 10;
+
+/// An option matching a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SearchMatch {
+    /// The index of the option
+    pub(crate) index: usize,
+    pub(crate) score: u32,
+    /// The matched ranges of characters in the label, sorted and disjoint
+    pub(crate) ranges: Vec<Range<usize>>,
+}
+
+/// A label prepared for searching.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    chars: Vec<char>,
+    // the initial consonant of each Hangul syllable, or the character itself
+    choseong: Vec<char>,
+    jamo: Vec<char>,
+    // the index in `chars` of each character in `jamo`
+    jamo_owner: Vec<usize>,
+}
+
+impl Entry {
+    fn new(label: &str) -> Self {
+        let chars = normalize(label);
+        let choseong = chars
+            .iter()
+            .map(|&c| {
+                syllable_index(c).map_or(c, |i| {
+                    CHOSEONG[(i / (JUNGSEONG_COUNT * JONGSEONG_COUNT)) as usize]
+                })
+            })
+            .collect();
+        let mut jamo = Vec::new();
+        let mut jamo_owner = Vec::new();
+        for (owner, &c) in chars.iter().enumerate() {
+            for j in decompose(c) {
+                jamo.push(j);
+                jamo_owner.push(owner);
+            }
+        }
+        Self {
+            chars,
+            choseong,
+            jamo,
+            jamo_owner,
+        }
+    }
+
+    /// Returns the score and the matched range of characters for `token`.
+    #[allow(clippy::single_range_in_vec_init)]
+    fn match_token(&self, token: &[char]) -> Option<(u32, Vec<Range<usize>>)> {
+        if let Some(start) = find(&self.chars, token) {
+            let score = if start == 0 && token.len() == self.chars.len() {
+                SCORE_EXACT
+            } else if start == 0 {
+                SCORE_PREFIX
+            } else if !self.chars[start - 1].is_alphanumeric() {
+                SCORE_WORD
+            } else {
+                SCORE_SUBSTRING
+            };
+            return Some((score, vec![start..start + token.len()]));
+        }
+        if token.iter().all(|&c| CHOSEONG.contains(&c))
+            && let Some(start) = find(&self.choseong, token)
+        {
+            let bonus = if start == 0 { BONUS_AT_START } else { 0 };
+            return Some((SCORE_CHOSEONG + bonus, vec![start..start + token.len()]));
+        }
+        let token_jamo = token.iter().flat_map(|&c| decompose(c)).collect::<Vec<_>>();
+        if (token_jamo.len() != token.len() || token.iter().any(|&c| is_compatibility_jamo(c)))
+            && let Some(start) = find(&self.jamo, &token_jamo)
+        {
+            let first = self.jamo_owner[start];
+            let last = self.jamo_owner[start + token_jamo.len() - 1];
+            let bonus = if first == 0 { BONUS_AT_START } else { 0 };
+            return Some((SCORE_JAMO + bonus, vec![first..last + 1]));
+        }
+        self.match_subsequence(token)
+    }
+
+    fn match_subsequence(&self, token: &[char]) -> Option<(u32, Vec<Range<usize>>)> {
+        if token.len() < 2 {
+            return None;
+        }
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut position = 0;
+        for &c in token {
+            let found = position + self.chars[position..].iter().position(|&l| l == c)?;
+            match ranges.last_mut() {
+                Some(last) if last.end == found => last.end = found + 1,
+                _ => ranges.push(found..found + 1),
+            }
+            position = found + 1;
+        }
+        let first = ranges.first().map_or(0, |r| r.start);
+        let last = ranges.last().map_or(0, |r| r.end);
+        let gaps = u32::try_from(last - first - token.len()).unwrap_or(u32::MAX);
+        Some((SCORE_SUBSEQUENCE.saturating_sub(gaps * 10).max(1), ranges))
+    }
+}
+
+/// Labels of options prepared for searching.
+///
+/// Build it once when the options change, and search it on each keystroke.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SearchIndex {
+    entries: Vec<Entry>,
+}
+
+impl SearchIndex {
+    pub(crate) fn new<I, S>(labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            entries: labels
+                .into_iter()
+                .map(|label| Entry::new(label.as_ref()))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the options matching `query`, best matches first.
+    pub(crate) fn search(&self, query: &str) -> Vec<SearchMatch> {
+        let tokens = query.split_whitespace().map(normalize).collect::<Vec<_>>();
+        let mut matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let (score, ranges) = match_tokens(entry, &tokens)?;
+                Some(SearchMatch {
+                    index,
+                    score,
+                    ranges,
+                })
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| {
+                    self.entries[a.index]
+                        .chars
+                        .len()
+                        .cmp(&self.entries[b.index].chars.len())
+                })
+                .then_with(|| a.index.cmp(&b.index))
+        });
+        matches
+    }
+}
+
+fn match_tokens(entry: &Entry, tokens: &[Vec<char>]) -> Option<(u32, Vec<Range<usize>>)> {
+    let mut score = 0;
+    let mut ranges = Vec::new();
+    let mut in_order = true;
+    let mut previous_start = 0;
+    for token in tokens {
+        let (token_score, token_ranges) = entry.match_token(token)?;
+        let start = token_ranges.first().map_or(0, |r| r.start);
+        in_order &= start >= previous_start;
+        previous_start = start;
+        score += token_score;
+        ranges.extend(token_ranges);
+    }
+    if in_order && tokens.len() > 1 {
+        score += BONUS_IN_ORDER;
+    }
+    Some((score, merge(ranges)))
+}
+
+/// Returns the score of matching `query` against the addresses of a
+/// network group, or `None` if none of them matches.
+///
+/// An address matches if it starts with or contains the query. If the query
+/// is an IP address, a network or range containing it also matches.
+pub(crate) fn match_network(query: &str, networks: &NetworkGroup) -> Option<u32> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    let addresses = networks
+        .hosts
+        .iter()
+        .chain(&networks.networks)
+        .chain(networks.ranges.iter().flat_map(|r| [&r.start, &r.end]));
+    let mut best = addresses
+        .filter_map(|address| {
+            if address.starts_with(query) {
+                Some(SCORE_PREFIX)
+            } else if address.contains(query) {
+                Some(SCORE_SUBSTRING)
+            } else {
+                None
+            }
+        })
+        .max();
+    if let Ok(ip) = query.parse::<IpAddr>() {
+        let contained = networks
+            .networks
+            .iter()
+            .filter_map(|n| n.parse::<IpNet>().ok())
+            .any(|n| n.contains(&ip))
+            || networks.ranges.iter().any(|r| {
+                match (r.start.parse::<IpAddr>(), r.end.parse::<IpAddr>()) {
+                    (Ok(start), Ok(end)) => start <= ip && ip <= end,
+                    _ => false,
+                }
+            });
+        if contained {
+            best = best.max(Some(SCORE_WORD));
+        }
+    }
+    best
+}
+
+/// Returns the ranges of characters in `text` that contain `query`,
+/// ignoring case.
+pub(crate) fn substring_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    let text = normalize(text);
+    let query = normalize(query.trim());
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut ranges = Vec::new();
+    let mut position = 0;
+    while let Some(start) = find(&text[position..], &query) {
+        let start = position + start;
+        ranges.push(start..start + query.len());
+        position = start + query.len();
+    }
+    ranges
+}
+
+/// Renders `text` with the characters in `ranges` highlighted. Characters
+/// at or after `limit` are not highlighted, e.g. an ellipsis appended to a
+/// shortened label.
+pub(crate) fn highlight(text: &str, ranges: &[Range<usize>], limit: usize) -> Html {
+    let chars = text.chars().collect::<Vec<_>>();
+    let limit = limit.min(chars.len());
+    let mut parts = Vec::new();
+    let mut position = 0;
+    for range in ranges {
+        let start = range.start.min(limit).max(position);
+        let end = range.end.min(limit);
+        if start >= end {
+            continue;
+        }
+        parts.push((chars[position..start].iter().collect::<String>(), false));
+        parts.push((chars[start..end].iter().collect::<String>(), true));
+        position = end;
+    }
+    parts.push((chars[position..].iter().collect::<String>(), false));
+    html! {
+        for parts.into_iter().filter(|(part, _)| !part.is_empty()).map(|(part, matched)| {
+            if matched {
+                html! { <mark class="select-search-highlight">{ part }</mark> }
+            } else {
+                html! { part }
+            }
+        })
+    }
+}
+
+fn normalize(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn syllable_index(c: char) -> Option<u32> {
+    let code = u32::from(c);
+    (HANGUL_BASE..=HANGUL_LAST)
+        .contains(&code)
+        .then(|| code - HANGUL_BASE)
+}
+
+fn is_compatibility_jamo(c: char) -> bool {
+    ('ㄱ'..='ㅣ').contains(&c)
+}
+
+/// Decomposes a Hangul syllable or compound jamo into compatibility jamo.
+fn decompose(c: char) -> Vec<char> {
+    if let Some(index) = syllable_index(c) {
+        let lead = CHOSEONG[(index / (JUNGSEONG_COUNT * JONGSEONG_COUNT)) as usize];
+        let vowel = JUNGSEONG[((index / JONGSEONG_COUNT) % JUNGSEONG_COUNT) as usize];
+        let tail = JONGSEONG[(index % JONGSEONG_COUNT) as usize];
+        std::iter::once(lead)
+            .chain(vowel.chars())
+            .chain(tail.chars())
+            .collect()
+    } else if let Some((_, jamo)) = COMPOUND_JAMO.iter().find(|(compound, _)| *compound == c) {
+        jamo.chars().collect()
+    } else {
+        vec![c]
+    }
+}
+
+fn find(haystack: &[char], needle: &[char]) -> Option<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn merge(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchIndex, match_network};
+    use crate::{IpRange, NetworkGroup};
+
+    fn indices(index: &SearchIndex, query: &str) -> Vec<usize> {
+        index.search(query).into_iter().map(|m| m.index).collect()
+    }
+
+    #[test]
+    fn ranking_and_token_order() {
+        let index = SearchIndex::new(["Seoul office", "Office Seoul", "Busan", "office"]);
+        assert_eq!(indices(&index, "office"), vec![3, 1, 0]);
+        assert_eq!(indices(&index, "seoul office"), vec![0, 1]);
+        assert_eq!(indices(&index, "bsn"), vec![2]);
+
+        let matched = index.search("off seo");
+        assert_eq!(matched[0].ranges, vec![0..3, 7..10]);
+    }
+
+    #[test]
+    fn korean() {
+        let index = SearchIndex::new(["서울 본사", "부산 지사", "닭갈비"]);
+        assert_eq!(indices(&index, "ㅂㅅ"), vec![1, 0]);
+        assert_eq!(indices(&index, "ㅈㅅ"), vec![1]);
+        // a syllable being composed
+        assert_eq!(indices(&index, "서우"), vec![0]);
+        assert_eq!(indices(&index, "달"), vec![2]);
+        assert_eq!(index.search("본사")[0].ranges, vec![3..5]);
+    }
+
+    #[test]
+    fn network_prefix() {
+        let networks = NetworkGroup {
+            hosts: vec!["10.0.0.1".to_string()],
+            networks: vec!["192.168.0.0/16".to_string()],
+            ranges: vec![IpRange {
+                start: "172.16.0.1".to_string(),
+                end: "172.16.0.100".to_string(),
+            }],
+        };
+        assert!(match_network("10.0", &networks).is_some());
+        assert!(match_network("192.168.3.4", &networks).is_some());
+        assert!(match_network("172.16.0.50", &networks).is_some());
+        assert!(match_network("172.16.1.50", &networks).is_none());
+    }
+}
@@ -1,19 +1,26 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use std::rc::Rc;
 use std::{cell::RefCell, marker::PhantomData};
 
+use gloo_timers::callback::Timeout;
 use json_gettext::get_text;
 use num_traits::ToPrimitive;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlElement, HtmlInputElement, KeyboardEvent};
 use yew::virtual_dom::AttrValue;
-use yew::{Component, Context, Html, Properties, classes, events::InputEvent, html};
-
-use crate::click_outside::toggle_visibility;
-use crate::{
-    CheckStatus, Checkbox, Item, Texts, Theme, language::Language, shorten_text, text, text_width,
+use yew::{
+    Callback, Component, Context, Html, MouseEvent, NodeRef, Properties, classes,
+    events::InputEvent, html,
 };
 
+use super::hierarchy::{Hierarchy, Row};
+use super::navigation::{NavKey, TypeAhead, move_active, now, option_id, scroll_into_view};
+use super::search::{SearchIndex, highlight};
+use crate::click_outside::{is_visible, toggle_visibility};
+use crate::language::{Language, translate};
+use crate::{CheckStatus, Checkbox, Item, Texts, Theme, shorten_text, text, text_width};
+
 #[cfg(feature = "pumpkin")]
 const DEFAULT_FONT: &str = "";
 #[cfg(not(feature = "pumpkin"))]
@@ -25,10 +32,69 @@ pub enum Kind {
     Multi,
 }
 
+/// A search request sent to the parent in remote-search mode.
+///
+/// Call [`RemoteQuery::respond`] with the matching items, or with an error
+/// message (a translation key or raw text) if the search failed. Responses to
+/// outdated queries are ignored.
+#[derive(Clone, PartialEq)]
+pub struct RemoteQuery {
+    pub text: String,
+    serial: u32,
+    responder: Callback<(u32, Result<Vec<Item>, String>)>,
+}
+
+impl RemoteQuery {
+    pub fn respond(&self, result: Result<Vec<Item>, String>) {
+        self.responder.emit((self.serial, result));
+    }
+}
+
+/// The state of an option in a tree, computed once for all the options in
+/// the list
+#[derive(Clone, Copy)]
+struct OptionState {
+    depth: usize,
+    has_children: bool,
+    check_status: CheckStatus,
+    active: bool,
+}
+
+#[derive(Clone, PartialEq)]
+enum RemoteStatus {
+    Idle,
+    Loading,
+    Failed(String),
+}
+
 #[derive(Clone)]
 pub struct Model<T> {
+    // the options, which are `list` of `Props` unless in remote-search mode
+    list: Rc<RefCell<Vec<Item>>>,
+    // the results of the latest remote search, if any
+    remote_results: Option<Vec<Item>>,
     search_result: Option<Vec<usize>>,
     search_text: String,
+    search_index: SearchIndex,
+    hierarchy: Hierarchy,
+    // identifiers of the options whose children are shown
+    expanded_nodes: HashSet<String>,
+    // matched characters of each option in `search_result`
+    highlights: HashMap<usize, Vec<Range<usize>>>,
+    // position of the highlighted option among the visible options
+    active: Option<usize>,
+    // whether `active` was moved with the keyboard after the last search
+    navigated: bool,
+    expanded: bool,
+    type_ahead: TypeAhead,
+    scroll_to_active: bool,
+    pending_focus: Option<NodeRef>,
+    top: NodeRef,
+    search: NodeRef,
+    // the serial number of the latest remote search
+    remote_serial: u32,
+    remote_status: RemoteStatus,
+    debounce: Option<Rc<Timeout>>,
     phantom: PhantomData<T>,
 }
 
@@ -38,6 +104,11 @@ pub enum Message {
     InputSearch(String),
     ClickAll,
     ClickItem(String),
+    Toggle(String),
+    Key(String),
+    SearchRemote(u32),
+    RemoteResult(u32, Result<Vec<Item>, String>),
+    Create,
     InputError,
 }
 #[cfg(feature = "pumpkin")]
@@ -45,6 +116,9 @@ const ELEM_HEIGHT: u32 = 48;
 #[cfg(not(feature = "pumpkin"))]
 const ELEM_HEIGHT: u32 = 32;
 const DEFAULT_MAX_WIDTH: u32 = 500;
+const DEFAULT_SEARCH_DEBOUNCE: u32 = 300;
+// pixels by which each level of a tree of options is indented
+const TREE_INDENT: usize = 16;
 pub(super) const DEFAULT_SIZED_VALUE: bool = true;
 
 #[allow(clippy::struct_excessive_bools)]
@@ -78,6 +152,20 @@ where
     pub sized_value: bool,
     #[prop_or(false)]
     pub is_required: bool,
+    /// With `on_remote_search`, options are searched by the parent instead of
+    /// being filtered locally. While a search is shown, its results take the
+    /// place of `list`, which is left as is. Selected options from earlier
+    /// results are kept so that they can still be displayed. `selected`
+    /// should not be `None` in this mode, since "all" options are never known.
+    #[prop_or(None)]
+    pub on_remote_search: Option<Callback<RemoteQuery>>,
+    /// Milliseconds to wait after the last keystroke before a remote search
+    #[prop_or(DEFAULT_SEARCH_DEBOUNCE)]
+    pub search_debounce: u32,
+    /// Called with the search text when the user chooses to create a new
+    /// option because none matches it
+    #[prop_or(None)]
+    pub on_create: Option<Callback<String>>,
 
     #[prop_or(None)]
     pub parent_message: Option<T::Message>,
@@ -93,113 +181,256 @@ where
     type Message = Message;
     type Properties = Props<T>;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let list = if ctx.props().on_remote_search.is_some() {
+            Rc::new(RefCell::new(
+                ctx.props()
+                    .list
+                    .try_borrow()
+                    .map(|list| list.clone())
+                    .unwrap_or_default(),
+            ))
+        } else {
+            Rc::clone(&ctx.props().list)
+        };
         Self {
+            search_index: Self::build_search_index(ctx, &list),
+            hierarchy: Self::build_hierarchy(&list),
+            list,
+            remote_results: None,
             search_result: None,
             search_text: String::new(),
+            expanded_nodes: HashSet::new(),
+            highlights: HashMap::new(),
+            active: None,
+            navigated: false,
+            expanded: false,
+            type_ahead: TypeAhead::default(),
+            scroll_to_active: false,
+            pending_focus: None,
+            top: NodeRef::default(),
+            search: NodeRef::default(),
+            remote_serial: 0,
+            remote_status: RemoteStatus::Idle,
+            debounce: None,
             phantom: PhantomData,
         }
     }
 
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        if let Some(element) = self
+            .pending_focus
+            .take()
+            .and_then(|node| node.cast::<HtmlElement>())
+        {
+            let _ = element.focus();
+        }
+        if std::mem::take(&mut self.scroll_to_active)
+            && let Some(index) = self.active_index()
+        {
+            scroll_into_view(&option_id(&ctx.props().id, index));
+        }
+    }
+
     fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        let remote = ctx.props().on_remote_search.is_some();
+        if remote {
+            self.sync_remote_list(ctx);
+        } else {
+            self.list = Rc::clone(&ctx.props().list);
+        }
         if ctx.props().kind == Kind::Multi
             && let (Ok(mut sel), Ok(list)) = (
                 ctx.props().selected.try_borrow_mut(),
-                ctx.props().list.try_borrow(),
+                self.list.try_borrow(),
             )
         {
             // if threre is any deleted item that belongs to the list of the selected
             if let Some(selected) = sel.as_mut() {
                 let list_tmp = list.iter().map(Item::id).collect::<HashSet<&String>>();
                 selected.retain(|k| list_tmp.contains(k));
-                if !list.is_empty() && selected.len() == list.len() {
+                if !remote && !list.is_empty() && selected.len() == list.len() {
                     *sel = None;
                 }
             }
         }
+        if remote {
+            return true;
+        }
+        self.search_index = Self::build_search_index(ctx, &self.list);
+        self.hierarchy = Self::build_hierarchy(&self.list);
+        self.search();
 
         true
     }
 
     #[allow(clippy::too_many_lines)]
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
-        let txt = ctx.props().txt.txt.clone();
         let send_msg = match msg {
             Message::Click => {
                 let _ = toggle_visibility(&ctx.props().id);
+                self.expanded = is_visible(&ctx.props().id);
+                if self.expanded {
+                    self.active = self.selected_position(ctx);
+                    self.scroll_to_active = true;
+                }
                 false
             }
-            Message::InputSearch(input) => {
-                self.search_text.clone_from(&input);
-                if input.is_empty() {
+            Message::Key(key) => {
+                return NavKey::from_key(&key).is_some_and(|key| self.handle_key(ctx, key));
+            }
+            Message::InputSearch(input) if ctx.props().on_remote_search.is_some() => {
+                self.search_text = input;
+                self.navigated = false;
+                self.remote_serial = self.remote_serial.wrapping_add(1);
+                if self.remote_results.take().is_some() {
+                    self.sync_remote_list(ctx);
+                }
+                self.highlights.clear();
+                if self.search_text.trim().is_empty() {
+                    self.debounce = None;
+                    self.remote_status = RemoteStatus::Idle;
                     self.search_result = None;
-                } else if let Ok(list) = ctx.props().list.try_borrow() {
-                    let input = input.to_lowercase();
-                    self.search_result = Some(
-                        list.iter()
-                            .enumerate()
-                            .filter_map(|(i, item)| {
-                                if item
-                                    .value_txt(&txt, ctx.props().language)
-                                    .to_lowercase()
-                                    .contains(&input)
-                                {
-                                    Some(i)
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect(),
-                    );
+                } else {
+                    let serial = self.remote_serial;
+                    let link = ctx.link().clone();
+                    self.debounce = Some(Rc::new(Timeout::new(
+                        ctx.props().search_debounce,
+                        move || link.send_message(Message::SearchRemote(serial)),
+                    )));
+                    self.remote_status = RemoteStatus::Loading;
+                    self.search_result = Some(Vec::new());
                 }
+                self.active = None;
+                false
+            }
+            Message::SearchRemote(serial) => {
+                if serial != self.remote_serial {
+                    return false;
+                }
+                self.debounce = None;
+                if let Some(on_remote_search) = ctx.props().on_remote_search.as_ref() {
+                    on_remote_search.emit(RemoteQuery {
+                        text: self.search_text.trim().to_string(),
+                        serial,
+                        responder: ctx
+                            .link()
+                            .callback(|(serial, result)| Message::RemoteResult(serial, result)),
+                    });
+                }
+                return false;
+            }
+            Message::RemoteResult(serial, result) => {
+                if serial != self.remote_serial {
+                    return false;
+                }
+                match result {
+                    Ok(items) => {
+                        self.remote_results = Some(items);
+                        self.sync_remote_list(ctx);
+                        self.remote_status = RemoteStatus::Idle;
+                    }
+                    Err(error) => {
+                        if self.remote_results.take().is_some() {
+                            self.sync_remote_list(ctx);
+                        }
+                        self.remote_status = RemoteStatus::Failed(error);
+                        self.search_result = Some(Vec::new());
+                    }
+                }
+                self.active = (!self.visible_indices().is_empty()).then_some(0);
+                false
+            }
+            Message::Create => {
+                let text = self.search_text.trim().to_string();
+                if text.is_empty() {
+                    return false;
+                }
+                if let Some(on_create) = ctx.props().on_create.as_ref() {
+                    on_create.emit(text);
+                }
+                self.search_text.clear();
+                if self.remote_results.take().is_some() {
+                    self.sync_remote_list(ctx);
+                }
+                self.search_result = None;
+                self.highlights.clear();
+                self.remote_status = RemoteStatus::Idle;
+                self.active = None;
+                if ctx.props().kind == Kind::Single {
+                    ctx.link().send_message(Message::Click);
+                }
+                false
+            }
+            Message::InputSearch(input) => {
+                self.search_text = input;
+                self.navigated = false;
+                // `list` can be altered in place without changing `Properties`.
+                if self
+                    .list
+                    .try_borrow()
+                    .is_ok_and(|list| list.len() != self.search_index.len())
+                {
+                    self.search_index = Self::build_search_index(ctx, &self.list);
+                    self.hierarchy = Self::build_hierarchy(&self.list);
+                }
+                self.search();
+                self.active = (!self.visible_indices().is_empty()).then_some(0);
                 false
             }
             Message::ClickItem(key) => {
                 let (Ok(mut sel), Ok(list)) = (
                     ctx.props().selected.try_borrow_mut(),
-                    ctx.props().list.try_borrow(),
+                    self.list.try_borrow(),
                 ) else {
                     return false;
                 };
-                if let Some(selected) = sel.as_mut() {
-                    match ctx.props().kind {
-                        Kind::Multi => {
-                            if selected.contains(&key) {
-                                selected.remove(&key);
-                            } else {
-                                selected.insert(key);
-                            }
+                match ctx.props().kind {
+                    Kind::Multi => {
+                        let selected = sel.get_or_insert_with(|| {
+                            list.iter()
+                                .map(|i| i.id().clone())
+                                .collect::<HashSet<String>>()
+                        });
+                        // an option with children selects or deselects all of them
+                        if let Some(index) = list.iter().position(|item| item.id() == &key) {
+                            self.hierarchy.toggle(&list, selected, index);
                         }
-                        Kind::Single => {
+                    }
+                    Kind::Single => {
+                        if let Some(selected) = sel.as_mut() {
                             if !selected.is_empty() {
                                 selected.clear();
                             }
                             selected.insert(key);
                             ctx.link().send_message(Message::Click);
-                        }
-                    }
-                } else {
-                    match ctx.props().kind {
-                        Kind::Multi => {
-                            let mut s = list
-                                .iter()
-                                .map(|i| i.id().clone())
-                                .collect::<HashSet<String>>();
-                            s.remove(&key);
-                            *sel = Some(s);
-                        }
-                        Kind::Single => {
+                        } else {
                             *sel = None;
                         }
                     }
                 }
                 true
             }
+            Message::Toggle(key) => {
+                if !self.expanded_nodes.remove(&key) {
+                    self.expanded_nodes.insert(key.clone());
+                }
+                // keep the toggled option highlighted
+                let index = self
+                    .list
+                    .try_borrow()
+                    .ok()
+                    .and_then(|list| list.iter().position(|item| item.id() == &key));
+                if let Some(index) = index {
+                    self.active = self.visible_indices().iter().position(|&i| i == index);
+                }
+                return true;
+            }
             Message::ClickAll => {
                 if ctx.props().kind == Kind::Multi {
                     let (Ok(mut sel), Ok(list)) = (
                         ctx.props().selected.try_borrow_mut(),
-                        ctx.props().list.try_borrow(),
+                        self.list.try_borrow(),
                     ) else {
                         return false;
                     };
@@ -235,9 +466,10 @@ where
                                         selected.insert(key.clone());
                                     }
                                 }
-                                if selected.len() == list.len() {
-                                    *sel = None;
-                                }
+                            }
+                            self.hierarchy.sync_ancestors(&list, selected);
+                            if selected.len() == list.len() {
+                                *sel = None;
                             }
                         }
                     } else if !list.is_empty() {
@@ -285,11 +517,10 @@ where
         let txt = ctx.props().txt.txt.clone();
         let theme = ctx.props().theme;
         let mut class_input = "searchable-select-input";
-        let value = if let (Ok(selected), Ok(list)) = (
-            ctx.props().selected.try_borrow(),
-            ctx.props().list.try_borrow(),
-        ) {
-            if list.is_empty() {
+        let value = if let (Ok(selected), Ok(list)) =
+            (ctx.props().selected.try_borrow(), self.list.try_borrow())
+        {
+            if list.is_empty() && !self.has_popup(ctx) {
                 class_input = if cfg!(feature = "pumpkin") {
                     "searchable-select-input-empty-disabled"
                 } else {
@@ -307,7 +538,9 @@ where
                 } else {
                     match ctx.props().kind {
                         Kind::Multi => {
-                            if selected.len() == list.len() {
+                            if selected.len() == list.len()
+                                && ctx.props().on_remote_search.is_none()
+                            {
                                 text!(txt, ctx.props().language, "All").to_string()
                             } else {
                                 format!(
@@ -320,7 +553,7 @@ where
                         Kind::Single => {
                             let key = selected.iter().next().cloned();
 
-                            if let (Some(key), Ok(list)) = (key, ctx.props().list.try_borrow()) {
+                            if let (Some(key), Ok(list)) = (key, self.list.try_borrow()) {
                                 let mut value = String::new();
                                 for l in list.iter() {
                                     if l.id() == &key {
@@ -344,7 +577,15 @@ where
         html! {
             <div class="searchable-select">
                 <div onclick={onclick} class="searchable-select-top">
-                    <input type="text" class={classes!("searchable-select-top-input", class_input)} readonly={true} value={value.clone()} style={style} />
+                    <input type="text" class={classes!("searchable-select-top-input", class_input)} readonly={true} value={value.clone()} style={style}
+                        ref={self.top.clone()}
+                        role="combobox"
+                        aria-haspopup="listbox"
+                        aria-expanded={self.expanded.to_string()}
+                        aria-controls={format!("{}-listbox", ctx.props().id)}
+                        aria-activedescendant={self.active_descendant(ctx)}
+                        onkeydown={Self::onkeydown(ctx, false, true, true)}
+                    />
                 </div>
                 { self.view_searchable_list(ctx, &value, theme) }
             </div>
@@ -357,9 +598,323 @@ where
     T: Clone + Component + PartialEq,
     <T as Component>::Message: Clone + PartialEq,
 {
-    fn caculate_width(ctx: &Context<Self>) -> u32 {
+    /// With `in_search`, keys editing the search text are left to the input.
+    /// Left and Right still expand and collapse options if `tree_keys` is
+    /// `true`, and Space toggles the active option if `space_toggles` is
+    /// `true`.
+    fn onkeydown(
+        ctx: &Context<Self>,
+        in_search: bool,
+        tree_keys: bool,
+        space_toggles: bool,
+    ) -> Callback<KeyboardEvent> {
+        ctx.link().batch_callback(move |e: KeyboardEvent| {
+            let key = NavKey::from_event(&e)?;
+            let edits = match key {
+                NavKey::Char(_) | NavKey::Home | NavKey::End => true,
+                NavKey::Space => !space_toggles,
+                NavKey::Left | NavKey::Right => !tree_keys,
+                _ => false,
+            };
+            if in_search && edits {
+                return None;
+            }
+            if key.prevents_default() {
+                e.prevent_default();
+            }
+            Some(Message::Key(e.key()))
+        })
+    }
+
+    /// Returns `true` if Space in the search box toggles the active option
+    /// rather than typing a space. This is the case in `Kind::Multi` unless
+    /// the user is typing a search.
+    fn space_toggles(&self, ctx: &Context<Self>) -> bool {
+        ctx.props().kind == Kind::Multi
+            && self.active.is_some()
+            && (self.search_text.is_empty() || self.navigated)
+    }
+
+    fn active_descendant(&self, ctx: &Context<Self>) -> Option<String> {
+        if !self.expanded {
+            None
+        } else if self.active == Some(self.visible_indices().len()) && self.can_create(ctx) {
+            Some(Self::create_option_id(ctx))
+        } else {
+            self.active_index()
+                .map(|index| option_id(&ctx.props().id, index))
+        }
+    }
+
+    fn create_option_id(ctx: &Context<Self>) -> String {
+        format!("{}-option-create", ctx.props().id)
+    }
+
+    /// Returns `true` if the list can be opened even though `list` is empty.
+    fn has_popup(&self, ctx: &Context<Self>) -> bool {
+        ctx.props().on_remote_search.is_some()
+            || ctx.props().on_create.is_some()
+            || self.list.try_borrow().is_ok_and(|list| !list.is_empty())
+    }
+
+    /// Returns `true` if the "create" row is shown, i.e. the search text
+    /// does not exactly match any visible option.
+    fn can_create(&self, ctx: &Context<Self>) -> bool {
+        let query = self.search_text.trim().to_lowercase();
+        if ctx.props().on_create.is_none()
+            || query.is_empty()
+            || self.remote_status == RemoteStatus::Loading
+        {
+            return false;
+        }
+        let txt = &ctx.props().txt.txt;
+        let visible = self.visible_indices();
+        self.list.try_borrow().is_ok_and(|list| {
+            !visible
+                .iter()
+                .filter_map(|&index| list.get(index))
+                .any(|item| item.value_txt(txt, ctx.props().language).to_lowercase() == query)
+        })
+    }
+
+    /// Rebuilds the options in remote-search mode from the results of the
+    /// latest search, followed by `list` of `Props` and the selected options
+    /// of earlier results. Only the results are shown while searching.
+    fn sync_remote_list(&mut self, ctx: &Context<Self>) {
+        let result_len = self.remote_results.as_ref().map(Vec::len);
+        if let (Ok(mut list), Ok(parent_list), Ok(selected)) = (
+            self.list.try_borrow_mut(),
+            ctx.props().list.try_borrow(),
+            ctx.props().selected.try_borrow(),
+        ) {
+            let earlier = list.drain(..).filter(|item| {
+                selected
+                    .as_ref()
+                    .is_some_and(|selected| selected.contains(item.id()))
+            });
+            let mut ids = HashSet::new();
+            *list = self
+                .remote_results
+                .iter()
+                .flatten()
+                .cloned()
+                .chain(parent_list.iter().cloned())
+                .chain(earlier.collect::<Vec<_>>())
+                .filter(|item| ids.insert(item.id().clone()))
+                .collect();
+        }
+        self.search_index = Self::build_search_index(ctx, &self.list);
+        self.hierarchy = Self::build_hierarchy(&self.list);
+        self.highlights.clear();
+        if let Some(result_len) = result_len {
+            self.highlights = self
+                .search_index
+                .search(&self.search_text)
+                .into_iter()
+                .filter(|m| m.index < result_len)
+                .map(|m| (m.index, m.ranges))
+                .collect();
+            self.search_result = Some((0..result_len).collect());
+        }
+    }
+
+    fn build_search_index(ctx: &Context<Self>, list: &RefCell<Vec<Item>>) -> SearchIndex {
+        let txt = &ctx.props().txt.txt;
+        list.try_borrow()
+            .map(|list| {
+                SearchIndex::new(
+                    list.iter()
+                        .map(|item| item.value_txt(txt, ctx.props().language)),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn build_hierarchy(list: &RefCell<Vec<Item>>) -> Hierarchy {
+        list.try_borrow()
+            .map(|list| Hierarchy::new(&list))
+            .unwrap_or_default()
+    }
+
+    /// Updates `search_result` for `search_text`, best matches first.
+    fn search(&mut self) {
+        self.highlights.clear();
+        if self.search_text.trim().is_empty() {
+            self.search_result = None;
+            return;
+        }
+        let matches = self.search_index.search(&self.search_text);
+        self.search_result = Some(matches.iter().map(|m| m.index).collect());
+        self.highlights = matches.into_iter().map(|m| (m.index, m.ranges)).collect();
+    }
+
+    /// Returns the indices in `list` of the options currently shown.
+    fn visible_indices(&self) -> Vec<usize> {
+        self.search_result.clone().unwrap_or_else(|| {
+            self.rows()
+                .into_iter()
+                .filter_map(|row| match row {
+                    Row::Option { index, .. } => Some(index),
+                    Row::Header(_) => None,
+                })
+                .collect()
+        })
+    }
+
+    /// Returns the rows shown when the list is not being searched.
+    fn rows(&self) -> Vec<Row> {
+        self.list
+            .try_borrow()
+            .map(|list| self.hierarchy.rows(&list, &self.expanded_nodes))
+            .unwrap_or_default()
+    }
+
+    /// Returns the index in `list` of the highlighted option.
+    fn active_index(&self) -> Option<usize> {
+        self.active
+            .and_then(|active| self.visible_indices().get(active).copied())
+    }
+
+    /// Returns the position of the first selected option among the visible
+    /// options, or the first option if none of them is selected.
+    fn selected_position(&self, ctx: &Context<Self>) -> Option<usize> {
+        let visible = self.visible_indices();
+        let first = (!visible.is_empty()).then_some(0);
+        let (Ok(list), Ok(selected)) = (self.list.try_borrow(), ctx.props().selected.try_borrow())
+        else {
+            return first;
+        };
+        selected.as_ref().map_or(first, |selected| {
+            visible
+                .iter()
+                .position(|&index| {
+                    list.get(index)
+                        .is_some_and(|item| selected.contains(item.id()))
+                })
+                .or(first)
+        })
+    }
+
+    fn type_ahead(&mut self, ctx: &Context<Self>, c: char, visible: &[usize]) {
+        let Ok(list) = self.list.try_borrow() else {
+            return;
+        };
+        let txt = &ctx.props().txt.txt;
+        let labels = visible
+            .iter()
+            .filter_map(|&index| list.get(index))
+            .map(|item| item.value_txt(txt, ctx.props().language))
+            .collect::<Vec<_>>();
+        if let Some(position) = self.type_ahead.search(c, now(), &labels, self.active) {
+            self.active = Some(position);
+            self.scroll_to_active = true;
+        }
+    }
+
+    fn handle_key(&mut self, ctx: &Context<Self>, key: NavKey) -> bool {
+        let id = ctx.props().id.clone();
+        self.expanded = is_visible(&id);
+        let visible = self.visible_indices();
+        if !self.expanded {
+            let opens = matches!(
+                key,
+                NavKey::Down | NavKey::Up | NavKey::Enter | NavKey::Space | NavKey::Char(_)
+            );
+            if !opens || !self.has_popup(ctx) {
+                return false;
+            }
+            let _ = toggle_visibility(&id);
+            self.expanded = true;
+            self.active = self.selected_position(ctx);
+            if let NavKey::Char(c) = key {
+                self.type_ahead(ctx, c, &visible);
+            }
+            self.pending_focus = Some(self.search.clone());
+            self.scroll_to_active = true;
+            return true;
+        }
+        match key {
+            NavKey::Down | NavKey::Up | NavKey::Home | NavKey::End => {
+                let len = visible.len() + usize::from(self.can_create(ctx));
+                self.active = move_active(self.active, len, key);
+                self.navigated = true;
+                self.scroll_to_active = true;
+            }
+            NavKey::Enter | NavKey::Space => {
+                if self.active == Some(visible.len()) && self.can_create(ctx) {
+                    return self.update(ctx, Message::Create);
+                }
+                let item_id = self.active_index().and_then(|index| {
+                    self.list
+                        .try_borrow()
+                        .ok()
+                        .and_then(|list| list.get(index).map(|item| item.id().clone()))
+                });
+                let Some(item_id) = item_id else {
+                    return false;
+                };
+                if ctx.props().kind == Kind::Single {
+                    self.pending_focus = Some(self.top.clone());
+                }
+                return self.update(ctx, Message::ClickItem(item_id));
+            }
+            NavKey::Escape | NavKey::Tab => {
+                let _ = toggle_visibility(&id);
+                self.expanded = false;
+                if key == NavKey::Escape {
+                    self.pending_focus = Some(self.top.clone());
+                }
+            }
+            NavKey::Left | NavKey::Right => return self.handle_tree_key(key, &visible),
+            NavKey::Char(c) => self.type_ahead(ctx, c, &visible),
+        }
+        true
+    }
+
+    /// Expands the highlighted option with Right, or collapses it or moves
+    /// to its parent with Left.
+    fn handle_tree_key(&mut self, key: NavKey, visible: &[usize]) -> bool {
+        let Some(index) = self.active_index() else {
+            return false;
+        };
+        if self.search_result.is_some() {
+            return false;
+        }
+        let Some(id) = self
+            .list
+            .try_borrow()
+            .ok()
+            .and_then(|list| list.get(index).map(|item| item.id().clone()))
+        else {
+            return false;
+        };
+        let is_expanded = self.expanded_nodes.contains(&id);
+        match key {
+            NavKey::Right if self.hierarchy.has_children(index) => {
+                if is_expanded {
+                    self.active = self.active.map(|active| active + 1);
+                } else {
+                    self.expanded_nodes.insert(id);
+                }
+            }
+            NavKey::Left if is_expanded => {
+                self.expanded_nodes.remove(&id);
+            }
+            NavKey::Left => {
+                let Some(parent) = self.hierarchy.parent(index) else {
+                    return false;
+                };
+                self.active = visible.iter().position(|&i| i == parent);
+            }
+            _ => return false,
+        }
+        self.scroll_to_active = true;
+        true
+    }
+
+    fn caculate_width(&self, ctx: &Context<Self>) -> u32 {
         let txt = ctx.props().txt.txt.clone();
-        let max_size = ctx.props().list.try_borrow().ok().and_then(|list| {
+        let max_size = self.list.try_borrow().ok().and_then(|list| {
             list.iter()
                 .map(|item| {
                     text_width(
@@ -384,14 +939,9 @@ where
 
     #[allow(clippy::too_many_lines)]
     fn view_searchable_list(&self, ctx: &Context<Self>, value: &str, theme: Option<Theme>) -> Html {
-        let width = Self::caculate_width(ctx);
-        let list_len = ctx
-            .props()
-            .list
-            .try_borrow()
-            .map_or(0, |list| list.len())
-            .to_u32()
-            .expect("> u32::MAX never happens");
+        let width = self.caculate_width(ctx);
+        let rows = self.rows();
+        let list_len = rows.len().to_u32().expect("> u32::MAX never happens");
         let extra_height = if ctx.props().kind == Kind::Single {
             if cfg!(feature = "pumpkin") { 67 } else { 42 }
         } else if cfg!(feature = "pumpkin") {
@@ -483,10 +1033,9 @@ where
 
         let txt = ctx.props().txt.txt.clone();
         let search_notice = text!(txt, ctx.props().language, "Search").to_string();
-        let check_status = if let (Ok(selected), Ok(list)) = (
-            ctx.props().selected.try_borrow(),
-            ctx.props().list.try_borrow(),
-        ) {
+        let check_status = if let (Ok(selected), Ok(list)) =
+            (ctx.props().selected.try_borrow(), self.list.try_borrow())
+        {
             self.search_result.as_ref().map_or_else(
                 || {
                     selected.as_ref().map_or(CheckStatus::Checked, |selected| {
@@ -534,12 +1083,32 @@ where
             CheckStatus::Unchecked
         };
 
-        let Ok(list) = ctx.props().list.try_borrow() else {
+        let active = self.active_index();
+        let column_count = 1
+            + usize::from(ctx.props().kind == Kind::Multi)
+            + usize::from(self.hierarchy.is_tree());
+        let listbox_id = format!("{}-listbox", ctx.props().id);
+        let option_class =
+            |index: usize| (active == Some(index)).then_some("searchable-select-list-item-active");
+        let Ok(list) = self.list.try_borrow() else {
             return html! {};
         };
-        if list.is_empty() {
+        if list.is_empty() && !self.has_popup(ctx) {
             return html! {};
         }
+        let check_statuses = ctx
+            .props()
+            .selected
+            .try_borrow()
+            .map(|selected| self.hierarchy.check_statuses(&list, selected.as_ref()))
+            .unwrap_or_default();
+        let check_status_of = |index: usize| {
+            check_statuses
+                .get(index)
+                .copied()
+                .unwrap_or(CheckStatus::Unchecked)
+        };
+        let remote = ctx.props().on_remote_search.is_some();
         html! {
             <div id={ctx.props().id.clone()} class="searchable-select-list-down" style={style}>
                 <div style ={style_inner}>
@@ -549,11 +1118,21 @@ where
                             placeholder={search_notice}
                             style={style_inner_width_search}
                             oninput={oninput_search}
+                            ref={self.search.clone()}
+                            aria-label={text!(txt, ctx.props().language, "Search").to_string()}
+                            aria-controls={listbox_id.clone()}
+                            aria-activedescendant={self.active_descendant(ctx)}
+                            onkeydown={Self::onkeydown(
+                                ctx,
+                                true,
+                                self.search_text.is_empty() && self.hierarchy.is_tree(),
+                                self.space_toggles(ctx),
+                            )}
                         />
                     </div>
                     <div class="scrollable-table-wrapper" style={style_scrollable_table}>
                     {
-                        if ctx.props().kind == Kind::Multi {
+                        if ctx.props().kind == Kind::Multi && !remote {
                             html! {
                                 <div class="searchable-select-list-search-all" style={style_inner_width.clone()}>
                                     <table>
@@ -581,34 +1160,44 @@ where
                         }
                     }
 
-                    <table style={style_inner_width}>
+                    <table style={style_inner_width.clone()}
+                        id={listbox_id}
+                        role="listbox"
+                        aria-label={text!(txt, ctx.props().language, &ctx.props().title).to_string()}
+                        aria-multiselectable={(ctx.props().kind == Kind::Multi).to_string()}
+                    >
                     {
                         if let Some(search_result) = self.search_result.as_ref() {
                             html! {
                                 for search_result.iter().map(|&index| {
                                     let item = list.get(index).expect("should exist");
-                                    let check_status = if let Ok(selected) = ctx.props().selected.try_borrow() {
-                                        selected.as_ref().map_or(CheckStatus::Checked, |selected|
-                                            if selected.contains(item.id()) {
-                                                CheckStatus::Checked
-                                            } else {
-                                                CheckStatus::Unchecked
-                                            }
-                                        )
+                                    let check_status = check_status_of(index);
+                                    let label = item.value_txt(&txt, ctx.props().language);
+                                    let (item_value, limit) = if ctx.props().sized_value {
+                                        let shortened = shorten_text(&label, width, &ctx.props().font, 5);
+                                        let limit = if shortened == label {
+                                            label.chars().count()
+                                        } else {
+                                            shortened.chars().count().saturating_sub(3)
+                                        };
+                                        (shortened, limit)
                                     } else {
-                                        CheckStatus::Unchecked
+                                        let limit = label.chars().count();
+                                        (label, limit)
                                     };
-                                    let mut item_value = item.value_txt(&txt, ctx.props().language);
-                                    if  ctx.props().sized_value { item_value = shorten_text(item.value_txt(&txt, ctx.props().language).as_str(), width, &ctx.props().font, 5); }
+                                    let ranges = self.highlights.get(&index).map_or(&[][..], Vec::as_slice);
                                     html! {
-                                        <tr>
+                                        <tr id={option_id(&ctx.props().id, index)} role="option"
+                                            class={classes!(option_class(index))}
+                                            aria-selected={(check_status == CheckStatus::Checked).to_string()}
+                                        >
                                             <td class="searchable-select-list-checkbox">
                                                 <div onclick={onclick_item(item.id().clone())}>
                                                     <Checkbox status={check_status} {theme}/>
                                                 </div>
                                             </td>
                                             <td class="searchable-select-list-item">
-                                                { item_value }
+                                                { highlight(&item_value, ranges, limit) }
                                             </td>
                                         </tr>
                                     }
@@ -616,58 +1205,172 @@ where
                             }
                         } else {
                             html! {
-                                for list.iter().map(|item| {
-                                    let check_status = if let Ok(selected) = ctx.props().selected.try_borrow() {
-                                        selected.as_ref().map_or(CheckStatus::Checked, |selected|
-                                            if selected.contains(item.id()) {
-                                                CheckStatus::Checked
-                                            } else {
-                                            CheckStatus::Unchecked
-                                        })
-                                    } else {
-                                        CheckStatus::Unchecked
-                                    };
-                                    let mut item_value = item.value_txt(&txt, ctx.props().language);
-                                    if ctx.props().sized_value { item_value = shorten_text(item.value_txt(&txt, ctx.props().language).as_str(), width, &ctx.props().font, 5); }
-                                    if ctx.props().kind == Kind::Multi {
-                                        html! {
-                                            <tr>
-                                                <td class="searchable-select-list-checkbox">
-                                                    <div onclick={onclick_item(item.id().clone())}>
-                                                        <Checkbox status={check_status} {theme} />
-                                                    </div>
-                                                </td>
-                                                <td class="searchable-select-list-item">
-                                                    { item_value }
-                                                </td>
-                                            </tr>
-                                        }
-                                    } else if item.value_txt(&txt, ctx.props().language) == value {
-                                        html!{
-                                            <tr class="searchable-select-list-item-single" onclick={onclick_item(item.id().clone())}>
-                                                <td class="searchable-select-list-item-single-selected">
-                                                    { item_value }
-                                                </td>
-                                            </tr>
-                                        }
-                                    }
-                                    else {
-                                        html! {
-                                            <tr class="searchable-select-list-item-single" onclick={onclick_item(item.id().clone())}>
-                                                <td class="searchable-select-list-item-single">
-                                                    { item_value }
-                                                </td>
-                                            </tr>
-                                        }
+                                for rows.iter().map(|row| match row {
+                                    Row::Header(group) => html! {
+                                        <tr role="presentation" class="searchable-select-list-group">
+                                            <td colspan={column_count.to_string()}>
+                                                { group.to_string_txt(&txt, ctx.props().language) }
+                                            </td>
+                                        </tr>
+                                    },
+                                    &Row::Option { index, depth, has_children } => {
+                                        let item = list.get(index).expect("should exist");
+                                        let state = OptionState {
+                                            depth,
+                                            has_children,
+                                            check_status: check_status_of(index),
+                                            active: active == Some(index),
+                                        };
+                                        self.view_option(ctx, item, index, state, value, width, theme)
                                     }
                                 })
                             }
                         }
                     }
+                    { self.view_create(ctx) }
                     </table>
+                    { self.view_search_status(ctx, &style_inner_width) }
                     </div>
                 </div>
             </div>
         }
     }
+
+    #[allow(clippy::too_many_arguments)]
+    fn view_option(
+        &self,
+        ctx: &Context<Self>,
+        item: &Item,
+        index: usize,
+        state: OptionState,
+        value: &str,
+        width: u32,
+        theme: Option<Theme>,
+    ) -> Html {
+        let OptionState {
+            depth,
+            has_children,
+            check_status,
+            active,
+        } = state;
+        let txt = ctx.props().txt.txt.clone();
+        let label = item.value_txt(&txt, ctx.props().language);
+        let item_value = if ctx.props().sized_value {
+            shorten_text(&label, width, &ctx.props().font, 5)
+        } else {
+            label.clone()
+        };
+        let key = item.id().clone();
+        let onclick_item = ctx
+            .link()
+            .callback(move |_| Message::ClickItem(key.clone()));
+        let is_expanded = self.expanded_nodes.contains(item.id());
+        let toggle = if self.hierarchy.is_tree() {
+            let key = item.id().clone();
+            let onclick_toggle = ctx.link().callback(move |e: MouseEvent| {
+                e.stop_propagation();
+                Message::Toggle(key.clone())
+            });
+            let style = format!("padding-left: {}px;", depth * TREE_INDENT);
+            html! {
+                <td class="searchable-select-list-toggle" {style}>
+                if has_children {
+                    <div class={classes!("searchable-select-list-toggle-button", is_expanded.then_some("expanded"))}
+                        onclick={onclick_toggle}
+                        aria-hidden="true"
+                    />
+                }
+                </td>
+            }
+        } else {
+            html! {}
+        };
+        let option_class = active.then_some("searchable-select-list-item-active");
+        let aria_level = self.hierarchy.is_tree().then(|| (depth + 1).to_string());
+        let aria_expanded = has_children.then(|| is_expanded.to_string());
+        if ctx.props().kind == Kind::Multi {
+            html! {
+                <tr id={option_id(&ctx.props().id, index)} role="option"
+                    class={classes!(option_class)}
+                    aria-selected={(check_status == CheckStatus::Checked).to_string()}
+                    aria-level={aria_level}
+                    aria-expanded={aria_expanded}
+                >
+                    { toggle }
+                    <td class="searchable-select-list-checkbox">
+                        <div onclick={onclick_item}>
+                            <Checkbox status={check_status} {theme} />
+                        </div>
+                    </td>
+                    <td class="searchable-select-list-item">
+                        { item_value }
+                    </td>
+                </tr>
+            }
+        } else {
+            let selected = label == value;
+            let class_item = if selected {
+                "searchable-select-list-item-single-selected"
+            } else {
+                "searchable-select-list-item-single"
+            };
+            html! {
+                <tr id={option_id(&ctx.props().id, index)} role="option" aria-selected={selected.to_string()}
+                    class={classes!("searchable-select-list-item-single", option_class)}
+                    onclick={onclick_item}
+                    aria-level={aria_level}
+                    aria-expanded={aria_expanded}
+                >
+                    { toggle }
+                    <td class={class_item}>
+                        { item_value }
+                    </td>
+                </tr>
+            }
+        }
+    }
+
+    fn view_create(&self, ctx: &Context<Self>) -> Html {
+        if !self.can_create(ctx) {
+            return html! {};
+        }
+        let txt = ctx.props().txt.txt.clone();
+        let active = self.active == Some(self.visible_indices().len());
+        let onclick = ctx.link().callback(|_| Message::Create);
+        html! {
+            <tr id={Self::create_option_id(ctx)} role="option" aria-selected="false"
+                class={classes!("searchable-select-list-create", active.then_some("searchable-select-list-item-active"))}
+                {onclick}
+            >
+                <td class="searchable-select-list-create" colspan="2">
+                    { translate(&txt, ctx.props().language, "Create") }
+                    { format!(" \"{}\"", self.search_text.trim()) }
+                </td>
+            </tr>
+        }
+    }
+
+    fn view_search_status(&self, ctx: &Context<Self>, style: &str) -> Html {
+        let txt = ctx.props().txt.txt.clone();
+        let (message, class) = match &self.remote_status {
+            RemoteStatus::Loading => ("Loading...".to_string(), None),
+            RemoteStatus::Failed(error) => {
+                (error.clone(), Some("searchable-select-list-status-error"))
+            }
+            RemoteStatus::Idle => {
+                if self.search_result.as_ref().is_some_and(Vec::is_empty) && !self.can_create(ctx) {
+                    ("No results".to_string(), None)
+                } else {
+                    return html! {};
+                }
+            }
+        };
+        html! {
+            <div class={classes!("searchable-select-list-status", class)} style={style.to_string()}
+                role={if class.is_some() { "alert" } else { "status" }}
+            >
+                { translate(&txt, ctx.props().language, &message) }
+            </div>
+        }
+    }
 }